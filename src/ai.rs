@@ -0,0 +1,272 @@
+//! Computer opponent.
+//!
+//! The computer searches the full game tree with minimax, so on Hard it
+//! never loses; easier difficulties mix in random moves. Quicker wins and
+//! slower losses score higher, which makes it finish games promptly
+//! instead of toying with the opponent.
+//!
+//! The search is written against [`Game`], so it plays any variant; the
+//! classic board is searched through [`GameState`].
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+
+use crate::engine::{Board, GameState, Player, Position};
+use crate::game::{Game, Status};
+use crate::rules::RuleSet;
+
+/// The side the computer plays against a human.
+pub const COMPUTER_SIDE: Player = Player::O;
+
+/// Who plays the side opposite the human.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Opponent {
+    /// Another person at the same board.
+    #[default]
+    Human,
+    /// The minimax search.
+    Computer,
+}
+
+/// How strongly the computer plays.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Difficulty {
+    /// Plays a random empty cell.
+    Easy,
+    /// Plays the best move half the time and a random one otherwise.
+    Medium,
+    /// Always plays the best move.
+    #[default]
+    Hard,
+}
+
+impl Difficulty {
+    /// Returns the next difficulty, wrapping from Hard back to Easy.
+    pub fn next(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Medium,
+            Difficulty::Medium => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Easy,
+        }
+    }
+}
+
+/// Score of a win found at the root; deeper wins score less.
+const WIN_SCORE: i32 = 1000;
+
+/// Returns the best move for the player to move in `game`, looking at
+/// most `max_depth` moves ahead, or `None` if there is no legal move.
+/// Positions still undecided at that depth score as draws.
+///
+/// Ties are broken in favor of the first move in `legal_moves` order.
+#[instrument(level = "debug", skip(game))]
+pub fn search<G: Game>(game: &G, max_depth: u32) -> Option<G::Move> {
+    let player = game.current_player();
+    let mut best = None;
+    let mut best_score = i32::MIN;
+    for mv in game.legal_moves() {
+        let Ok(next) = game.apply(mv) else {
+            continue;
+        };
+        let score = minimax(&next, player, 1, max_depth);
+        if score > best_score {
+            best_score = score;
+            best = Some(mv);
+        }
+    }
+    best
+}
+
+/// Returns the best move for `player` under `rules`, or `None` if the
+/// board is full.
+///
+/// Expiring marks are not modelled: the search looks ahead as if every
+/// mark stayed, which keeps it finite and still plays sensibly.
+///
+/// Ties are broken in favor of the first cell in reading order.
+pub fn best_move(board: &Board, player: Player, rules: RuleSet) -> Option<Position> {
+    let rules = RuleSet {
+        expiring: false,
+        ..rules
+    };
+    search(&GameState::new(player, *board, rules), u32::MAX)
+}
+
+/// Returns how `state` stands for X with best play from both sides: near
+/// 1.0 if X can force a win, near -1.0 if O can, and 0.0 for a draw. Wins
+/// further off score a little closer to zero.
+///
+/// Expiring marks are not modelled, as in [`best_move`].
+pub fn evaluate(state: &GameState) -> f32 {
+    let mut state = *state;
+    state.set_rules(RuleSet {
+        expiring: false,
+        ..state.rules()
+    });
+    minimax(&state, Player::X, 0, u32::MAX) as f32 / WIN_SCORE as f32
+}
+
+/// Returns the computer's move for `player` at the given difficulty, or
+/// `None` if the board is full.
+pub fn choose_move(
+    board: &Board,
+    player: Player,
+    difficulty: Difficulty,
+    rules: RuleSet,
+) -> Option<Position> {
+    let mut rng = rand::thread_rng();
+    let play_best = match difficulty {
+        Difficulty::Easy => false,
+        Difficulty::Medium => rng.gen_bool(0.5),
+        Difficulty::Hard => true,
+    };
+    let choice = if play_best {
+        best_move(board, player, rules)
+    } else {
+        GameState::new(player, *board, rules)
+            .legal_moves()
+            .choose(&mut rng)
+            .copied()
+    };
+    debug!(
+        ?player,
+        ?difficulty,
+        play_best,
+        ?choice,
+        "computer chose a move"
+    );
+    choice
+}
+
+/// Scores `game` for `player`, `depth` moves below the root: positively
+/// if they are winning, negatively if they are losing.
+fn minimax<G: Game>(game: &G, player: Player, depth: u32, max_depth: u32) -> i32 {
+    let depth_score = depth.min(WIN_SCORE as u32) as i32;
+    match game.status() {
+        Status::Won(winner) if winner == player => return WIN_SCORE - depth_score,
+        Status::Won(_) => return depth_score - WIN_SCORE,
+        Status::Drawn => return 0,
+        Status::InProgress if depth >= max_depth => return 0,
+        Status::InProgress => {}
+    }
+
+    let scores = game.legal_moves().into_iter().filter_map(|mv| {
+        let next = game.apply(mv).ok()?;
+        Some(minimax(&next, player, depth + 1, max_depth))
+    });
+    // The player picks their best reply and the opponent their worst
+    let best = if game.current_player() == player {
+        scores.max()
+    } else {
+        scores.min()
+    };
+    best.unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::Cell;
+
+    /// Reads a board from rows of `X`, `O` and `.`.
+    fn board(rows: [&str; 3]) -> Board {
+        let mut board = [[Cell::Empty; 3]; 3];
+        for (row, text) in rows.iter().enumerate() {
+            for (col, symbol) in text.chars().enumerate() {
+                board[row][col] = match symbol {
+                    'X' => Cell::Player(Player::X),
+                    'O' => Cell::Player(Player::O),
+                    _ => Cell::Empty,
+                };
+            }
+        }
+        board
+    }
+
+    fn at(row: usize, col: usize) -> Position {
+        Position::new(row, col).unwrap()
+    }
+
+    #[test]
+    fn takes_a_winning_move() {
+        let board = board(["XX.", "OO.", "..."]);
+        assert_eq!(
+            best_move(&board, Player::X, RuleSet::default()),
+            Some(at(0, 2))
+        );
+    }
+
+    #[test]
+    fn blocks_the_opponents_line() {
+        let board = board(["XX.", ".O.", "..."]);
+        assert_eq!(
+            best_move(&board, Player::O, RuleSet::default()),
+            Some(at(0, 2))
+        );
+    }
+
+    #[test]
+    fn winning_comes_before_blocking() {
+        let board = board(["XX.", "OO.", "..X"]);
+        assert_eq!(
+            best_move(&board, Player::O, RuleSet::default()),
+            Some(at(1, 2))
+        );
+    }
+
+    #[test]
+    fn avoids_completing_a_line_under_misere() {
+        let rules = RuleSet {
+            misere: true,
+            ..RuleSet::default()
+        };
+        let board = board(["XX.", "OO.", "O.X"]);
+        // The top right completes X's line at once, and the middle right
+        // leaves it the last free cell
+        assert_eq!(best_move(&board, Player::X, rules), Some(at(2, 1)));
+    }
+
+    #[test]
+    fn a_full_board_has_no_move() {
+        let board = board(["XOX", "XOO", "OXX"]);
+        assert_eq!(best_move(&board, Player::X, RuleSet::default()), None);
+        assert_eq!(
+            choose_move(&board, Player::X, Difficulty::Easy, RuleSet::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn perfect_play_from_the_start_is_a_draw() {
+        let state = GameState::new(Player::X, board(["...", "...", "..."]), RuleSet::default());
+        assert_eq!(evaluate(&state), 0.0);
+    }
+
+    #[test]
+    fn a_forced_win_evaluates_for_the_winner() {
+        let state = GameState::new(Player::X, board(["XX.", "OO.", "..."]), RuleSet::default());
+        assert!(evaluate(&state) > 0.9);
+        let state = GameState::new(Player::O, board(["XX.", "OO.", "..X"]), RuleSet::default());
+        assert!(evaluate(&state) < -0.9);
+    }
+
+    #[test]
+    fn every_difficulty_plays_a_legal_move() {
+        let board = board(["X..", ".O.", "..."]);
+        for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+            let position = choose_move(&board, Player::X, difficulty, RuleSet::default()).unwrap();
+            assert_eq!(board[position.row()][position.col()], Cell::Empty);
+        }
+    }
+
+    #[test]
+    fn hard_plays_the_best_move() {
+        let board = board(["XX.", ".O.", "..."]);
+        assert_eq!(
+            choose_move(&board, Player::O, Difficulty::Hard, RuleSet::default()),
+            Some(at(0, 2))
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! Analysis: trying other moves from a replayed game against the engine.
+//!
+//! From any position of a finished game in the replay view, the player
+//! can take over the side to move and play on against the engine at full
+//! strength. Every line tried is kept as a tree of variations branching
+//! from that position, so any earlier point of any line can be returned
+//! to and a different move tried from there. Leaving analysis goes back
+//! to the game as it was played.
+
+use tic_tac_toe::ai::{self, Difficulty};
+use tic_tac_toe::game::{Game, Status};
+
+use crate::{GameState, Player, Position};
+
+/// A move in the tree of variations.
+#[derive(Clone, Copy, Debug)]
+pub struct Node {
+    /// The cell the mark landed on.
+    pub position: Position,
+    /// The player who made the move.
+    pub player: Player,
+    /// The move before this one, or `None` for a move played from the
+    /// position analysis started at.
+    pub parent: Option<usize>,
+}
+
+/// The variations tried from a position of the replayed game.
+#[derive(Clone, Debug)]
+pub struct Analysis {
+    /// The number of moves of the game before the position analysis
+    /// started at.
+    pub from_ply: usize,
+    /// The position analysis started at.
+    root: GameState,
+    /// Every move tried, each after its parent.
+    nodes: Vec<Node>,
+    /// The move leading to the position shown, or `None` for the starting
+    /// position.
+    pub current: Option<usize>,
+}
+
+impl Analysis {
+    /// Starts analysing `state`, the position after `from_ply` moves of
+    /// the game.
+    pub fn new(from_ply: usize, state: GameState) -> Self {
+        Self {
+            from_ply,
+            root: state,
+            nodes: Vec::new(),
+            current: None,
+        }
+    }
+
+    /// Returns the move at `index` in the tree.
+    pub fn node(&self, index: usize) -> Node {
+        self.nodes[index]
+    }
+
+    /// Returns the position shown.
+    pub fn state(&self) -> GameState {
+        self.state_after(self.current)
+    }
+
+    /// Whether the position shown is still being played, so it takes a
+    /// move.
+    pub fn is_playable(&self) -> bool {
+        self.state().status() == Status::InProgress
+    }
+
+    /// Plays the player's move at `position` from the position shown, then
+    /// the engine's reply, adding any move not tried before to the tree.
+    /// Returns whether the move was legal.
+    pub fn play(&mut self, position: Position) -> bool {
+        let mut state = self.state();
+        if state.status() != Status::InProgress {
+            return false;
+        }
+        let player = state.to_move();
+        let Ok(landed) = state.play(position) else {
+            return false;
+        };
+        self.current = Some(self.follow(landed, player));
+        if state.status() == Status::InProgress {
+            let reply = ai::choose_move(
+                state.board(),
+                state.to_move(),
+                Difficulty::Hard,
+                state.rules(),
+            );
+            if let Some(reply) = reply {
+                let replier = state.to_move();
+                // The engine only chooses cells it can play
+                if let Ok(landed) = state.play(reply) {
+                    self.current = Some(self.follow(landed, replier));
+                }
+            }
+        }
+        true
+    }
+
+    /// Shows the position after the move at `node`, or the starting
+    /// position for `None`.
+    pub fn select(&mut self, node: Option<usize>) {
+        self.current = node;
+    }
+
+    /// Steps back to the position before the one shown.
+    pub fn back(&mut self) {
+        self.current = self.current.and_then(|index| self.nodes[index].parent);
+    }
+
+    /// Returns every move of the tree in reading order, each after its
+    /// parent and before its parent's later variations, with how many
+    /// moves deep it is.
+    pub fn lines(&self) -> Vec<(usize, usize)> {
+        let mut lines = Vec::new();
+        self.push_lines(None, 0, &mut lines);
+        lines
+    }
+
+    fn push_lines(&self, parent: Option<usize>, depth: usize, lines: &mut Vec<(usize, usize)>) {
+        for (index, node) in self.nodes.iter().enumerate() {
+            if node.parent == parent {
+                lines.push((index, depth));
+                self.push_lines(Some(index), depth + 1, lines);
+            }
+        }
+    }
+
+    /// Returns the move from the position shown to `position` by
+    /// `player`, adding it to the tree if it has not been tried.
+    fn follow(&mut self, position: Position, player: Player) -> usize {
+        let existing = self.nodes.iter().position(|node| {
+            node.parent == self.current && node.position == position && node.player == player
+        });
+        existing.unwrap_or_else(|| {
+            self.nodes.push(Node {
+                position,
+                player,
+                parent: self.current,
+            });
+            self.nodes.len() - 1
+        })
+    }
+
+    /// Returns the position after the move at `node`, replaying its line
+    /// from the starting position.
+    fn state_after(&self, node: Option<usize>) -> GameState {
+        let mut line = Vec::new();
+        let mut next = node;
+        while let Some(index) = next {
+            line.push(self.nodes[index]);
+            next = self.nodes[index].parent;
+        }
+        let mut state = self.root;
+        for node in line.into_iter().rev() {
+            // Each move was legal when it was added
+            let _ = state.play(node.position);
+        }
+        state
+    }
+}
@@ -0,0 +1,63 @@
+//! Annotations on the moves of a game.
+//!
+//! Stepping through a finished game in the replay view, any move can be
+//! marked with a glyph judging it, as in chess: `!!` for brilliant, `!` for
+//! good, and `?` for bad, and given a text comment. Annotations are kept
+//! with the game record, so they are saved with the game and written into
+//! its notation, where the glyph follows the move and the comment comes
+//! after it in braces, such as `X:b2! {takes the centre}`.
+
+use serde::{Deserialize, Serialize};
+
+/// A judgement of a move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Glyph {
+    Brilliant,
+    Good,
+    Bad,
+}
+
+impl Glyph {
+    /// Every glyph, in the order their buttons are shown.
+    pub const ALL: [Glyph; 3] = [Glyph::Brilliant, Glyph::Good, Glyph::Bad];
+
+    /// Returns the glyph as written after a move.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Glyph::Brilliant => "!!",
+            Glyph::Good => "!",
+            Glyph::Bad => "?",
+        }
+    }
+
+    /// Splits the glyph written at the end of a move token off it,
+    /// returning the move and the glyph, if there is one.
+    pub fn split(token: &str) -> (&str, Option<Glyph>) {
+        // Longest first, so "!!" is not read as "!"
+        for glyph in [Glyph::Brilliant, Glyph::Good, Glyph::Bad] {
+            if let Some(token) = token.strip_suffix(glyph.symbol()) {
+                return (token, Some(glyph));
+            }
+        }
+        (token, None)
+    }
+}
+
+/// The annotation on one move.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    /// How the move is judged, if at all.
+    #[serde(default)]
+    pub glyph: Option<Glyph>,
+    /// A remark on the move, empty for none. Braces are left out, since
+    /// they delimit the comment in notation.
+    #[serde(default)]
+    pub comment: String,
+}
+
+impl Annotation {
+    /// Whether the annotation says nothing, and so need not be kept.
+    pub fn is_empty(&self) -> bool {
+        self.glyph.is_none() && self.comment.is_empty()
+    }
+}
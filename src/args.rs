@@ -0,0 +1,101 @@
+//! Command-line options.
+//!
+//! Options set up the window before it opens, such as playing the computer
+//! or opening a saved game, and pick the modes that run without a window:
+//! `--cli` plays in the terminal and `simulate` runs computer self-play.
+
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use tracing::level_filters::LevelFilter;
+
+use crate::bot::Bot;
+use crate::connect_k;
+use crate::game_record::GameRecord;
+use crate::simulate;
+use crate::Difficulty;
+
+/// The options the game was started with.
+#[derive(Debug, Parser)]
+#[command(
+    name = "tic-tac-toe",
+    version,
+    about = "Tic Tac Toe in a window or the terminal"
+)]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Play in the terminal instead of opening a window.
+    #[arg(long)]
+    pub cli: bool,
+    /// In the terminal, play another person instead of the computer.
+    #[arg(long, requires = "cli")]
+    pub two_player: bool,
+    /// In the terminal, how strongly the computer plays.
+    #[arg(long, value_name = "DIFFICULTY", value_parser = parse_difficulty, default_value = "hard")]
+    pub difficulty: Difficulty,
+    /// Play the computer at the given strength: easy, medium, or hard.
+    #[arg(long, value_name = "DIFFICULTY", value_parser = parse_difficulty, conflicts_with = "cli")]
+    pub vs_ai: Option<Difficulty>,
+    /// Play against the external engine at PATH.
+    #[arg(long, value_name = "PATH", conflicts_with = "cli")]
+    pub bot: Option<PathBuf>,
+    /// Open a game saved in `.ttt` notation.
+    #[arg(long, value_name = "FILE", conflicts_with = "cli")]
+    pub load: Option<PathBuf>,
+    /// Open the window full screen.
+    #[arg(long, conflicts_with = "cli")]
+    pub fullscreen: bool,
+    /// Cells along each edge of the board: 3 for the classic game, or 6 or
+    /// 15 to open Connect-K on that board.
+    #[arg(long, value_name = "N", value_parser = parse_size, conflicts_with_all = ["cli", "load"])]
+    pub size: Option<usize>,
+    /// Color theme. Only `dark` exists so far, so any other is refused.
+    #[arg(long, value_name = "THEME", conflicts_with = "cli")]
+    pub theme: Option<String>,
+    /// How much to write to the log file: off, error, warn, info, debug,
+    /// or trace.
+    #[arg(long, value_name = "LEVEL", default_value = "info")]
+    pub log_level: LevelFilter,
+}
+
+/// Modes run as subcommands.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Play computer-vs-computer games and print statistics.
+    Simulate(simulate::Options),
+}
+
+/// What the window starts with, read from the options.
+#[derive(Debug, Default)]
+pub struct Launch {
+    /// The computer's strength, from `--vs-ai`.
+    pub vs_ai: Option<Difficulty>,
+    /// The external engine playing the computer's side, from `--bot`.
+    pub bot: Option<Bot>,
+    /// The game to open, from `--load`.
+    pub game: Option<GameRecord>,
+    /// The board's size, from `--size`.
+    pub size: Option<usize>,
+}
+
+/// The only theme the window has.
+pub const THEME: &str = "dark";
+
+fn parse_size(text: &str) -> Result<usize, String> {
+    match text.parse() {
+        Ok(size) if size == 3 || connect_k::SIZES.iter().any(|offered| offered.cells == size) => {
+            Ok(size)
+        }
+        _ => Err("expected 3, or 6 or 15 for Connect-K".to_string()),
+    }
+}
+
+fn parse_difficulty(name: &str) -> Result<Difficulty, String> {
+    match name {
+        "easy" => Ok(Difficulty::Easy),
+        "medium" => Ok(Difficulty::Medium),
+        "hard" => Ok(Difficulty::Hard),
+        _ => Err("expected easy, medium, or hard".to_string()),
+    }
+}
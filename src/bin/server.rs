@@ -0,0 +1,815 @@
+//! Relay server for online play.
+//!
+//! Players connect over WebSockets. One creates a room under a code and is
+//! seated as X; the other joins it by that code and is seated as O. A
+//! third player is turned away, as is a code that is taken or unknown.
+//! The relay passes game messages between the two, checking each move
+//! against its own copy of the board so a client cannot play out of turn
+//! or onto a taken cell.
+//!
+//! Anyone may also watch a room by its code. Spectators are sent the game
+//! so far and then every move and chat line as it is sent, but nothing
+//! they send reaches the players, who are only told how many are
+//! watching.
+//!
+//! A player whose connection drops may take their seat back by rejoining
+//! as the same side, and is sent the game so far to pick up from. A room
+//! whose players have both left is kept for [`RECONNECT_WINDOW`] if a
+//! game was under way, and removed at once otherwise. A room with no
+//! messages for [`ROOM_IDLE_LIMIT`] is closed along with its connections.
+//!
+//! The relay also hosts replays. Instead of entering a room, a client may
+//! upload a game in `.ttt` notation, which is stored as a file under a new
+//! random id, or fetch the game stored under an id. Ids are checked before
+//! they name a file, so a fetch cannot read anything else.
+//!
+//! A seated player may also ask for the room's games to be recorded. Each
+//! game that then finishes is stored the same way, as JSON, and its id is
+//! sent to the players who asked, so they can fetch it again later.
+//!
+//! Run it with an optional address to listen on and directory to keep
+//! replays in:
+//!
+//! ```text
+//! cargo run --bin server -- 0.0.0.0:9001 replays
+//! ```
+//!
+//! The relay speaks the game's own [`protocol`] and checks moves with its
+//! [`engine`], so the two cannot drift apart. A client must first say
+//! which protocol version it speaks, and one speaking another is told the
+//! relay's version and disconnected.
+//!
+//! What the relay does is traced to standard error, each connection and
+//! room in its own span so one game can be followed through the log.
+//!
+//! [`protocol`]: tic_tac_toe::protocol
+//! [`engine`]: tic_tac_toe::engine
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use tic_tac_toe::engine::{Board, GameState, MoveError, Player, Position};
+use tic_tac_toe::protocol::{
+    ClientMessage, Message, RecordedGame, ServerMessage, PROTOCOL_VERSION,
+};
+use tic_tac_toe::rules::RuleSet;
+use tracing::{info, info_span, warn, Level};
+use tungstenite::{Message as Frame, WebSocket};
+
+/// Address listened on when none is given.
+const DEFAULT_ADDRESS: &str = "0.0.0.0:9001";
+
+/// How long a room may go without a message before it is closed.
+const ROOM_IDLE_LIMIT: Duration = Duration::from_secs(30 * 60);
+
+/// How long an empty room with a game under way waits for its players to
+/// come back.
+const RECONNECT_WINDOW: Duration = Duration::from_secs(2 * 60);
+
+/// How often idle rooms are looked for.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often each connection checks for messages in both directions.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Longest chat line passed on, in characters.
+const MAX_CHAT_LEN: usize = 200;
+
+/// Directory replays are kept in when none is given.
+const DEFAULT_REPLAY_DIR: &str = "replays";
+
+/// Longest replay stored, in bytes.
+const MAX_REPLAY_LEN: usize = 16 * 1024;
+
+/// Number of characters in a replay id.
+const REPLAY_ID_LEN: usize = 8;
+
+/// Characters replay ids are made of, as for room codes.
+const REPLAY_ID_CHARS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+/// Extension of uploaded replays, which are in `.ttt` notation.
+const REPLAY_EXTENSION: &str = "ttt";
+
+/// Extension of recorded games, which are JSON.
+const RECORDING_EXTENSION: &str = "json";
+
+/// How a connection asked to enter a room.
+#[derive(Clone, Copy, Debug)]
+enum Entry {
+    Create,
+    Join,
+    Watch,
+    /// Take back the seat of `side` after a dropped connection.
+    Rejoin(Player),
+}
+
+/// Who a connection is in its room.
+#[derive(Clone, Copy, Debug)]
+enum Role {
+    Player(Player),
+    /// A spectator, told apart from others by a number unique to the
+    /// relay.
+    Spectator(u64),
+}
+
+/// Numbers handed out to spectators.
+static NEXT_SPECTATOR: AtomicU64 = AtomicU64::new(0);
+
+/// The relay's copy of a room's game, for checking moves.
+#[derive(Default)]
+struct Game {
+    /// The game being played, once one has started.
+    state: Option<GameState>,
+    moves: Vec<(usize, usize, Player)>,
+    /// Who moved first and from which board, once a game has started.
+    opening: Option<(Player, Board)>,
+    /// The resignation or accepted draw that ended the game away from the
+    /// board.
+    ending: Option<Message>,
+    /// Who offered the draw awaiting an answer, if anyone.
+    draw_offer: Option<Player>,
+    /// Who asked to take back a move and how many moves the game held
+    /// then, while the request awaits an answer.
+    takeback: Option<(Player, usize)>,
+}
+
+impl Game {
+    /// Starts a game from `board` with `first` to move.
+    fn start(&mut self, first: Player, board: Board, rules: RuleSet) {
+        *self = Game {
+            state: Some(GameState::new(first, board, rules)),
+            opening: Some((first, board)),
+            ..Game::default()
+        };
+    }
+
+    fn rules(&self) -> RuleSet {
+        self.state.map(|state| state.rules()).unwrap_or_default()
+    }
+
+    /// Whether a game has started and not yet ended.
+    fn in_progress(&self) -> bool {
+        self.ending.is_none() && self.state.is_some_and(|state| !state.outcome().is_over())
+    }
+
+    /// Returns the messages that bring a newly arrived spectator up to the
+    /// current position.
+    fn replay(&self) -> Vec<Message> {
+        let Some((first, board)) = self.opening else {
+            return Vec::new();
+        };
+        let start = Message::Start {
+            first,
+            board,
+            rules: self.rules(),
+        };
+        std::iter::once(start)
+            .chain(
+                self.moves
+                    .iter()
+                    .map(|&(row, col, _)| Message::Move { row, col }),
+            )
+            .chain(self.ending.clone())
+            .collect()
+    }
+
+    /// Returns the game for recording, or `None` if no game has started.
+    fn record(&self) -> Option<RecordedGame> {
+        let (first, board) = self.opening?;
+        Some(RecordedGame {
+            first,
+            board,
+            rules: self.rules(),
+            moves: self.moves.clone(),
+            ending: self.ending.clone(),
+        })
+    }
+
+    /// Returns the game so far for a player taking their seat back, or
+    /// `None` if no game has started.
+    fn resync(&self) -> Option<ServerMessage> {
+        let (first, board) = self.opening?;
+        Some(ServerMessage::Resync {
+            first,
+            board,
+            rules: self.rules(),
+            moves: self.moves.clone(),
+            ending: self.ending.clone(),
+        })
+    }
+
+    /// Takes back the last move if the game holds `moves` moves, by
+    /// replaying the rest from the opening so expired marks come back.
+    fn take_back(&mut self, moves: usize) {
+        let Some((first, board)) = self.opening else {
+            return;
+        };
+        if moves == 0 || self.moves.len() != moves || self.ending.is_some() {
+            return;
+        }
+        let mut kept = std::mem::take(&mut self.moves);
+        kept.pop();
+        self.start(first, board, self.rules());
+        for (row, col, player) in kept {
+            let _ = self.play(player, row, col);
+        }
+    }
+
+    /// Plays a move by `player`, or says why it is not allowed.
+    fn play(&mut self, player: Player, row: usize, col: usize) -> Result<(), &'static str> {
+        let in_progress = self.in_progress();
+        let Some(state) = self
+            .state
+            .as_mut()
+            .filter(|state| state.to_move() == player)
+        else {
+            return Err(MoveError::NotYourTurn.reason());
+        };
+        if !in_progress {
+            return Err(MoveError::GameOver.reason());
+        }
+        let position = Position::new(row, col).ok_or(MoveError::OutOfBounds.reason())?;
+        let landed = state.play(position).map_err(MoveError::reason)?;
+        self.moves.push((landed.row(), landed.col(), player));
+        self.draw_offer = None;
+        self.takeback = None;
+        Ok(())
+    }
+}
+
+/// A room and the players seated in it.
+#[derive(Default)]
+struct Room {
+    /// Where to send messages for each seated player, indexed by
+    /// [`Player::index`].
+    seats: [Option<Sender<ServerMessage>>; 2],
+    /// Where to send messages for each spectator, by their number.
+    spectators: HashMap<u64, Sender<ServerMessage>>,
+    game: Game,
+    last_active: Option<Instant>,
+    /// Whether each seated player asked for finished games to be recorded,
+    /// indexed by [`Player::index`].
+    recording: [bool; 2],
+}
+
+impl Room {
+    /// Sends `message` to the player seated at `side`, if anyone is.
+    fn send(&self, side: Player, message: ServerMessage) {
+        if let Some(seat) = &self.seats[side.index()] {
+            let _ = seat.send(message);
+        }
+    }
+
+    /// Sends `message` to every spectator.
+    fn send_spectators(&self, message: ServerMessage) {
+        for spectator in self.spectators.values() {
+            let _ = spectator.send(message.clone());
+        }
+    }
+
+    /// Whether both seats are taken.
+    fn is_full(&self) -> bool {
+        self.seats.iter().all(Option::is_some)
+    }
+
+    /// Stores the game that just finished, if a player asked for it, and
+    /// sends those who did the id it is kept under.
+    fn record(&self, replays: &Replays) {
+        if !self.recording.contains(&true) {
+            return;
+        }
+        let Some(game) = self.game.record() else {
+            return;
+        };
+        let stored = serde_json::to_string(&game)
+            .map_err(io::Error::from)
+            .and_then(|json| replays.store(&json, RECORDING_EXTENSION));
+        match stored {
+            Ok(id) => {
+                info!(%id, "recorded game");
+                for side in [Player::X, Player::O] {
+                    if self.recording[side.index()] {
+                        self.send(side, ServerMessage::Recorded { id: id.clone() });
+                    }
+                }
+            }
+            Err(err) => warn!(%err, "could not record game"),
+        }
+    }
+
+    /// Tells the players how many spectators are watching.
+    fn announce_spectators(&self) {
+        let count = self.spectators.len();
+        for side in [Player::X, Player::O] {
+            self.send(side, ServerMessage::Spectators { count });
+        }
+    }
+}
+
+type Rooms = Arc<Mutex<HashMap<String, Room>>>;
+
+/// Games stored for replaying, a file each named by its id: uploaded games
+/// in `.ttt` notation and recorded ones as JSON.
+struct Replays {
+    dir: PathBuf,
+}
+
+impl Replays {
+    /// Stores `contents` in a file with `extension` under a new id and
+    /// returns the id.
+    fn store(&self, contents: &str, extension: &str) -> io::Result<String> {
+        fs::create_dir_all(&self.dir)?;
+        let mut rng = rand::thread_rng();
+        loop {
+            let id: String = (0..REPLAY_ID_LEN)
+                .map(|_| char::from(REPLAY_ID_CHARS[rng.gen_range(0..REPLAY_ID_CHARS.len())]))
+                .collect();
+            // Creating the file only if it is new keeps ids unique
+            match OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(self.path(&id, extension))
+            {
+                Ok(mut file) => {
+                    file.write_all(contents.as_bytes())?;
+                    return Ok(id);
+                }
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Returns what is stored with `extension` under `id`, or `None` if
+    /// there is nothing.
+    fn load(&self, id: &str, extension: &str) -> Option<String> {
+        let valid =
+            id.len() == REPLAY_ID_LEN && id.bytes().all(|symbol| REPLAY_ID_CHARS.contains(&symbol));
+        if !valid {
+            return None;
+        }
+        fs::read_to_string(self.path(id, extension)).ok()
+    }
+
+    fn path(&self, id: &str, extension: &str) -> PathBuf {
+        self.dir.join(format!("{id}.{extension}"))
+    }
+}
+
+fn main() -> io::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let address = args.next().unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+    let replays = Arc::new(Replays {
+        dir: args
+            .next()
+            .unwrap_or_else(|| DEFAULT_REPLAY_DIR.to_string())
+            .into(),
+    });
+    tracing_subscriber::fmt()
+        .with_writer(io::stderr)
+        .with_max_level(Level::INFO)
+        .init();
+    let listener = TcpListener::bind(&address)?;
+    info!(%address, "relay listening");
+
+    let rooms = Rooms::default();
+    thread::spawn({
+        let rooms = rooms.clone();
+        move || sweep_idle_rooms(&rooms)
+    });
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let rooms = rooms.clone();
+        let replays = replays.clone();
+        let span = match stream.peer_addr() {
+            Ok(peer) => info_span!("connection", %peer),
+            Err(_) => info_span!("connection"),
+        };
+        thread::spawn(move || {
+            let _span = span.entered();
+            match serve(stream, &rooms, &replays) {
+                Ok(()) => info!("connection closed"),
+                Err(err) => info!(%err, "connection closed"),
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Closes rooms that have been quiet for too long. Dropping a room drops
+/// the senders its players' connections listen on, which ends them.
+fn sweep_idle_rooms(rooms: &Rooms) {
+    loop {
+        thread::sleep(SWEEP_INTERVAL);
+        let mut rooms = rooms.lock().unwrap();
+        rooms.retain(|code, room| {
+            let limit = if room.seats.iter().all(Option::is_none) {
+                RECONNECT_WINDOW
+            } else {
+                ROOM_IDLE_LIMIT
+            };
+            let idle = room.last_active.is_some_and(|last| last.elapsed() > limit);
+            if idle {
+                info!(room = %code, "closing idle room");
+            }
+            !idle
+        });
+    }
+}
+
+/// Runs one player's connection from the handshake until they leave, or
+/// answers a replay upload or fetch.
+fn serve(stream: TcpStream, rooms: &Rooms, replays: &Replays) -> io::Result<()> {
+    let mut socket =
+        tungstenite::accept(stream).map_err(|err| io::Error::other(err.to_string()))?;
+
+    // The first message must give the protocol version, since a client
+    // speaking another would misread what follows
+    loop {
+        match read(&mut socket)? {
+            Some(ClientMessage::Hello { version }) if version == PROTOCOL_VERSION => break,
+            Some(ClientMessage::Hello { version }) => {
+                warn!(version, "client speaks another protocol version");
+                let version = PROTOCOL_VERSION;
+                return send(&mut socket, &ServerMessage::Incompatible { version });
+            }
+            Some(_) => {
+                let reason = "this relay needs a newer version of the game".to_string();
+                return send(&mut socket, &ServerMessage::Rejected { reason });
+            }
+            None => {}
+        }
+    }
+
+    // The next must create, join, or watch a room, unless it is about a
+    // replay
+    let (room_code, entry) = loop {
+        match read(&mut socket)? {
+            Some(ClientMessage::UploadReplay { notation }) => {
+                return upload_replay(&mut socket, replays, &notation);
+            }
+            Some(ClientMessage::FetchReplay { id }) => {
+                return fetch_replay(&mut socket, replays, &id);
+            }
+            Some(ClientMessage::FetchRecording { id }) => {
+                return fetch_recording(&mut socket, replays, &id);
+            }
+            Some(ClientMessage::Create { room }) if !room.is_empty() => {
+                break (room, Entry::Create)
+            }
+            Some(ClientMessage::Join { room }) if !room.is_empty() => break (room, Entry::Join),
+            Some(ClientMessage::Watch { room }) if !room.is_empty() => break (room, Entry::Watch),
+            Some(ClientMessage::Rejoin { room, side }) if !room.is_empty() => {
+                break (room, Entry::Rejoin(side))
+            }
+            Some(_) => {
+                send(
+                    &mut socket,
+                    &ServerMessage::Rejected {
+                        reason: "join a room first".to_string(),
+                    },
+                )?;
+            }
+            None => {}
+        }
+    };
+    let (outgoing, incoming) = mpsc::channel();
+    let role = match enter(rooms, &room_code, entry, outgoing) {
+        Ok(role) => role,
+        Err(reason) => {
+            warn!(room = %room_code, ?entry, reason, "could not enter room");
+            let reason = reason.to_string();
+            return send(&mut socket, &ServerMessage::Rejected { reason });
+        }
+    };
+    let _span = info_span!("room", code = %room_code, ?role).entered();
+    info!("joined room");
+
+    socket.get_mut().set_nonblocking(true)?;
+    let result = relay(&mut socket, rooms, replays, &room_code, role, &incoming);
+    leave(rooms, &room_code, role);
+    info!("left room");
+    result
+}
+
+/// Stores an uploaded replay and answers with its id.
+fn upload_replay(
+    socket: &mut WebSocket<TcpStream>,
+    replays: &Replays,
+    notation: &str,
+) -> io::Result<()> {
+    if notation.len() > MAX_REPLAY_LEN {
+        let reason = "that replay is too long".to_string();
+        return send(socket, &ServerMessage::Rejected { reason });
+    }
+    match replays.store(notation, REPLAY_EXTENSION) {
+        Ok(id) => {
+            info!(%id, "stored replay");
+            send(socket, &ServerMessage::ReplayStored { id })
+        }
+        Err(err) => {
+            warn!(%err, "could not store replay");
+            let reason = "the replay could not be stored".to_string();
+            send(socket, &ServerMessage::Rejected { reason })
+        }
+    }
+}
+
+/// Answers with the replay stored under `id`.
+fn fetch_replay(socket: &mut WebSocket<TcpStream>, replays: &Replays, id: &str) -> io::Result<()> {
+    match replays.load(id, REPLAY_EXTENSION) {
+        Some(notation) => send(socket, &ServerMessage::Replay { notation }),
+        None => {
+            let reason = "no replay has that id".to_string();
+            send(socket, &ServerMessage::Rejected { reason })
+        }
+    }
+}
+
+/// Answers with the game recorded under `id`.
+fn fetch_recording(
+    socket: &mut WebSocket<TcpStream>,
+    replays: &Replays,
+    id: &str,
+) -> io::Result<()> {
+    let game = replays
+        .load(id, RECORDING_EXTENSION)
+        .and_then(|json| serde_json::from_str(&json).ok());
+    match game {
+        Some(game) => send(socket, &ServerMessage::Recording(game)),
+        None => {
+            let reason = "no recorded game has that id".to_string();
+            send(socket, &ServerMessage::Rejected { reason })
+        }
+    }
+}
+
+/// Enters `room_code` as `entry` asks, first creating the room for
+/// [`Entry::Create`], and returns the connection's role or why it cannot
+/// enter.
+fn enter(
+    rooms: &Rooms,
+    room_code: &str,
+    entry: Entry,
+    outgoing: Sender<ServerMessage>,
+) -> Result<Role, &'static str> {
+    let mut rooms = rooms.lock().unwrap();
+    let room = match (entry, rooms.contains_key(room_code)) {
+        (Entry::Create, true) => return Err("that room code is taken"),
+        (Entry::Create, false) | (_, true) => rooms.entry(room_code.to_string()).or_default(),
+        (_, false) => return Err("no room has that code"),
+    };
+
+    if let Entry::Watch = entry {
+        let id = NEXT_SPECTATOR.fetch_add(1, Ordering::Relaxed);
+        // Spectators hear about the players where a player hears about
+        // their opponent, so "present" means both seats are taken
+        let _ = outgoing.send(ServerMessage::Watching {
+            players_present: room.is_full(),
+        });
+        for message in room.game.replay() {
+            let _ = outgoing.send(ServerMessage::Game(message));
+        }
+        room.spectators.insert(id, outgoing);
+        room.announce_spectators();
+        return Ok(Role::Spectator(id));
+    }
+
+    let side = match entry {
+        Entry::Rejoin(side) if room.seats[side.index()].is_some() => {
+            return Err("that seat has been taken");
+        }
+        Entry::Rejoin(side) => side,
+        _ => [Player::X, Player::O]
+            .into_iter()
+            .find(|side| room.seats[side.index()].is_none())
+            .ok_or("that room is full")?,
+    };
+    let opponent_present = room.seats[side.other().index()].is_some();
+    let _ = outgoing.send(ServerMessage::Seated {
+        side,
+        opponent_present,
+    });
+    let rejoined = matches!(entry, Entry::Rejoin(_));
+    if rejoined {
+        if let Some(resync) = room.game.resync() {
+            let _ = outgoing.send(resync);
+        }
+    }
+    room.seats[side.index()] = Some(outgoing);
+    room.last_active = Some(Instant::now());
+    // A returning player carries on the game, while a new one is started
+    // afresh by X
+    room.send(
+        side.other(),
+        if rejoined {
+            ServerMessage::OpponentReturned
+        } else {
+            ServerMessage::OpponentJoined
+        },
+    );
+    if room.is_full() {
+        room.send_spectators(ServerMessage::OpponentJoined);
+    }
+    room.announce_spectators();
+    Ok(Role::Player(side))
+}
+
+/// Frees a player's seat, telling the others in the room, and once no
+/// player is left removes the room unless a game is under way for them to
+/// come back to. Spectators leave quietly apart from the count the players
+/// see.
+fn leave(rooms: &Rooms, room_code: &str, role: Role) {
+    let mut rooms = rooms.lock().unwrap();
+    let Some(room) = rooms.get_mut(room_code) else {
+        return;
+    };
+    let side = match role {
+        Role::Player(side) => side,
+        Role::Spectator(id) => {
+            room.spectators.remove(&id);
+            room.announce_spectators();
+            return;
+        }
+    };
+    // The game stays for the player to pick up if they come back
+    room.seats[side.index()] = None;
+    room.recording[side.index()] = false;
+    room.last_active = Some(Instant::now());
+    room.send(side.other(), ServerMessage::OpponentLeft);
+    room.send_spectators(ServerMessage::OpponentLeft);
+    if room.seats.iter().all(Option::is_none) && room.game.opening.is_none() {
+        rooms.remove(room_code);
+    }
+}
+
+/// Passes messages between a connection and its room until either side
+/// closes, recording each game that finishes if a player asked for it.
+fn relay(
+    socket: &mut WebSocket<TcpStream>,
+    rooms: &Rooms,
+    replays: &Replays,
+    room_code: &str,
+    role: Role,
+    incoming: &Receiver<ServerMessage>,
+) -> io::Result<()> {
+    loop {
+        loop {
+            match incoming.try_recv() {
+                Ok(message) => send(socket, &message)?,
+                Err(TryRecvError::Empty) => break,
+                // The room was closed
+                Err(TryRecvError::Disconnected) => return Ok(()),
+            }
+        }
+        while let Some(message) = read(socket)? {
+            // Spectators only listen
+            let Role::Player(side) = role else {
+                continue;
+            };
+            let mut rooms = rooms.lock().unwrap();
+            let Some(room) = rooms.get_mut(room_code) else {
+                return Ok(());
+            };
+            let message = match message {
+                ClientMessage::Game(message) => message,
+                ClientMessage::Record => {
+                    room.recording[side.index()] = true;
+                    continue;
+                }
+                _ => continue,
+            };
+            room.last_active = Some(Instant::now());
+            let was_in_progress = room.game.in_progress();
+            match check(&mut room.game, side, &message) {
+                Ok(()) => {
+                    // Spectators see moves and their outcome, not requests
+                    if !matches!(
+                        message,
+                        Message::NewGame
+                            | Message::TakebackRequest { .. }
+                            | Message::DrawOffer
+                            | Message::Rematch
+                    ) {
+                        room.send_spectators(ServerMessage::Game(message.clone()));
+                    }
+                    room.send(side.other(), ServerMessage::Game(message));
+                    if was_in_progress && !room.game.in_progress() {
+                        room.record(replays);
+                    }
+                }
+                Err(reason) => {
+                    warn!(?message, reason, "refused message");
+                    room.send(
+                        side,
+                        ServerMessage::Rejected {
+                            reason: reason.to_string(),
+                        },
+                    );
+                }
+            }
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Checks a game message from `side` against the room's game, applying it
+/// if it is allowed.
+fn check(game: &mut Game, side: Player, message: &Message) -> Result<(), &'static str> {
+    match *message {
+        Message::Start {
+            first,
+            board,
+            rules,
+        } => {
+            if side != Player::X {
+                return Err("only X starts games");
+            }
+            game.start(first, board, rules);
+            Ok(())
+        }
+        Message::Move { row, col } => game.play(side, row, col),
+        Message::Hello { .. } => Err("the protocol version was already given"),
+        Message::NewGame if side == Player::O => Ok(()),
+        Message::NewGame => Err("only O asks for new games"),
+        Message::Chat { from, .. } if from != side => Err("chat must come from your own side"),
+        Message::Chat { ref text, .. } if text.chars().count() > MAX_CHAT_LEN => {
+            Err("that chat message is too long")
+        }
+        Message::Chat { .. } => Ok(()),
+        Message::TakebackRequest { moves } => {
+            game.takeback = Some((side, moves));
+            Ok(())
+        }
+        Message::TakebackAnswer { moves, .. } if game.takeback != Some((side.other(), moves)) => {
+            Err("there is no takeback request to answer")
+        }
+        Message::TakebackAnswer { moves, accepted } => {
+            game.takeback = None;
+            if accepted {
+                game.take_back(moves);
+            }
+            Ok(())
+        }
+        Message::Resign { player } if player != side => Err("you can only resign your own side"),
+        Message::DrawOffer | Message::DrawAnswer { .. } | Message::Resign { .. }
+            if !game.in_progress() =>
+        {
+            Err("the game is already over")
+        }
+        Message::DrawAnswer { .. } if game.draw_offer != Some(side.other()) => {
+            Err("there is no draw offer to answer")
+        }
+        Message::DrawOffer => {
+            game.draw_offer = Some(side);
+            Ok(())
+        }
+        Message::DrawAnswer { accepted: false } => {
+            game.draw_offer = None;
+            Ok(())
+        }
+        // X follows an agreed rematch with a new Start
+        Message::Rematch => Ok(()),
+        Message::Resign { .. } | Message::DrawAnswer { accepted: true } => {
+            game.ending = Some(message.clone());
+            Ok(())
+        }
+    }
+}
+
+/// Sends a message to the player, waiting out a full socket.
+fn send(socket: &mut WebSocket<TcpStream>, message: &ServerMessage) -> io::Result<()> {
+    let json = serde_json::to_string(message)?;
+    let mut result = socket.send(Frame::text(json));
+    while let Err(tungstenite::Error::Io(err)) = &result {
+        if err.kind() != io::ErrorKind::WouldBlock {
+            break;
+        }
+        thread::sleep(POLL_INTERVAL);
+        result = socket.flush();
+    }
+    result.map_err(io::Error::other)
+}
+
+/// Reads the next message from the player, or `None` if nothing has
+/// arrived yet or the frame was not part of the protocol.
+fn read(socket: &mut WebSocket<TcpStream>) -> io::Result<Option<ClientMessage>> {
+    match socket.read() {
+        Ok(Frame::Text(text)) => Ok(serde_json::from_str(&text).ok()),
+        Ok(Frame::Close(_)) => Err(io::ErrorKind::UnexpectedEof.into()),
+        Ok(_) => Ok(None),
+        Err(tungstenite::Error::Io(err)) if err.kind() == io::ErrorKind::WouldBlock => Ok(None),
+        Err(err) => Err(io::Error::other(err)),
+    }
+}
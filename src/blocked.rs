@@ -0,0 +1,37 @@
+//! Blocked cells variant.
+//!
+//! A game starts with one to three random cells blocked off, so no mark
+//! can be placed on them. The layout is derived from a seed that can be
+//! copied and pasted, letting both players try the same layout.
+
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use rand::{Rng, SeedableRng};
+
+use crate::{Board, Cell};
+
+/// Fewest cells a layout blocks.
+const MIN_BLOCKED: usize = 1;
+/// Most cells a layout blocks.
+const MAX_BLOCKED: usize = 3;
+
+/// Returns an empty board with the cells picked by `seed` blocked.
+pub fn layout(seed: u64) -> Board {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let count = rng.gen_range(MIN_BLOCKED..=MAX_BLOCKED);
+    let mut board = [[Cell::Empty; 3]; 3];
+    for index in sample(&mut rng, 9, count) {
+        board[index / 3][index % 3] = Cell::Blocked;
+    }
+    board
+}
+
+/// Formats a layout seed for sharing.
+pub fn format_seed(seed: u64) -> String {
+    format!("{seed:016x}")
+}
+
+/// Reads a shared layout seed, or `None` if `text` is not one.
+pub fn parse_seed(text: &str) -> Option<u64> {
+    u64::from_str_radix(text.trim(), 16).ok()
+}
@@ -0,0 +1,89 @@
+//! Playing against an external engine.
+//!
+//! `--bot <path>` starts the program at `path` and has it play the
+//! computer's side in place of the built-in search, so bots can be written
+//! in any language. The window and the bot talk over the bot's standard
+//! input and output, one command per line:
+//!
+//! - `position <cells> <side>` gives the board as nine cells in reading
+//!   order, `X`, `O`, `.` for empty and `#` for blocked, then the side to
+//!   move, as in `position X...O.... X`.
+//! - `go` asks for a move, which the bot answers with `bestmove <cell>`,
+//!   such as `bestmove b2`. Any other lines it prints first are ignored.
+//! - `quit` is sent when the window closes.
+//!
+//! The bot's moves are checked like anyone else's, and one it should not
+//! have played is refused.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use crate::notation::{self, format_board};
+use crate::{Board, Player, Position};
+
+/// A running bot process.
+#[derive(Debug)]
+pub struct Bot {
+    child: Child,
+    input: ChildStdin,
+    output: BufReader<ChildStdout>,
+}
+
+impl Bot {
+    /// Starts the bot at `path`.
+    pub fn start(path: &Path) -> io::Result<Self> {
+        let mut child = Command::new(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|err| {
+                io::Error::new(
+                    err.kind(),
+                    format!("could not start {}: {err}", path.display()),
+                )
+            })?;
+        let input = child.stdin.take().expect("the bot's input is piped");
+        let output = BufReader::new(child.stdout.take().expect("the bot's output is piped"));
+        Ok(Self {
+            child,
+            input,
+            output,
+        })
+    }
+
+    /// Sends the bot the position and waits for the move it plays.
+    pub fn best_move(&mut self, board: &Board, to_move: Player) -> io::Result<Position> {
+        writeln!(self.input, "position {} {to_move:?}", format_board(board))?;
+        writeln!(self.input, "go")?;
+        self.input.flush()?;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.output.read_line(&mut line)? == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "the bot has exited",
+                ));
+            }
+            if let Some(cell) = line.trim().strip_prefix("bestmove ") {
+                return notation::parse_cell(cell.trim()).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("{} is not a cell", cell.trim()),
+                    )
+                });
+            }
+        }
+    }
+}
+
+impl Drop for Bot {
+    fn drop(&mut self) {
+        let _ = writeln!(self.input, "quit");
+        let _ = self.input.flush();
+        // A bot that ignores `quit` is stopped rather than left running
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
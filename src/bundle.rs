@@ -0,0 +1,116 @@
+//! Data bundle: everything the player has saved, in one file.
+//!
+//! Exporting gathers the settings, player profiles, game history
+//! database, and best scores into a single JSON file, each file's bytes
+//! written as base64 under its name. Importing writes them back over the
+//! saved ones, so a setup can be backed up or carried to another machine.
+//! The window's placement and the autosaved game are left out, since they
+//! belong to the machine and the moment rather than to the player.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::{daily, history, profiles, puzzle, settings, speedrun};
+
+/// File name suggested when exporting the bundle.
+pub const DEFAULT_FILE_NAME: &str = "tic-tac-toe-data.json";
+
+/// Version of the bundle's layout, raised whenever it changes.
+const FORMAT: u32 = 1;
+
+/// Returns each file a bundle carries, by its name in the bundle, with
+/// where it is saved.
+fn files() -> [(&'static str, Option<PathBuf>); 6] {
+    [
+        ("settings.toml", settings::settings_path()),
+        ("profiles.json", profiles::profiles_path()),
+        ("history.sqlite3", history::history_path()),
+        ("speedrun.json", speedrun::bests_path()),
+        ("dailies.json", daily::dailies_path()),
+        ("puzzle_rush.json", puzzle::scores_path()),
+    ]
+}
+
+/// The player's saved files, packed together.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Bundle {
+    /// The layout the bundle was written in.
+    format: u32,
+    /// Each file's bytes as base64, by its name in the bundle.
+    files: BTreeMap<String, String>,
+}
+
+impl Bundle {
+    /// Gathers the saved files, leaving out any not written yet.
+    pub fn collect() -> io::Result<Self> {
+        let mut files = BTreeMap::new();
+        for (name, path) in files() {
+            let Some(path) = path else {
+                continue;
+            };
+            match fs::read(&path) {
+                Ok(bytes) => {
+                    files.insert(name.to_string(), STANDARD.encode(bytes));
+                }
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(Self {
+            format: FORMAT,
+            files,
+        })
+    }
+
+    /// Writes the bundle to `path`.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json)
+    }
+
+    /// Reads a bundle from `path`, refusing one written in a layout this
+    /// version does not know.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        let bundle: Self = serde_json::from_str(&json)?;
+        if bundle.format != FORMAT {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsupported data bundle format {}", bundle.format),
+            ));
+        }
+        Ok(bundle)
+    }
+
+    /// Writes the bundle's files over the saved ones, returning how many
+    /// were restored. Names it does not carry are left as they are, and
+    /// names this version does not know are ignored.
+    ///
+    /// Every file is decoded before any is written, so a damaged bundle
+    /// changes nothing.
+    pub fn restore(&self) -> io::Result<usize> {
+        let mut decoded = Vec::new();
+        for (name, path) in files() {
+            let (Some(encoded), Some(path)) = (self.files.get(name), path) else {
+                continue;
+            };
+            let bytes = STANDARD.decode(encoded).map_err(|err| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("{name}: {err}"))
+            })?;
+            decoded.push((path, bytes));
+        }
+        for (path, bytes) in &decoded {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(path, bytes)?;
+        }
+        Ok(decoded.len())
+    }
+}
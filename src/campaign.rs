@@ -0,0 +1,143 @@
+//! Campaign: a ladder of themed computer opponents.
+//!
+//! Each level pits the player, as X, against a named opponent at a set
+//! difficulty with a twist to the rules. A level is cleared by not losing
+//! and earns up to three stars: three for a quick win, two for any other
+//! win, and one for a draw. Clearing a level opens the next. The best
+//! stars for each level are saved with the profile seated at X.
+
+use tic_tac_toe::ai::Difficulty;
+
+use crate::{RuleSet, Topology};
+
+/// Most of their own moves the player may take to win a level with three
+/// stars.
+const QUICK_WIN_MOVES: usize = 4;
+
+/// The rules without any twist.
+const STANDARD: RuleSet = RuleSet {
+    misere: false,
+    gravity: false,
+    expiring: false,
+    topology: Topology::Flat,
+};
+
+/// A level of the campaign.
+#[derive(Clone, Copy, Debug)]
+pub struct Level {
+    /// The opponent's name.
+    pub opponent: &'static str,
+    /// How strongly the opponent plays.
+    pub difficulty: Difficulty,
+    /// The rules the level is played under.
+    pub rules: RuleSet,
+    /// The rules' twist, as shown on the level list.
+    pub twist: &'static str,
+}
+
+/// The levels, in the order they open.
+pub const LEVELS: [Level; 6] = [
+    Level {
+        opponent: "Rookie Rosie",
+        difficulty: Difficulty::Easy,
+        rules: STANDARD,
+        twist: "Standard",
+    },
+    Level {
+        opponent: "Gravity Gus",
+        difficulty: Difficulty::Easy,
+        rules: RuleSet {
+            gravity: true,
+            ..STANDARD
+        },
+        twist: "Gravity",
+    },
+    Level {
+        opponent: "Contrary Mo",
+        difficulty: Difficulty::Medium,
+        rules: RuleSet {
+            misere: true,
+            ..STANDARD
+        },
+        twist: "Misère",
+    },
+    Level {
+        opponent: "Wraparound Wren",
+        difficulty: Difficulty::Medium,
+        rules: RuleSet {
+            topology: Topology::Torus,
+            ..STANDARD
+        },
+        twist: "Torus",
+    },
+    Level {
+        opponent: "Fading Fay",
+        difficulty: Difficulty::Hard,
+        rules: RuleSet {
+            expiring: true,
+            ..STANDARD
+        },
+        twist: "Expiring",
+    },
+    Level {
+        opponent: "Grandmaster Gray",
+        difficulty: Difficulty::Hard,
+        rules: STANDARD,
+        twist: "Standard",
+    },
+];
+
+/// A campaign level being played.
+#[derive(Clone, Copy, Debug)]
+pub struct Campaign {
+    /// Index of the level in [`LEVELS`].
+    pub level: usize,
+    /// Stars earned by the level's last finished game, if one has finished.
+    pub stars: Option<u8>,
+}
+
+impl Campaign {
+    /// Starts playing the level at `level`.
+    pub fn new(level: usize) -> Self {
+        Self { level, stars: None }
+    }
+
+    /// Returns the level being played.
+    pub fn current(&self) -> Level {
+        LEVELS[self.level]
+    }
+}
+
+/// Returns the stars a game earns, where `won` is `None` for a draw and
+/// `moves` is how many moves the player made.
+pub fn stars(won: Option<bool>, moves: usize) -> u8 {
+    match won {
+        Some(true) if moves <= QUICK_WIN_MOVES => 3,
+        Some(true) => 2,
+        None => 1,
+        Some(false) => 0,
+    }
+}
+
+/// Keeps `stars` as the best for `level` in `progress`, the best stars for
+/// each level in order.
+pub fn award(progress: &mut Vec<u8>, level: usize, stars: u8) {
+    if progress.len() <= level {
+        progress.resize(level + 1, 0);
+    }
+    progress[level] = progress[level].max(stars);
+}
+
+/// Returns how many levels are open: the first, and each one after a
+/// cleared level.
+pub fn unlocked(progress: &[u8]) -> usize {
+    let cleared = progress.iter().take_while(|&&stars| stars > 0).count();
+    (cleared + 1).min(LEVELS.len())
+}
+
+/// Returns `stars` out of three as filled and empty stars, such as "★★☆".
+pub fn star_label(stars: u8) -> String {
+    (0..3)
+        .map(|star| if star < stars { '★' } else { '☆' })
+        .collect()
+}
@@ -0,0 +1,68 @@
+//! Text chat between the players of a LAN or online game.
+//!
+//! Lines travel as [`crate::net::Message::Chat`] alongside the moves, so
+//! the chat lasts exactly as long as the connection. Each line is stamped
+//! with the local time it was sent or received.
+
+use std::fmt;
+
+use chrono::{DateTime, Local};
+
+use crate::Player;
+
+/// Longest line that can be sent, in characters.
+pub const MAX_LINE_LEN: usize = 200;
+
+/// Standard messages offered as one-click buttons.
+pub const EMOTES: [&str; 3] = ["👍", "😮", "GG"];
+
+/// A line of chat.
+#[derive(Clone, Debug)]
+pub struct ChatLine {
+    pub time: DateTime<Local>,
+    pub from: Player,
+    pub text: String,
+}
+
+impl fmt::Display for ChatLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:?}: {}",
+            self.time.format("%H:%M"),
+            self.from,
+            self.text
+        )
+    }
+}
+
+/// The chat of one network session, oldest line first.
+#[derive(Debug, Default)]
+pub struct Chat {
+    lines: Vec<ChatLine>,
+    /// Lines received while the chat panel was collapsed.
+    pub unread: usize,
+}
+
+impl Chat {
+    /// Records a line from `from` at the current time.
+    pub fn push(&mut self, from: Player, text: String) {
+        self.lines.push(ChatLine {
+            time: Local::now(),
+            from,
+            text,
+        });
+    }
+
+    /// Returns the lines, oldest first.
+    pub fn lines(&self) -> &[ChatLine] {
+        &self.lines
+    }
+}
+
+/// Trims `text` for sending, cutting it to [`MAX_LINE_LEN`] characters,
+/// or returns `None` if nothing is left.
+pub fn prepare(text: &str) -> Option<String> {
+    let text: String = text.trim().chars().take(MAX_LINE_LEN).collect();
+    (!text.is_empty()).then_some(text)
+}
@@ -0,0 +1,92 @@
+//! Playing in the terminal.
+//!
+//! `--cli` skips the window and plays on standard input and output with
+//! the same rules and computer opponent, so the game works over SSH and
+//! can be driven by scripts. The board is printed as text and moves are
+//! read one per line as coordinates such as `b2`.
+//!
+//! By default the human plays X against the computer on Hard. The options
+//! `--two-player` and `--difficulty easy|medium|hard` change that.
+
+use std::io::{self, BufRead, Write};
+
+use tic_tac_toe::ai::{self, Difficulty, Opponent, COMPUTER_SIDE};
+use tic_tac_toe::engine::{Board, Cell, GameState, Outcome, Player};
+use tic_tac_toe::rules::RuleSet;
+
+use crate::notation;
+use crate::{cell_name, column_label, row_label};
+
+/// Plays a game in the terminal against `opponent`, with the computer at
+/// `difficulty`.
+pub fn run(opponent: Opponent, difficulty: Difficulty) -> io::Result<()> {
+    let mut game = GameState::new(Player::X, [[Cell::Empty; 3]; 3], RuleSet::default());
+    let mut lines = io::stdin().lock().lines();
+    let mut out = io::stdout().lock();
+
+    loop {
+        write!(out, "{}", format_board(game.board()))?;
+        match game.outcome() {
+            Outcome::InProgress => {}
+            Outcome::Won(winner, _) => {
+                writeln!(out, "{winner:?} wins!")?;
+                return Ok(());
+            }
+            Outcome::Draw => {
+                writeln!(out, "It's a draw!")?;
+                return Ok(());
+            }
+        }
+        let player = game.to_move();
+        let position = if opponent == Opponent::Computer && player == COMPUTER_SIDE {
+            let position = ai::choose_move(game.board(), player, difficulty, game.rules())
+                .expect("an unfinished game has a free cell");
+            writeln!(
+                out,
+                "Computer plays {}",
+                cell_name(position.row(), position.col())
+            )?;
+            position
+        } else {
+            write!(out, "{player:?} to move: ")?;
+            out.flush()?;
+            // Running out of input ends the game quietly, as a script would
+            let Some(line) = lines.next().transpose()? else {
+                writeln!(out)?;
+                return Ok(());
+            };
+            match line.trim() {
+                "q" | "quit" => return Ok(()),
+                input => match notation::parse_cell(input) {
+                    Some(position) => position,
+                    None => {
+                        writeln!(out, "Enter a cell such as b2, or q to quit.")?;
+                        continue;
+                    }
+                },
+            }
+        };
+        if let Err(err) = game.play(position) {
+            writeln!(out, "Can't play there: {err}.")?;
+        }
+    }
+}
+
+/// Draws the board as text with its coordinates, one row per line.
+fn format_board(board: &Board) -> String {
+    let columns: Vec<_> = (0..3).map(column_label).collect();
+    let mut text = format!("\n  {}\n", columns.join(" "));
+    for (row, cells) in board.iter().enumerate() {
+        let cells: Vec<_> = cells
+            .iter()
+            .map(|cell| match cell {
+                Cell::Empty => ".",
+                Cell::Blocked => "#",
+                Cell::Player(Player::X) => "X",
+                Cell::Player(Player::O) => "O",
+            })
+            .collect();
+        text.push_str(&format!("{} {}\n", row_label(row), cells.join(" ")));
+    }
+    text
+}
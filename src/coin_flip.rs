@@ -0,0 +1,57 @@
+//! Coin flip deciding who moves first.
+//!
+//! The outcome and the number of flips shown are both derived from a seed,
+//! so replaying a game with the same seed shows the same flip.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::Player;
+
+/// An animated coin flip that settles on the starting player.
+#[derive(Clone, Copy, Debug)]
+pub struct CoinFlip {
+    /// The side currently facing up.
+    pub face: Player,
+    /// The side the coin will land on.
+    pub result: Player,
+    /// Flips remaining before the coin lands.
+    flips_left: u32,
+}
+
+impl CoinFlip {
+    /// Creates a flip whose result is determined by `seed`.
+    pub fn new(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let result = if rng.gen_bool(0.5) {
+            Player::X
+        } else {
+            Player::O
+        };
+        let flips_left = rng.gen_range(6..10);
+        // Start on whichever face lands on `result` after the final flip.
+        let face = if flips_left % 2 == 0 {
+            result
+        } else {
+            result.other()
+        };
+        Self {
+            face,
+            result,
+            flips_left,
+        }
+    }
+
+    /// Whether the coin has finished flipping and shows its result.
+    pub fn has_landed(&self) -> bool {
+        self.flips_left == 0
+    }
+
+    /// Turns the coin over once.
+    pub fn step(&mut self) {
+        if self.flips_left > 0 {
+            self.face = self.face.other();
+            self.flips_left -= 1;
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! Connect-K: longer lines on a larger board.
+//!
+//! The classic rules are played on a bigger square board, where more
+//! marks in a row are needed to win: four on 6x6, or five on 15x15 as in
+//! Gomoku. The computer only looks a couple of moves ahead, taking a win
+//! or blocking one, since the boards are far too big to search to the end.
+
+use tic_tac_toe::ai::Opponent;
+
+use crate::{play_variant, Cell, GameState, Outcome, Player, Position, RuleSet};
+
+/// How many moves ahead the computer looks.
+pub const SEARCH_DEPTH: u32 = 2;
+
+/// A board offered, with the marks in a row needed to win on it.
+#[derive(Clone, Copy, Debug)]
+pub struct Size {
+    /// Cells along each edge.
+    pub cells: usize,
+    /// Marks in a row needed to win.
+    pub win_length: usize,
+    /// Name on the board's button.
+    pub name: &'static str,
+}
+
+/// The boards offered, smallest first.
+pub const SIZES: [Size; 2] = [
+    Size {
+        cells: 6,
+        win_length: 4,
+        name: "4 in a Row · 6x6",
+    },
+    Size {
+        cells: 15,
+        win_length: 5,
+        name: "Gomoku · 15x15",
+    },
+];
+
+/// A Connect-K game on one of the boards offered.
+#[derive(Clone, Debug)]
+pub enum ConnectK {
+    Six(GameState<6>),
+    Fifteen(GameState<15>),
+}
+
+impl ConnectK {
+    /// Starts a game on the board `cells` across with `first` to move, or
+    /// returns `None` if no board that size is offered.
+    pub fn new(cells: usize, first: Player) -> Option<Self> {
+        let size = SIZES.iter().find(|size| size.cells == cells)?;
+        let rules = RuleSet::default();
+        Some(match cells {
+            6 => ConnectK::Six(
+                GameState::new(first, [[Cell::Empty; 6]; 6], rules)
+                    .with_win_length(size.win_length),
+            ),
+            _ => ConnectK::Fifteen(
+                GameState::new(first, [[Cell::Empty; 15]; 15], rules)
+                    .with_win_length(size.win_length),
+            ),
+        })
+    }
+
+    /// Returns the number of cells along each edge.
+    pub fn size(&self) -> usize {
+        match self {
+            ConnectK::Six(_) => 6,
+            ConnectK::Fifteen(_) => 15,
+        }
+    }
+
+    /// Returns the number of marks in a row needed to win.
+    pub fn win_length(&self) -> usize {
+        match self {
+            ConnectK::Six(game) => game.win_length(),
+            ConnectK::Fifteen(game) => game.win_length(),
+        }
+    }
+
+    /// Returns what the board holds at `row` and `col`.
+    pub fn cell(&self, row: usize, col: usize) -> Cell {
+        match self {
+            ConnectK::Six(game) => game.board()[row][col],
+            ConnectK::Fifteen(game) => game.board()[row][col],
+        }
+    }
+
+    /// Returns the player to move.
+    pub fn to_move(&self) -> Player {
+        match self {
+            ConnectK::Six(game) => game.to_move(),
+            ConnectK::Fifteen(game) => game.to_move(),
+        }
+    }
+
+    /// Returns where the game stands.
+    pub fn outcome(&self) -> Outcome {
+        match self {
+            ConnectK::Six(game) => game.outcome(),
+            ConnectK::Fifteen(game) => game.outcome(),
+        }
+    }
+
+    /// Plays a mark at `row` and `col` and, against the computer, its
+    /// reply. A move off the board or on a taken cell is ignored.
+    pub fn play(&mut self, row: usize, col: usize, opponent: Opponent) {
+        match self {
+            ConnectK::Six(game) => {
+                if let Some(position) = Position::within::<6>(row, col) {
+                    play_variant(game, position, opponent, SEARCH_DEPTH);
+                }
+            }
+            ConnectK::Fifteen(game) => {
+                if let Some(position) = Position::within::<15>(row, col) {
+                    play_variant(game, position, opponent, SEARCH_DEPTH);
+                }
+            }
+        }
+    }
+}
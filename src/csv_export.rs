@@ -0,0 +1,226 @@
+//! Export of the game history as CSV for spreadsheets.
+//!
+//! The games passing the History screen's filters are written one per
+//! row, with only the columns the player picked. A second file beside it
+//! sums the same games up per player. Fields holding the delimiter, a
+//! quote, or a line break are quoted. Spreadsheets in locales that write
+//! decimals with a comma expect a semicolon between fields, so the
+//! delimiter can be changed from the default comma.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::history::HistoryEntry;
+use crate::notation::Notation;
+use crate::Player;
+
+/// File name suggested when exporting the games.
+pub const DEFAULT_FILE_NAME: &str = "tic-tac-toe-games.csv";
+
+/// Ending added to the games file's name for the per-player summary.
+const SUMMARY_SUFFIX: &str = "-summary";
+
+/// What separates the fields of a row.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Delimiter {
+    #[default]
+    Comma,
+    Semicolon,
+    Tab,
+}
+
+impl Delimiter {
+    /// Returns the next delimiter, wrapping from Tab back to Comma.
+    pub fn next(self) -> Self {
+        match self {
+            Delimiter::Comma => Delimiter::Semicolon,
+            Delimiter::Semicolon => Delimiter::Tab,
+            Delimiter::Tab => Delimiter::Comma,
+        }
+    }
+
+    /// Returns the delimiter's name, for its button.
+    pub fn label(self) -> &'static str {
+        match self {
+            Delimiter::Comma => "Comma",
+            Delimiter::Semicolon => "Semicolon",
+            Delimiter::Tab => "Tab",
+        }
+    }
+
+    fn symbol(self) -> char {
+        match self {
+            Delimiter::Comma => ',',
+            Delimiter::Semicolon => ';',
+            Delimiter::Tab => '\t',
+        }
+    }
+}
+
+/// A column of the games file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Column {
+    PlayedAt,
+    XPlayer,
+    OPlayer,
+    Variant,
+    Result,
+    Moves,
+    DurationSecs,
+    Notation,
+}
+
+impl Column {
+    /// Every column, in the order they are written.
+    pub const ALL: [Column; 8] = [
+        Column::PlayedAt,
+        Column::XPlayer,
+        Column::OPlayer,
+        Column::Variant,
+        Column::Result,
+        Column::Moves,
+        Column::DurationSecs,
+        Column::Notation,
+    ];
+
+    /// Returns the column's heading, also used for its button.
+    pub fn header(self) -> &'static str {
+        match self {
+            Column::PlayedAt => "Played at",
+            Column::XPlayer => "X player",
+            Column::OPlayer => "O player",
+            Column::Variant => "Variant",
+            Column::Result => "Result",
+            Column::Moves => "Moves",
+            Column::DurationSecs => "Duration (s)",
+            Column::Notation => "Notation",
+        }
+    }
+
+    fn value(self, entry: &HistoryEntry) -> String {
+        match self {
+            Column::PlayedAt => entry.played_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            Column::XPlayer => entry.x_player.clone(),
+            Column::OPlayer => entry.o_player.clone(),
+            Column::Variant => entry.variant.clone(),
+            Column::Result => entry.record.describe_result(),
+            Column::Moves => entry.record.moves.len().to_string(),
+            Column::DurationSecs => entry.duration_secs.to_string(),
+            Column::Notation => Notation::from_history(entry).to_string(),
+        }
+    }
+}
+
+/// The columns and delimiter chosen for exporting.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsvExport {
+    /// The columns written, in [`Column::ALL`] order.
+    pub columns: Vec<Column>,
+    pub delimiter: Delimiter,
+}
+
+impl Default for CsvExport {
+    fn default() -> Self {
+        Self {
+            columns: Column::ALL.to_vec(),
+            delimiter: Delimiter::default(),
+        }
+    }
+}
+
+impl CsvExport {
+    /// Adds `column` to the export, or leaves it out if it was in.
+    pub fn toggle(&mut self, column: Column) {
+        if self.columns.contains(&column) {
+            self.columns.retain(|&chosen| chosen != column);
+        } else {
+            self.columns.push(column);
+            self.columns
+                .sort_by_key(|chosen| Column::ALL.iter().position(|each| each == chosen));
+        }
+    }
+
+    /// Writes `entries` to `path`, and their per-player summary to a file
+    /// beside it with [`SUMMARY_SUFFIX`] added to the name.
+    pub fn save(&self, entries: &[HistoryEntry], path: &Path) -> io::Result<()> {
+        fs::write(path, self.games(entries))?;
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let mut name = format!("{stem}{SUMMARY_SUFFIX}");
+        if let Some(extension) = path.extension() {
+            name = format!("{name}.{}", extension.to_string_lossy());
+        }
+        fs::write(path.with_file_name(name), self.summary(entries))
+    }
+
+    /// Returns the games file: a heading row, then a row per game.
+    fn games(&self, entries: &[HistoryEntry]) -> String {
+        let mut csv = self.row(
+            self.columns
+                .iter()
+                .map(|column| column.header().to_string()),
+        );
+        for entry in entries {
+            csv += &self.row(self.columns.iter().map(|column| column.value(entry)));
+        }
+        csv
+    }
+
+    /// Returns the summary file: each player's games, wins, losses, draws,
+    /// and share of games won, by name.
+    fn summary(&self, entries: &[HistoryEntry]) -> String {
+        // Wins, losses, and draws by player, in order of first appearance
+        let mut players: Vec<(&str, [u32; 3])> = Vec::new();
+        for entry in entries {
+            let winner = entry.record.winner();
+            for (side, name) in [(Player::X, &entry.x_player), (Player::O, &entry.o_player)] {
+                let name = name.as_str();
+                let index = match players.iter().position(|&(player, _)| player == name) {
+                    Some(index) => index,
+                    None => {
+                        players.push((name, [0; 3]));
+                        players.len() - 1
+                    }
+                };
+                let tally = match winner {
+                    Some(winner) if winner == side => 0,
+                    Some(_) => 1,
+                    None => 2,
+                };
+                players[index].1[tally] += 1;
+            }
+        }
+
+        let headers = ["Player", "Games", "Wins", "Losses", "Draws", "Win rate (%)"];
+        let mut csv = self.row(headers.map(str::to_string));
+        for (name, [wins, losses, draws]) in players {
+            let games = wins + losses + draws;
+            let rate = 100.0 * wins as f32 / games as f32;
+            csv += &self.row([
+                name.to_string(),
+                games.to_string(),
+                wins.to_string(),
+                losses.to_string(),
+                draws.to_string(),
+                format!("{rate:.1}"),
+            ]);
+        }
+        csv
+    }
+
+    /// Joins `fields` into a line, quoting those that need it.
+    fn row(&self, fields: impl IntoIterator<Item = String>) -> String {
+        let delimiter = self.delimiter.symbol();
+        let fields: Vec<_> = fields
+            .into_iter()
+            .map(|field| {
+                if field.contains([delimiter, '"', '\n', '\r']) {
+                    format!("\"{}\"", field.replace('"', "\"\""))
+                } else {
+                    field
+                }
+            })
+            .collect();
+        fields.join(&delimiter.to_string()) + "\r\n"
+    }
+}
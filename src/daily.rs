@@ -0,0 +1,232 @@
+//! Daily Challenge: one game a day against the computer, the same for
+//! everyone.
+//!
+//! The date seeds the challenge, which picks the rules' twists, whether
+//! some cells are blocked off and which, how strongly the computer plays,
+//! and who moves first. Every challenge is played on the classic 3x3
+//! board, where the rule options and results apply; the larger Connect-K
+//! boards are left out. The first finished game of each day is its
+//! result and is kept between sessions. A daily not lost completes the
+//! day, and completing days in a row builds a streak. Each result is
+//! ranked against the past dailies: a win beats a draw and a draw a loss,
+//! then fewer moves and less thinking time rank higher.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::{Datelike, NaiveDate};
+use directories::ProjectDirs;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use tic_tac_toe::ai::Difficulty;
+
+use crate::{Player, RuleSet, Topology};
+
+/// Chance of each twist to the rules being part of a day's challenge.
+const TWIST_CHANCE: f64 = 0.25;
+/// Chance of a day's challenge having cells blocked off.
+const BLOCKED_CHANCE: f64 = 0.3;
+
+/// A day's challenge.
+#[derive(Clone, Copy, Debug)]
+pub struct Challenge {
+    /// The day the challenge is for.
+    pub date: NaiveDate,
+    /// The rules it is played under.
+    pub rules: RuleSet,
+    /// Seed of its blocked-cell layout, if it has one.
+    pub layout: Option<u64>,
+    /// How strongly the computer plays.
+    pub difficulty: Difficulty,
+    /// Who moves first.
+    pub first: Player,
+}
+
+impl Challenge {
+    /// Returns the challenge for `date`, which is the same on every
+    /// machine.
+    pub fn for_date(date: NaiveDate) -> Self {
+        let mut rng = StdRng::seed_from_u64(date.num_days_from_ce() as u64);
+        let rules = RuleSet {
+            misere: rng.gen_bool(TWIST_CHANCE),
+            gravity: rng.gen_bool(TWIST_CHANCE),
+            expiring: rng.gen_bool(TWIST_CHANCE),
+            topology: if rng.gen_bool(TWIST_CHANCE) {
+                Topology::Torus
+            } else {
+                Topology::Flat
+            },
+        };
+        let layout = rng.gen_bool(BLOCKED_CHANCE).then(|| rng.gen());
+        let difficulty = match rng.gen_range(0..3) {
+            0 => Difficulty::Easy,
+            1 => Difficulty::Medium,
+            _ => Difficulty::Hard,
+        };
+        let first = if rng.gen_bool(0.5) {
+            Player::X
+        } else {
+            Player::O
+        };
+        Self {
+            date,
+            rules,
+            layout,
+            difficulty,
+            first,
+        }
+    }
+}
+
+/// How a daily ended for the player.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Outcome {
+    Won,
+    Drew,
+    Lost,
+}
+
+/// The result of a day's challenge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyResult {
+    /// How the game ended.
+    pub outcome: Outcome,
+    /// How many moves the player made.
+    pub moves: usize,
+    /// How long the player spent thinking.
+    pub time: Duration,
+}
+
+impl DailyResult {
+    /// Whether the day counts as completed: the player did not lose.
+    pub fn completed(&self) -> bool {
+        self.outcome != Outcome::Lost
+    }
+
+    /// Returns the key results are ranked by, lowest best.
+    fn rank(&self) -> (Outcome, usize, Duration) {
+        (self.outcome, self.moves, self.time)
+    }
+
+    /// Describes the result, such as "Won in 3 moves".
+    pub fn describe(&self) -> String {
+        let outcome = match self.outcome {
+            Outcome::Won => "Won",
+            Outcome::Drew => "Drew",
+            Outcome::Lost => "Lost",
+        };
+        let plural = if self.moves == 1 { "" } else { "s" };
+        format!("{outcome} in {} move{plural}", self.moves)
+    }
+}
+
+/// The result of every daily played, persisted between sessions.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Dailies {
+    /// Each day's result, keyed by the day's number counted from the
+    /// start of the common era.
+    results: BTreeMap<i32, DailyResult>,
+}
+
+impl Dailies {
+    /// Loads the saved results, falling back to none.
+    pub fn load() -> Self {
+        dailies_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Returns the result of the daily for `date`, if it was played.
+    pub fn result(&self, date: NaiveDate) -> Option<DailyResult> {
+        self.results.get(&date.num_days_from_ce()).copied()
+    }
+
+    /// Keeps `result` for the daily on `date` and saves, unless that day
+    /// already has a result. Returns whether it was kept.
+    pub fn record(&mut self, date: NaiveDate, result: DailyResult) -> bool {
+        let day = date.num_days_from_ce();
+        if self.results.contains_key(&day) {
+            return false;
+        }
+        self.results.insert(day, result);
+        self.save();
+        true
+    }
+
+    /// Returns how many days in a row up to `today` were completed. A day
+    /// not yet played today does not break the streak.
+    pub fn streak(&self, today: NaiveDate) -> usize {
+        let today = today.num_days_from_ce();
+        let start = if self.results.contains_key(&today) {
+            today
+        } else {
+            today - 1
+        };
+        (0..)
+            .map(|back| self.results.get(&(start - back)))
+            .take_while(|result| result.is_some_and(DailyResult::completed))
+            .count()
+    }
+
+    /// Returns the most days in a row ever completed.
+    pub fn longest_streak(&self) -> usize {
+        let mut longest = 0;
+        let mut run = 0;
+        let mut previous = None;
+        for (&day, result) in &self.results {
+            if !result.completed() {
+                run = 0;
+            } else if previous == Some(day - 1) {
+                run += 1;
+            } else {
+                run = 1;
+            }
+            previous = Some(day);
+            longest = longest.max(run);
+        }
+        longest
+    }
+
+    /// Returns how many dailies before `date` its result beats, out of how
+    /// many there were.
+    pub fn rank(&self, date: NaiveDate) -> Option<(usize, usize)> {
+        let day = date.num_days_from_ce();
+        let result = self.results.get(&day)?;
+        let past: Vec<_> = self.results.range(..day).map(|(_, past)| past).collect();
+        let beaten = past
+            .iter()
+            .filter(|past| result.rank() < past.rank())
+            .count();
+        Some((beaten, past.len()))
+    }
+
+    /// Returns the best result of the dailies before `date`.
+    pub fn best_before(&self, date: NaiveDate) -> Option<DailyResult> {
+        self.results
+            .range(..date.num_days_from_ce())
+            .map(|(_, result)| *result)
+            .min_by_key(DailyResult::rank)
+    }
+
+    /// Writes the results to disk, ignoring failures.
+    fn save(&self) {
+        let Some(path) = dailies_path() else {
+            return;
+        };
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        if let Ok(json) = serde_json::to_string_pretty(self) {
+            let _ = fs::write(path, json);
+        }
+    }
+}
+
+/// Location of the daily results file in the platform data directory.
+pub fn dailies_path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "tic-tac-toe").map(|dirs| dirs.data_dir().join("dailies.json"))
+}
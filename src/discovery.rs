@@ -0,0 +1,139 @@
+//! Finding LAN games through mDNS.
+//!
+//! A host advertises its game as a `_tictactoe._tcp` service, with the
+//! host's name in a TXT record, for as long as it waits for someone to
+//! join. The join screen browses for these services and lists each game
+//! it finds, so nobody has to type an address.
+
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+
+use mdns_sd::{Receiver, ServiceDaemon, ServiceEvent, ServiceInfo};
+
+/// Service type games are advertised under.
+const SERVICE_TYPE: &str = "_tictactoe._tcp.local.";
+
+/// TXT record key holding the host's name.
+const NAME_KEY: &str = "name";
+
+/// A game hosted somewhere on the local network.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FoundGame {
+    /// The service's full mDNS name, which tells games apart.
+    fullname: String,
+    /// Name of the player hosting.
+    pub host: String,
+    pub address: SocketAddr,
+}
+
+impl FoundGame {
+    /// Describes the game for the join screen, such as "Alice's game –
+    /// 192.168.1.5".
+    pub fn label(&self) -> String {
+        format!("{}'s game – {}", self.host, self.address.ip())
+    }
+}
+
+/// Advertises a hosted game until dropped.
+pub struct Advertisement {
+    daemon: ServiceDaemon,
+    fullname: String,
+}
+
+impl Advertisement {
+    /// Advertises a game hosted by `name` at `ip` and `port`.
+    pub fn start(name: &str, ip: IpAddr, port: u16) -> io::Result<Self> {
+        let daemon = ServiceDaemon::new().map_err(io::Error::other)?;
+        // Instance and host names only need to be unique on the network
+        let id: u32 = rand::random();
+        let service = ServiceInfo::new(
+            SERVICE_TYPE,
+            &format!("{name} {id:08x}"),
+            &format!("tictactoe-{id:08x}.local."),
+            ip,
+            port,
+            &[(NAME_KEY, name)][..],
+        )
+        .map_err(io::Error::other)?;
+        let fullname = service.get_fullname().to_string();
+        daemon.register(service).map_err(io::Error::other)?;
+        Ok(Self { daemon, fullname })
+    }
+}
+
+impl Drop for Advertisement {
+    fn drop(&mut self) {
+        let _ = self.daemon.unregister(&self.fullname);
+        let _ = self.daemon.shutdown();
+    }
+}
+
+/// Looks for games hosted on the local network until dropped.
+pub struct Browser {
+    daemon: ServiceDaemon,
+    events: Receiver<ServiceEvent>,
+    games: Vec<FoundGame>,
+}
+
+impl Browser {
+    /// Starts looking for games.
+    pub fn start() -> io::Result<Self> {
+        let daemon = ServiceDaemon::new().map_err(io::Error::other)?;
+        let events = daemon.browse(SERVICE_TYPE).map_err(io::Error::other)?;
+        Ok(Self {
+            daemon,
+            events,
+            games: Vec::new(),
+        })
+    }
+
+    /// Takes in what has been heard since the last call, returning whether
+    /// the list of games changed.
+    pub fn poll(&mut self) -> bool {
+        let mut changed = false;
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                ServiceEvent::ServiceResolved(info) => {
+                    // Prefer IPv4, which every machine on a home network
+                    // can reach
+                    let addresses = info.get_addresses();
+                    let Some(&ip) = addresses
+                        .iter()
+                        .find(|ip| ip.is_ipv4())
+                        .or_else(|| addresses.iter().next())
+                    else {
+                        continue;
+                    };
+                    let game = FoundGame {
+                        fullname: info.get_fullname().to_string(),
+                        host: info
+                            .get_property_val_str(NAME_KEY)
+                            .unwrap_or("Someone")
+                            .to_string(),
+                        address: SocketAddr::new(ip, info.get_port()),
+                    };
+                    self.games.retain(|found| found.fullname != game.fullname);
+                    self.games.push(game);
+                    changed = true;
+                }
+                ServiceEvent::ServiceRemoved(_, fullname) => {
+                    self.games.retain(|found| found.fullname != fullname);
+                    changed = true;
+                }
+                _ => {}
+            }
+        }
+        changed
+    }
+
+    /// Returns the games found so far, in the order they were found.
+    pub fn games(&self) -> &[FoundGame] {
+        &self.games
+    }
+}
+
+impl Drop for Browser {
+    fn drop(&mut self) {
+        let _ = self.daemon.shutdown();
+    }
+}
@@ -0,0 +1,121 @@
+//! Board editor for setting up custom positions.
+//!
+//! Marks can be placed and removed freely; the position is validated
+//! before play starts from it. Positions can also be written as text, such
+//! as `X.O.X.... O` with the side to move last, for the clipboard.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::notation::{format_board, parse_board};
+use crate::{has_line, Board, Cell, Player};
+
+/// Why an edited position cannot be played from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PositionError {
+    /// One side has more than one extra mark.
+    UnbalancedMarks,
+    /// The side to move cannot be on move given the mark counts.
+    WrongSideToMove,
+    /// A line is already complete, so the game is over.
+    AlreadyWon,
+    /// No empty cells remain.
+    BoardFull,
+}
+
+impl fmt::Display for PositionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PositionError::UnbalancedMarks => "X and O counts differ by more than one",
+            PositionError::WrongSideToMove => "The side with more marks cannot move next",
+            PositionError::AlreadyWon => "A line is already complete",
+            PositionError::BoardFull => "The board is full",
+        })
+    }
+}
+
+/// A position being edited.
+#[derive(Clone, Copy, Debug)]
+pub struct PositionEditor {
+    pub board: Board,
+    pub to_move: Player,
+}
+
+impl PositionEditor {
+    /// Starts editing from an existing position.
+    pub fn new(board: Board, to_move: Player) -> Self {
+        Self { board, to_move }
+    }
+
+    /// Cycles a cell through empty, X, and O.
+    pub fn cycle_cell(&mut self, row: usize, col: usize) {
+        self.board[row][col] = match self.board[row][col] {
+            Cell::Empty => Cell::Player(Player::X),
+            Cell::Player(Player::X) => Cell::Player(Player::O),
+            Cell::Player(Player::O) | Cell::Blocked => Cell::Empty,
+        };
+    }
+
+    /// Removes every mark.
+    pub fn clear(&mut self) {
+        self.board = [[Cell::Empty; 3]; 3];
+    }
+
+    /// Checks that the position could arise in a game still in progress.
+    ///
+    /// On success, returns the player who must have moved first.
+    pub fn validate(&self) -> Result<Player, PositionError> {
+        let count = |player| {
+            self.board
+                .iter()
+                .flatten()
+                .filter(|&&cell| cell == Cell::Player(player))
+                .count()
+        };
+        let (x, o) = (count(Player::X), count(Player::O));
+
+        let first = if x == o {
+            self.to_move
+        } else if x == o + 1 {
+            Player::X
+        } else if o == x + 1 {
+            Player::O
+        } else {
+            return Err(PositionError::UnbalancedMarks);
+        };
+        if x != o && self.to_move == first {
+            return Err(PositionError::WrongSideToMove);
+        }
+        if has_line(&self.board, Player::X) || has_line(&self.board, Player::O) {
+            return Err(PositionError::AlreadyWon);
+        }
+        if x + o == 9 {
+            return Err(PositionError::BoardFull);
+        }
+        Ok(first)
+    }
+}
+
+impl fmt::Display for PositionEditor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:?}", format_board(&self.board), self.to_move)
+    }
+}
+
+impl FromStr for PositionEditor {
+    type Err = &'static str;
+
+    /// Reads a position written as nine cells and the side to move. The
+    /// position still needs [`PositionEditor::validate`] before play.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        const SYNTAX: &str = "Expected nine of X, O, . and # then the side to move";
+        let (cells, to_move) = text.trim().split_once(' ').ok_or(SYNTAX)?;
+        let board = parse_board(cells).ok_or(SYNTAX)?;
+        let to_move = match to_move.trim() {
+            "X" => Player::X,
+            "O" => Player::O,
+            _ => return Err(SYNTAX),
+        };
+        Ok(Self::new(board, to_move))
+    }
+}
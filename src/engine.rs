@@ -0,0 +1,620 @@
+//! The rules of the game, independent of any frontend.
+//!
+//! [`GameState`] holds a game in progress and checks every move against
+//! its [`RuleSet`], so the window, the terminal mode, and the self-play
+//! simulator all play by the same rules.
+//!
+//! A [`GameState`] is a small `Copy` value that keeps only what the rules
+//! need, not the full move history, so search can branch positions with
+//! [`Game::apply`] without touching the game being played.
+//!
+//! The board is square, with its size a const parameter that is 3 unless
+//! given, and the number of marks in a row needed to win is set per game,
+//! so the same rules play Connect-K games such as four in a row on 6x6 or
+//! Gomoku's five on 15x15.
+
+use std::error::Error;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::{Game, Status};
+use crate::rules::{RuleSet, Topology, MAX_LIVE_MARKS};
+
+/// Number of marks in a row needed to win, unless a game sets its own.
+pub const WIN_LENGTH: usize = 3;
+
+/// Most marks in a row a game may need to win.
+pub const MAX_WIN_LENGTH: usize = 5;
+
+/// Represents a player in the game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Player {
+    X,
+    O,
+}
+
+impl Player {
+    /// Returns the opposing player.
+    pub fn other(self) -> Self {
+        match self {
+            Player::X => Player::O,
+            Player::O => Player::X,
+        }
+    }
+
+    /// Returns the player's index into per-player arrays.
+    pub fn index(self) -> usize {
+        match self {
+            Player::X => 0,
+            Player::O => 1,
+        }
+    }
+}
+
+/// Represents the state of a cell on the board.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Cell {
+    /// The cell is empty and available for play.
+    Empty,
+    /// The cell is occupied by a player.
+    Player(Player),
+    /// The cell is blocked off and no mark may be placed on it.
+    Blocked,
+}
+
+/// A square game board, 3x3 unless `N` says otherwise, indexed by row
+/// then column.
+pub type Board<const N: usize = 3> = [[Cell; N]; N];
+
+/// A cell of the board. Positions can only be made for cells that exist
+/// on the board they are made for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Position {
+    row: usize,
+    col: usize,
+}
+
+impl Position {
+    /// The center cell.
+    pub const CENTER: Position = Position { row: 1, col: 1 };
+
+    /// Returns the cell at `row` and `col`, or `None` if that is off the
+    /// 3x3 board.
+    pub fn new(row: usize, col: usize) -> Option<Self> {
+        Self::within::<3>(row, col)
+    }
+
+    /// Returns the cell at `row` and `col`, or `None` if that is off a
+    /// board `N` cells across.
+    pub fn within<const N: usize>(row: usize, col: usize) -> Option<Self> {
+        (row < N && col < N).then_some(Self { row, col })
+    }
+
+    pub fn row(self) -> usize {
+        self.row
+    }
+
+    pub fn col(self) -> usize {
+        self.col
+    }
+
+    /// Lists every cell of the 3x3 board in reading order.
+    pub fn all() -> impl Iterator<Item = Position> {
+        Self::all_within::<3>()
+    }
+
+    /// Lists every cell of a board `N` cells across in reading order.
+    pub fn all_within<const N: usize>() -> impl Iterator<Item = Position> {
+        (0..N).flat_map(|row| (0..N).map(move |col| Position { row, col }))
+    }
+
+    /// Returns what `board` holds at this cell.
+    pub fn cell<const N: usize>(self, board: &Board<N>) -> Cell {
+        board[self.row][self.col]
+    }
+}
+
+/// The cells of a completed line, in order along it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WinLine {
+    cells: [Position; MAX_WIN_LENGTH],
+    len: usize,
+}
+
+impl WinLine {
+    /// Makes the line of the cells of `run`, of which there are at most
+    /// [`MAX_WIN_LENGTH`].
+    fn new(run: &[(usize, usize)]) -> Self {
+        let mut cells = [Position { row: 0, col: 0 }; MAX_WIN_LENGTH];
+        for (cell, &(row, col)) in cells.iter_mut().zip(run) {
+            *cell = Position { row, col };
+        }
+        Self {
+            cells,
+            len: run.len().min(MAX_WIN_LENGTH),
+        }
+    }
+
+    /// Returns the line's cells, in order along it.
+    pub fn cells(&self) -> &[Position] {
+        &self.cells[..self.len]
+    }
+
+    /// Whether the line runs through `position`.
+    pub fn contains(&self, position: &Position) -> bool {
+        self.cells().contains(position)
+    }
+}
+
+/// Where a game stands on the board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    InProgress,
+    /// A line decided the game for the player. Under misère rules the
+    /// line is the loser's.
+    Won(Player, WinLine),
+    Draw,
+}
+
+impl Outcome {
+    /// Whether the game is over.
+    pub fn is_over(self) -> bool {
+        self != Outcome::InProgress
+    }
+
+    /// Returns the winner, if the game has one.
+    pub fn winner(self) -> Option<Player> {
+        match self {
+            Outcome::Won(winner, _) => Some(winner),
+            Outcome::InProgress | Outcome::Draw => None,
+        }
+    }
+
+    /// Returns the line that decided the game, if one did.
+    pub fn line(self) -> Option<WinLine> {
+        match self {
+            Outcome::Won(_, line) => Some(line),
+            Outcome::InProgress | Outcome::Draw => None,
+        }
+    }
+}
+
+/// Why a move was refused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveError {
+    /// The game has already ended.
+    GameOver,
+    /// The cell is not on the board.
+    OutOfBounds,
+    /// The cell, or under gravity its whole column, is taken.
+    CellOccupied,
+    /// The move was made by the player not to move, such as by a network
+    /// player on the opponent's turn.
+    NotYourTurn,
+    /// The move is on a board that is not in play, such as a decided
+    /// small board in Ultimate.
+    BoardClosed,
+}
+
+impl MoveError {
+    /// Says why the move was refused, for showing to the player.
+    pub fn reason(self) -> &'static str {
+        match self {
+            MoveError::GameOver => "the game is over",
+            MoveError::OutOfBounds => "that cell is not on the board",
+            MoveError::CellOccupied => "that cell is taken",
+            MoveError::NotYourTurn => "it is not your turn",
+            MoveError::BoardClosed => "that board is not in play",
+        }
+    }
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.reason())
+    }
+}
+
+impl Error for MoveError {}
+
+/// A game in progress: the board, whose turn it is, and how many moves
+/// have been played.
+#[derive(Clone, Copy, Debug)]
+pub struct GameState<const N: usize = 3> {
+    board: Board<N>,
+    rules: RuleSet,
+    /// Marks in a row needed to win.
+    win_length: usize,
+    to_move: Player,
+    ply: usize,
+    /// Each player's latest marks, oldest first, indexed by
+    /// [`Player::index`]. Under expiring marks the oldest is the next to
+    /// go once all are placed.
+    marks: [[Option<Position>; MAX_LIVE_MARKS]; 2],
+    outcome: Outcome,
+}
+
+impl<const N: usize> GameState<N> {
+    /// Starts a game from `board` with `first` to move, won by a line
+    /// across the board, or of five on larger boards.
+    pub fn new(first: Player, board: Board<N>, rules: RuleSet) -> Self {
+        Self {
+            board,
+            rules,
+            win_length: N.min(MAX_WIN_LENGTH),
+            to_move: first,
+            ply: 0,
+            marks: [[None; MAX_LIVE_MARKS]; 2],
+            outcome: Outcome::InProgress,
+        }
+    }
+
+    pub fn board(&self) -> &Board<N> {
+        &self.board
+    }
+
+    pub fn rules(&self) -> RuleSet {
+        self.rules
+    }
+
+    /// Returns the number of marks in a row needed to win.
+    pub fn win_length(&self) -> usize {
+        self.win_length
+    }
+
+    /// Returns the game with `win_length` marks in a row needed to win,
+    /// kept between 1 and the board's size or [`MAX_WIN_LENGTH`],
+    /// whichever is smaller.
+    pub fn with_win_length(mut self, win_length: usize) -> Self {
+        self.win_length = win_length.clamp(1, N.min(MAX_WIN_LENGTH));
+        self
+    }
+
+    /// Returns the player to move, which stays the last mover once the
+    /// game is over.
+    pub fn to_move(&self) -> Player {
+        self.to_move
+    }
+
+    /// Returns the number of moves played.
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+
+    pub fn outcome(&self) -> Outcome {
+        self.outcome
+    }
+
+    /// Returns the mark of `player`'s that their next move will remove
+    /// under expiring marks, once they have the most allowed.
+    pub fn expiring_mark(&self, player: Player) -> Option<Position> {
+        self.marks[player.index()][0].filter(|_| self.rules.expiring)
+    }
+
+    /// Changes the rules from the next move on, leaving the board and
+    /// outcome as they are.
+    pub fn set_rules(&mut self, rules: RuleSet) {
+        self.rules = rules;
+    }
+
+    /// Passes the turn to the opponent without placing a mark, as a
+    /// blind-mode forfeit does. Does nothing once the game is over.
+    pub fn pass(&mut self) {
+        if !self.outcome.is_over() {
+            self.to_move = self.to_move.other();
+        }
+    }
+
+    /// Places a mark for the player to move at `position`, returning the
+    /// cell it lands on, which under gravity may be lower in the column.
+    ///
+    /// Under expiring marks the player's oldest mark is removed first once
+    /// they have the most allowed. A move that completes a line or fills
+    /// the board ends the game. Only lines through the new mark are
+    /// looked for, since any other would have ended the game already.
+    pub fn play(&mut self, position: Position) -> Result<Position, MoveError> {
+        if self.outcome.is_over() {
+            return Err(MoveError::GameOver);
+        }
+        if position.row >= N || position.col >= N {
+            return Err(MoveError::OutOfBounds);
+        }
+        let player = self.to_move;
+        let col = position.col;
+        let row = self
+            .rules
+            .landing_row(&self.board, position.row, col)
+            .ok_or(MoveError::CellOccupied)?;
+        let marks = &mut self.marks[player.index()];
+        if let (true, Some(oldest)) = (self.rules.expiring, marks[0]) {
+            self.board[oldest.row][oldest.col] = Cell::Empty;
+        }
+        marks.rotate_left(1);
+        marks[MAX_LIVE_MARKS - 1] = Some(Position { row, col });
+        self.board[row][col] = Cell::Player(player);
+        self.ply += 1;
+
+        let landed = Position { row, col };
+        let run = run_through(
+            &self.board,
+            player,
+            landed,
+            self.win_length,
+            self.rules.topology,
+        );
+        if let Some(run) = run {
+            let line = WinLine::new(&run);
+            self.outcome = Outcome::Won(self.rules.line_winner(player), line);
+        } else if self.board.iter().flatten().all(|&cell| cell != Cell::Empty) {
+            self.outcome = Outcome::Draw;
+        } else {
+            self.to_move = player.other();
+        }
+        Ok(landed)
+    }
+}
+
+impl<const N: usize> Game for GameState<N> {
+    type Move = Position;
+
+    fn current_player(&self) -> Player {
+        self.to_move
+    }
+
+    /// Lists the cells a mark may be placed on, in reading order. Under
+    /// gravity only the cell each column's mark would land on is listed.
+    fn legal_moves(&self) -> Vec<Position> {
+        if self.outcome.is_over() {
+            return Vec::new();
+        }
+        Position::all_within::<N>()
+            .filter(|&position| position.cell(&self.board) == Cell::Empty)
+            .filter(|&Position { row, col }| {
+                self.rules.landing_row(&self.board, row, col) == Some(row)
+            })
+            .collect()
+    }
+
+    fn apply_move(&mut self, position: Position) -> Result<(), MoveError> {
+        self.play(position).map(|_| ())
+    }
+
+    fn status(&self) -> Status {
+        match self.outcome {
+            Outcome::InProgress => Status::InProgress,
+            Outcome::Won(winner, _) => Status::Won(winner),
+            Outcome::Draw => Status::Drawn,
+        }
+    }
+}
+
+/// Returns a line of [`WIN_LENGTH`] marks held by `player` on `board`, if
+/// there is one. On a torus the line may wrap around the edges.
+pub fn find_line(board: &Board, player: Player, topology: Topology) -> Option<WinLine> {
+    let run = find_run(board, player, WIN_LENGTH, topology)?;
+    Some(WinLine::new(&run))
+}
+
+/// Returns the cells of a run of `win_length` marks held by `player` on
+/// `board`, in order along it, if there is one.
+///
+/// Runs are scanned from every cell in the four directions a line can
+/// take: along a row, down a column, and along both diagonals, on a
+/// square board of any size. On a torus a run that leaves one edge carries
+/// on from the opposite edge, so (0, 2), (1, 0), (2, 1) is a diagonal.
+pub fn find_run<const N: usize>(
+    board: &[[Cell; N]; N],
+    player: Player,
+    win_length: usize,
+    topology: Topology,
+) -> Option<Vec<(usize, usize)>> {
+    const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+    let size = N as isize;
+    let owned = |row: isize, col: isize| {
+        let (row, col) = match topology {
+            Topology::Flat => (row, col),
+            Topology::Torus => (row.rem_euclid(size), col.rem_euclid(size)),
+        };
+        ((0..size).contains(&row) && (0..size).contains(&col))
+            .then(|| (row as usize, col as usize))
+            .filter(|&(row, col)| board[row][col] == Cell::Player(player))
+    };
+
+    (0..N)
+        .flat_map(|row| (0..N).map(move |col| (row as isize, col as isize)))
+        .find_map(|(row, col)| {
+            DIRECTIONS.iter().find_map(|&(d_row, d_col)| {
+                (0..win_length as isize)
+                    .map(|step| owned(row + step * d_row, col + step * d_col))
+                    .collect()
+            })
+        })
+}
+
+/// Returns the cells of a run of `win_length` marks held by `player` on
+/// `board` that passes through `position`, in order along it, if there is
+/// one.
+///
+/// Each of the four directions is scanned as in [`find_run`], but only
+/// along the stretches of the line through `position` that could hold
+/// it, which keeps checking a move cheap on large boards.
+fn run_through<const N: usize>(
+    board: &Board<N>,
+    player: Player,
+    position: Position,
+    win_length: usize,
+    topology: Topology,
+) -> Option<Vec<(usize, usize)>> {
+    const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+    let size = N as isize;
+    let owned = |row: isize, col: isize| {
+        let (row, col) = match topology {
+            Topology::Flat => (row, col),
+            Topology::Torus => (row.rem_euclid(size), col.rem_euclid(size)),
+        };
+        ((0..size).contains(&row) && (0..size).contains(&col))
+            .then(|| (row as usize, col as usize))
+            .filter(|&(row, col)| board[row][col] == Cell::Player(player))
+    };
+    let (row, col) = (position.row as isize, position.col as isize);
+    let reach = win_length as isize;
+
+    DIRECTIONS.iter().find_map(|&(d_row, d_col)| {
+        (1 - reach..=0).find_map(|start| {
+            (start..start + reach)
+                .map(|step| owned(row + step * d_row, col + step * d_col))
+                .collect()
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EMPTY: Board = [[Cell::Empty; 3]; 3];
+
+    fn at(row: usize, col: usize) -> Position {
+        Position::new(row, col).unwrap()
+    }
+
+    /// Plays `cells` in turn from an empty board with X first.
+    fn play_all(rules: RuleSet, cells: &[(usize, usize)]) -> GameState {
+        let mut game = GameState::new(Player::X, EMPTY, rules);
+        for &(row, col) in cells {
+            game.play(at(row, col)).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn completing_a_line_wins() {
+        let game = play_all(
+            RuleSet::default(),
+            &[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)],
+        );
+        assert_eq!(game.outcome().winner(), Some(Player::X));
+        assert_eq!(
+            game.outcome().line().map(|line| line.cells().to_vec()),
+            Some(vec![at(0, 0), at(0, 1), at(0, 2)])
+        );
+        assert_eq!(game.to_move(), Player::X);
+        assert!(game.legal_moves().is_empty());
+    }
+
+    #[test]
+    fn completing_a_line_loses_under_misere() {
+        let rules = RuleSet {
+            misere: true,
+            ..RuleSet::default()
+        };
+        let game = play_all(rules, &[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)]);
+        assert_eq!(game.outcome().winner(), Some(Player::O));
+    }
+
+    #[test]
+    fn filling_the_board_without_a_line_draws() {
+        let game = play_all(
+            RuleSet::default(),
+            &[
+                (0, 0),
+                (0, 1),
+                (0, 2),
+                (1, 1),
+                (1, 0),
+                (1, 2),
+                (2, 1),
+                (2, 0),
+                (2, 2),
+            ],
+        );
+        assert_eq!(game.outcome(), Outcome::Draw);
+        assert_eq!(game.ply(), 9);
+    }
+
+    #[test]
+    fn an_occupied_cell_is_refused() {
+        let mut game = play_all(RuleSet::default(), &[(1, 1)]);
+        assert_eq!(game.play(at(1, 1)), Err(MoveError::CellOccupied));
+        assert_eq!(game.to_move(), Player::O);
+        assert_eq!(game.ply(), 1);
+    }
+
+    #[test]
+    fn positions_off_the_board_do_not_exist() {
+        assert_eq!(Position::new(3, 0), None);
+        assert_eq!(Position::new(0, 3), None);
+        assert_eq!(Position::all().count(), 9);
+    }
+
+    #[test]
+    fn no_move_is_allowed_after_the_game_ends() {
+        let mut game = play_all(
+            RuleSet::default(),
+            &[(0, 0), (1, 0), (0, 1), (1, 1), (0, 2)],
+        );
+        assert_eq!(game.play(at(2, 2)), Err(MoveError::GameOver));
+        game.pass();
+        assert_eq!(game.to_move(), Player::X);
+    }
+
+    #[test]
+    fn marks_fall_down_their_column_under_gravity() {
+        let rules = RuleSet {
+            gravity: true,
+            ..RuleSet::default()
+        };
+        let mut game = GameState::new(Player::X, EMPTY, rules);
+        assert_eq!(game.play(at(0, 1)), Ok(at(2, 1)));
+        assert_eq!(game.play(at(0, 1)), Ok(at(1, 1)));
+    }
+
+    #[test]
+    fn the_oldest_mark_expires_under_expiring_marks() {
+        let rules = RuleSet {
+            expiring: true,
+            ..RuleSet::default()
+        };
+        let mut game = play_all(rules, &[(0, 0), (1, 0), (0, 1), (1, 1), (2, 2), (2, 0)]);
+        assert_eq!(game.expiring_mark(Player::X), Some(at(0, 0)));
+        game.play(at(1, 2)).unwrap();
+        assert_eq!(at(0, 0).cell(game.board()), Cell::Empty);
+        assert_eq!(game.expiring_mark(Player::X), Some(at(0, 1)));
+    }
+
+    #[test]
+    fn four_in_a_row_wins_on_a_larger_board() {
+        let mut game =
+            GameState::new(Player::X, [[Cell::Empty; 6]; 6], RuleSet::default()).with_win_length(4);
+        let cell = |row, col| Position::within::<6>(row, col).unwrap();
+        for (row, col) in [(2, 1), (5, 5), (2, 2), (5, 4), (2, 3), (0, 0)] {
+            game.play(cell(row, col)).unwrap();
+        }
+        assert_eq!(game.outcome(), Outcome::InProgress);
+        game.play(cell(2, 4)).unwrap();
+        assert_eq!(game.outcome().winner(), Some(Player::X));
+        assert_eq!(
+            game.outcome().line().map(|line| line.cells().len()),
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn a_cell_beyond_the_board_is_refused() {
+        let mut game = GameState::new(Player::X, EMPTY, RuleSet::default());
+        let beyond = Position::within::<6>(4, 4).unwrap();
+        assert_eq!(game.play(beyond), Err(MoveError::OutOfBounds));
+        assert_eq!(game.ply(), 0);
+    }
+
+    #[test]
+    fn lines_wrap_around_a_torus() {
+        let mut board = EMPTY;
+        for (row, col) in [(0, 2), (1, 0), (2, 1)] {
+            board[row][col] = Cell::Player(Player::X);
+        }
+        assert_eq!(find_line(&board, Player::X, Topology::Flat), None);
+        assert!(find_line(&board, Player::X, Topology::Torus).is_some());
+    }
+}
@@ -0,0 +1,104 @@
+//! Timestamped log of game events.
+//!
+//! Every move, result, and new game is recorded as it happens so a session
+//! can be reviewed afterwards, for example to settle a dispute over what
+//! was played.
+
+use std::fmt;
+
+use chrono::{DateTime, Local};
+
+use crate::{cell_name, Player};
+
+/// Something that happened during play.
+#[derive(Clone, Copy, Debug)]
+pub enum LogEvent {
+    /// A new game started with the given player to move.
+    NewGame { first: Player },
+    /// Play started from a position set up in the editor.
+    CustomPosition { to_move: Player },
+    /// A saved game was loaded.
+    Loaded { to_move: Player },
+    /// A player placed a mark.
+    Move {
+        player: Player,
+        row: usize,
+        col: usize,
+    },
+    /// A player completed a line.
+    Won(Player),
+    /// The board filled up without a winner.
+    Draw,
+    /// A player conceded the game.
+    Resigned(Player),
+    /// A player's game clock ran out.
+    TimedOut(Player),
+    /// The players agreed to a draw.
+    DrawAgreed,
+    /// A player lost their turn by picking an occupied cell in blind mode.
+    Forfeit(Player),
+    /// A move was taken back.
+    Undone {
+        player: Player,
+        row: usize,
+        col: usize,
+    },
+}
+
+impl fmt::Display for LogEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogEvent::NewGame { first } => write!(f, "New game, {first:?} to move"),
+            LogEvent::CustomPosition { to_move } => {
+                write!(f, "Custom position, {to_move:?} to move")
+            }
+            LogEvent::Loaded { to_move } => write!(f, "Loaded saved game, {to_move:?} to move"),
+            LogEvent::Move { player, row, col } => {
+                write!(f, "{player:?} played {}", cell_name(*row, *col))
+            }
+            LogEvent::Won(player) => write!(f, "{player:?} wins"),
+            LogEvent::Draw => write!(f, "Draw"),
+            LogEvent::Resigned(player) => write!(f, "{player:?} resigned"),
+            LogEvent::TimedOut(player) => write!(f, "{player:?} ran out of time"),
+            LogEvent::DrawAgreed => write!(f, "Draw agreed"),
+            LogEvent::Forfeit(player) => write!(f, "{player:?} forfeited the turn"),
+            LogEvent::Undone { player, row, col } => {
+                write!(f, "{player:?} took back {}", cell_name(*row, *col))
+            }
+        }
+    }
+}
+
+/// A logged event with the local time it occurred.
+#[derive(Clone, Copy, Debug)]
+pub struct LogEntry {
+    pub time: DateTime<Local>,
+    pub event: LogEvent,
+}
+
+impl fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.time.format("%H:%M:%S"), self.event)
+    }
+}
+
+/// The events recorded during this session, oldest first.
+#[derive(Debug, Default)]
+pub struct EventLog {
+    entries: Vec<LogEntry>,
+}
+
+impl EventLog {
+    /// Records an event at the current time.
+    pub fn push(&mut self, event: LogEvent) {
+        self.entries.push(LogEntry {
+            time: Local::now(),
+            event,
+        });
+    }
+
+    /// Returns the recorded entries, oldest first.
+    pub fn entries(&self) -> &[LogEntry] {
+        &self.entries
+    }
+}
@@ -0,0 +1,130 @@
+//! Board export as SVG vector graphics.
+//!
+//! The board is drawn as a classic grid with X and O glyphs, and the
+//! completed line, if there is one, struck through. Everything is plain
+//! SVG with no scripts or external references, so the file can be
+//! embedded directly in a web page.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::{find_line, Board, Cell, Player, Topology, WinLine};
+
+/// File name suggested when exporting the board as SVG.
+pub const DEFAULT_FILE_NAME: &str = "tic-tac-toe.svg";
+
+/// Width and height of a cell in SVG units.
+const CELL: f32 = 100.0;
+/// Space between the grid and the image edges.
+const MARGIN: f32 = 10.0;
+
+/// A board drawn as an SVG document when displayed.
+pub struct Svg<'a>(pub &'a Board);
+
+impl Svg<'_> {
+    /// Writes the document to `path`.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+}
+
+impl fmt::Display for Svg<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let board = self.0;
+        let size = 3.0 * CELL + 2.0 * MARGIN;
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {size} {size}">"#
+        )?;
+        writeln!(
+            f,
+            r##"  <defs><pattern id="hatch" width="8" height="8" patternUnits="userSpaceOnUse" patternTransform="rotate(45)"><rect width="2" height="8" fill="#555555"/></pattern></defs>"##
+        )?;
+        writeln!(
+            f,
+            r##"  <rect width="{size}" height="{size}" fill="#2d2d2d"/>"##
+        )?;
+
+        for (row, cells) in board.iter().enumerate() {
+            for (col, &cell) in cells.iter().enumerate() {
+                let (x, y) = corner(row, col);
+                let (center_x, center_y) = center(row, col);
+                match cell {
+                    Cell::Empty => {}
+                    Cell::Blocked => {
+                        writeln!(
+                            f,
+                            r##"  <rect x="{x}" y="{y}" width="{CELL}" height="{CELL}" fill="#2a2a2a"/>"##
+                        )?;
+                        writeln!(
+                            f,
+                            r#"  <rect x="{x}" y="{y}" width="{CELL}" height="{CELL}" fill="url(#hatch)"/>"#
+                        )?;
+                    }
+                    Cell::Player(Player::X) => {
+                        let reach = CELL * 0.28;
+                        let (left, right) = (center_x - reach, center_x + reach);
+                        let (top, bottom) = (center_y - reach, center_y + reach);
+                        writeln!(
+                            f,
+                            r##"  <path d="M{left} {top}L{right} {bottom}M{left} {bottom}L{right} {top}" stroke="#ff6b6b" stroke-width="10" stroke-linecap="round"/>"##
+                        )?;
+                    }
+                    Cell::Player(Player::O) => {
+                        let radius = CELL * 0.3;
+                        writeln!(
+                            f,
+                            r##"  <circle cx="{center_x}" cy="{center_y}" r="{radius}" fill="none" stroke="#4dabf7" stroke-width="10"/>"##
+                        )?;
+                    }
+                }
+            }
+        }
+
+        // The two inner lines each way make up the grid
+        for index in 1..3 {
+            let offset = MARGIN + index as f32 * CELL;
+            let end = MARGIN + 3.0 * CELL;
+            writeln!(
+                f,
+                r##"  <path d="M{offset} {MARGIN}V{end}M{MARGIN} {offset}H{end}" stroke="#cccccc" stroke-width="4" stroke-linecap="round"/>"##
+            )?;
+        }
+
+        if let Some(line) = winning_line(board) {
+            let cells = line.cells();
+            let (first, last) = (cells[0], cells[cells.len() - 1]);
+            let (start_x, start_y) = center(first.row(), first.col());
+            let (end_x, end_y) = center(last.row(), last.col());
+            writeln!(
+                f,
+                r##"  <line x1="{start_x}" y1="{start_y}" x2="{end_x}" y2="{end_y}" stroke="#ffd43b" stroke-width="8" stroke-linecap="round" opacity="0.9"/>"##
+            )?;
+        }
+
+        writeln!(f, "</svg>")
+    }
+}
+
+/// Returns the cells of a line filled by one player, if there is one.
+///
+/// Only straight lines are found, so a line that wraps around a toroidal
+/// board is not struck through.
+fn winning_line(board: &Board) -> Option<WinLine> {
+    [Player::X, Player::O]
+        .into_iter()
+        .find_map(|player| find_line(board, player, Topology::Flat))
+}
+
+/// Returns the top-left corner of a cell.
+fn corner(row: usize, col: usize) -> (f32, f32) {
+    (MARGIN + col as f32 * CELL, MARGIN + row as f32 * CELL)
+}
+
+/// Returns the center of a cell.
+fn center(row: usize, col: usize) -> (f32, f32) {
+    let (x, y) = corner(row, col);
+    (x + CELL / 2.0, y + CELL / 2.0)
+}
@@ -0,0 +1,44 @@
+//! The interface shared by every variant.
+//!
+//! Classic play under any [`RuleSet`](crate::rules::RuleSet), Ultimate,
+//! Qubic, and Notakto each implement [`Game`], so code that only needs to
+//! list moves, play them, and see who won, such as the computer's search
+//! in [`crate::ai`], is written once for all of them.
+
+use crate::engine::{MoveError, Player};
+
+/// Where a game stands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    InProgress,
+    Won(Player),
+    Drawn,
+}
+
+/// A turn-based game between X and O.
+pub trait Game: Clone {
+    /// A move in this game, such as a cell or a cell on a particular
+    /// board.
+    type Move: Copy;
+
+    /// Returns the player to move.
+    fn current_player(&self) -> Player;
+
+    /// Lists the moves the player to move may make, none once the game is
+    /// over.
+    fn legal_moves(&self) -> Vec<Self::Move>;
+
+    /// Makes `mv` for the player to move, or says why it is not allowed.
+    fn apply_move(&mut self, mv: Self::Move) -> Result<(), MoveError>;
+
+    /// Returns the game after `mv`, leaving this one as it is, or says why
+    /// the move is not allowed.
+    fn apply(&self, mv: Self::Move) -> Result<Self, MoveError> {
+        let mut next = self.clone();
+        next.apply_move(mv)?;
+        Ok(next)
+    }
+
+    /// Returns whether the game is still going, and if not how it ended.
+    fn status(&self) -> Status;
+}
@@ -0,0 +1,187 @@
+//! Notifications of what happens during a game.
+//!
+//! Frontends report moves, take-backs, results, new games, and changes of
+//! turn as [`GameEvent`]s to their [`Observers`], so features that follow
+//! the game, such as the event log, statistics, or network sync, can
+//! subscribe to them instead of being called from the move logic itself.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::engine::{Player, Position};
+
+/// Something that happened in a game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameEvent {
+    /// A player placed a mark.
+    MovePlayed { player: Player, position: Position },
+    /// A player's mark was taken back.
+    MoveTakenBack { player: Player, position: Position },
+    /// A player lost their turn without placing a mark.
+    TurnForfeited(Player),
+    /// The game ended with a winner, on the board or off it.
+    GameWon(Player),
+    /// The game ended without a winner.
+    Draw,
+    /// A new game started with the given player to move.
+    Reset { first: Player },
+    /// It became the given player's turn.
+    TurnChanged(Player),
+}
+
+/// Callbacks subscribed to a game's events.
+///
+/// Each callback is handed the frontend's state `T` along with the event,
+/// so subscribers can keep their own records on it, and can emit further
+/// events from it.
+pub struct Observers<T> {
+    callbacks: Vec<Box<dyn FnMut(&mut T, &GameEvent)>>,
+    /// Events waiting to be delivered, after the one being delivered now.
+    queued: VecDeque<GameEvent>,
+    /// Whether an event is being delivered, with the callbacks taken out.
+    delivering: bool,
+}
+
+impl<T> Observers<T> {
+    /// Calls `callback` with every event emitted from now on.
+    pub fn subscribe(&mut self, callback: impl FnMut(&mut T, &GameEvent) + 'static) {
+        self.callbacks.push(Box::new(callback));
+    }
+
+    /// Passes `event` to every subscriber of the observers `observers`
+    /// finds on `target`, in the order they subscribed.
+    ///
+    /// An event a subscriber emits while another is being delivered is
+    /// queued and delivered once every subscriber has had the first, so
+    /// each subscriber sees events in the order they were emitted.
+    pub fn emit(target: &mut T, event: GameEvent, observers: fn(&mut T) -> &mut Observers<T>) {
+        let this = observers(target);
+        this.queued.push_back(event);
+        if this.delivering {
+            return;
+        }
+        this.delivering = true;
+        let mut callbacks = std::mem::take(&mut this.callbacks);
+        while let Some(event) = observers(target).queued.pop_front() {
+            for callback in &mut callbacks {
+                callback(target, &event);
+            }
+        }
+        // Subscribers added during delivery go after the others
+        let this = observers(target);
+        callbacks.append(&mut this.callbacks);
+        this.callbacks = callbacks;
+        this.delivering = false;
+    }
+}
+
+impl<T> Default for Observers<T> {
+    fn default() -> Self {
+        Observers {
+            callbacks: Vec::new(),
+            queued: VecDeque::new(),
+            delivering: false,
+        }
+    }
+}
+
+impl<T> fmt::Debug for Observers<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Observers")
+            .field("subscribers", &self.callbacks.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A frontend that notes which subscriber saw each event.
+    #[derive(Default)]
+    struct Frontend {
+        observers: Observers<Frontend>,
+        seen: Vec<(usize, GameEvent)>,
+    }
+
+    fn observers(frontend: &mut Frontend) -> &mut Observers<Frontend> {
+        &mut frontend.observers
+    }
+
+    const MOVE: GameEvent = GameEvent::MovePlayed {
+        player: Player::X,
+        position: Position::CENTER,
+    };
+
+    #[test]
+    fn subscribers_see_events_in_order() {
+        let mut frontend = Frontend::default();
+        for index in 0..2 {
+            frontend
+                .observers
+                .subscribe(move |frontend: &mut Frontend, event| {
+                    frontend.seen.push((index, *event))
+                });
+        }
+        Observers::emit(&mut frontend, MOVE, observers);
+        Observers::emit(&mut frontend, GameEvent::Draw, observers);
+        assert_eq!(
+            frontend.seen,
+            vec![
+                (0, MOVE),
+                (1, MOVE),
+                (0, GameEvent::Draw),
+                (1, GameEvent::Draw)
+            ]
+        );
+    }
+
+    #[test]
+    fn an_event_emitted_by_a_subscriber_follows_the_current_one() {
+        let mut frontend = Frontend::default();
+        frontend
+            .observers
+            .subscribe(|frontend: &mut Frontend, event| {
+                frontend.seen.push((0, *event));
+                if let GameEvent::MovePlayed { player, .. } = *event {
+                    Observers::emit(frontend, GameEvent::TurnChanged(player.other()), observers);
+                }
+            });
+        frontend
+            .observers
+            .subscribe(|frontend: &mut Frontend, event| frontend.seen.push((1, *event)));
+        Observers::emit(&mut frontend, MOVE, observers);
+        let turn = GameEvent::TurnChanged(Player::O);
+        assert_eq!(
+            frontend.seen,
+            vec![(0, MOVE), (1, MOVE), (0, turn), (1, turn)]
+        );
+
+        // The subscribers are back in place for the next event
+        Observers::emit(&mut frontend, GameEvent::Draw, observers);
+        assert_eq!(frontend.seen.len(), 6);
+    }
+
+    #[test]
+    fn a_subscriber_added_during_delivery_sees_later_events() {
+        let mut frontend = Frontend::default();
+        frontend
+            .observers
+            .subscribe(|frontend: &mut Frontend, event| {
+                frontend.seen.push((0, *event));
+                if *event == MOVE {
+                    frontend
+                        .observers
+                        .subscribe(|frontend: &mut Frontend, event| {
+                            frontend.seen.push((1, *event))
+                        });
+                }
+            });
+        Observers::emit(&mut frontend, MOVE, observers);
+        Observers::emit(&mut frontend, GameEvent::Draw, observers);
+        assert_eq!(
+            frontend.seen,
+            vec![(0, MOVE), (0, GameEvent::Draw), (1, GameEvent::Draw)]
+        );
+    }
+}
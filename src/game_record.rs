@@ -0,0 +1,196 @@
+//! Saved games.
+//!
+//! A game is written as JSON holding the rules, the position it started
+//! from, and every move played, so loading it restores the board, the
+//! turn, and the full history for the timeline and replay view. Any
+//! annotations made on the moves in the replay view are saved with it.
+//!
+//! An unfinished game is also autosaved to the data directory after every
+//! move so it can be resumed on the next launch.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use directories::{ProjectDirs, UserDirs};
+use serde::{Deserialize, Serialize};
+
+use tic_tac_toe::protocol::{Message, RecordedGame};
+
+use crate::annotation::Annotation;
+use crate::game_result::GameResult;
+use crate::rules::RuleSet;
+use crate::{Board, GameState, Outcome, Player, Position};
+
+/// File name suggested when saving a game.
+pub const DEFAULT_FILE_NAME: &str = "tic-tac-toe.json";
+
+/// Everything needed to pick a game back up.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "SavedRecord")]
+pub struct GameRecord {
+    /// The rules the game was played under.
+    pub rules: RuleSet,
+    /// The position the game started from.
+    pub initial_board: Board,
+    /// The player who moved first.
+    pub starting_player: Player,
+    /// Seed of the coin flip that chose [`Self::starting_player`], if one
+    /// did, so the flip can be shown again exactly.
+    pub coin_seed: Option<u64>,
+    /// Moves played, in order.
+    pub moves: Vec<(usize, usize, Player)>,
+    /// The player to move next. Blind-mode forfeits pass the turn without
+    /// a move, so this cannot always be derived from the moves.
+    pub current_player: Player,
+    /// How the game ended, or `None` if it is unfinished.
+    pub result: Option<GameResult>,
+    /// Annotations on the moves, by the index of the move in
+    /// [`Self::moves`].
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<usize, Annotation>,
+}
+
+/// A game as saved, including games saved before the rules were stored,
+/// which were played under the standard rules, and before results were
+/// stored whole, which give the result as the fields below it.
+#[derive(Deserialize)]
+struct SavedRecord {
+    #[serde(default)]
+    rules: RuleSet,
+    initial_board: Board,
+    starting_player: Player,
+    #[serde(default)]
+    coin_seed: Option<u64>,
+    moves: Vec<(usize, usize, Player)>,
+    current_player: Player,
+    #[serde(default)]
+    result: Option<GameResult>,
+    #[serde(default)]
+    game_over: bool,
+    #[serde(default)]
+    winner: Option<Player>,
+    #[serde(default)]
+    resigned: bool,
+    #[serde(default)]
+    annotations: BTreeMap<usize, Annotation>,
+}
+
+impl From<SavedRecord> for GameRecord {
+    fn from(saved: SavedRecord) -> Self {
+        Self {
+            rules: saved.rules,
+            initial_board: saved.initial_board,
+            starting_player: saved.starting_player,
+            coin_seed: saved.coin_seed,
+            moves: saved.moves,
+            current_player: saved.current_player,
+            result: saved
+                .result
+                .or_else(|| GameResult::from_parts(saved.game_over, saved.winner, saved.resigned)),
+            annotations: saved.annotations,
+        }
+    }
+}
+
+impl From<RecordedGame> for GameRecord {
+    /// Reads a game the relay recorded, replaying its moves for the turn
+    /// and result.
+    fn from(game: RecordedGame) -> Self {
+        let mut state = GameState::new(game.first, game.board, game.rules);
+        for &(row, col, player) in &game.moves {
+            if let Some(position) = Position::new(row, col) {
+                if state.to_move() != player {
+                    state.pass();
+                }
+                let _ = state.play(position);
+            }
+        }
+        let result = match (state.outcome(), game.ending) {
+            (Outcome::Won(winner, _), _) => Some(GameResult::Win(winner)),
+            (Outcome::Draw, _) => Some(GameResult::Draw),
+            (Outcome::InProgress, Some(Message::Resign { player })) => {
+                Some(GameResult::Resignation {
+                    winner: player.other(),
+                })
+            }
+            (Outcome::InProgress, Some(Message::DrawAnswer { accepted: true })) => {
+                Some(GameResult::AgreedDraw)
+            }
+            (Outcome::InProgress, _) => None,
+        };
+        Self {
+            rules: game.rules,
+            initial_board: game.board,
+            starting_player: game.first,
+            coin_seed: None,
+            moves: game.moves,
+            current_player: state.to_move(),
+            result,
+            annotations: BTreeMap::new(),
+        }
+    }
+}
+
+impl GameRecord {
+    /// Returns the winner, if the game ended with one.
+    pub fn winner(&self) -> Option<Player> {
+        self.result.and_then(GameResult::winner)
+    }
+
+    /// Describes how the game ended, such as "X wins by resignation", or
+    /// says it is unfinished.
+    pub fn describe_result(&self) -> String {
+        self.result
+            .map_or_else(|| "Unfinished".to_string(), GameResult::describe)
+    }
+
+    /// Reads a saved game from `path`.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Writes the game to `path`.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json)
+    }
+
+    /// Loads the autosaved unfinished game, if there is one.
+    pub fn load_autosave() -> Option<Self> {
+        Self::load(&autosave_path()?).ok()
+    }
+
+    /// Autosaves the game, ignoring failures.
+    pub fn autosave(&self) {
+        let Some(path) = autosave_path() else {
+            return;
+        };
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let _ = self.save(&path);
+    }
+}
+
+/// Removes the autosaved game once there is nothing left to resume.
+pub fn clear_autosave() {
+    if let Some(path) = autosave_path() {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// Location of the autosaved game in the platform data directory.
+fn autosave_path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "tic-tac-toe").map(|dirs| dirs.data_dir().join("autosave.json"))
+}
+
+/// Directory the save dialog opens in: the documents folder, falling back
+/// to the home directory.
+pub fn default_dir() -> PathBuf {
+    UserDirs::new()
+        .map(|dirs| dirs.document_dir().unwrap_or(dirs.home_dir()).to_path_buf())
+        .unwrap_or_default()
+}
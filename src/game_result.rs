@@ -0,0 +1,63 @@
+//! How a finished game ended.
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::Player;
+
+/// The outcome of a finished game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameResult {
+    /// A line decided the game for `winner`. Under misère rules the line
+    /// was the loser's.
+    Win(Player),
+    /// The board filled up without a decisive line.
+    Draw,
+    /// The loser conceded to `winner`.
+    Resignation { winner: Player },
+    /// Both players agreed to a draw.
+    AgreedDraw,
+    /// The loser's clock ran out.
+    Timeout { winner: Player },
+}
+
+impl GameResult {
+    /// Returns the winner, or `None` for either kind of draw.
+    pub fn winner(self) -> Option<Player> {
+        match self {
+            GameResult::Win(winner)
+            | GameResult::Resignation { winner }
+            | GameResult::Timeout { winner } => Some(winner),
+            GameResult::Draw | GameResult::AgreedDraw => None,
+        }
+    }
+
+    /// Describes the result, such as "X wins by resignation".
+    pub fn describe(self) -> String {
+        match self {
+            GameResult::Win(winner) => format!("{winner:?} wins"),
+            GameResult::Draw => "Draw".to_string(),
+            GameResult::Resignation { winner } => format!("{winner:?} wins by resignation"),
+            GameResult::AgreedDraw => "Draw by agreement".to_string(),
+            GameResult::Timeout { winner } => format!("{winner:?} wins on time"),
+        }
+    }
+
+    /// Whether the game ended away from the board, by resignation,
+    /// agreement, or the clock. Such results stand and cannot be undone.
+    pub fn is_decided_off_board(self) -> bool {
+        !matches!(self, GameResult::Win(_) | GameResult::Draw)
+    }
+
+    /// Reads the result from the fields of a saved game that predates
+    /// results being stored whole.
+    pub fn from_parts(game_over: bool, winner: Option<Player>, resigned: bool) -> Option<Self> {
+        if !game_over {
+            return None;
+        }
+        Some(match (winner, resigned) {
+            (Some(winner), true) => GameResult::Resignation { winner },
+            (Some(winner), false) => GameResult::Win(winner),
+            (None, _) => GameResult::Draw,
+        })
+    }
+}
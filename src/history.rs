@@ -0,0 +1,328 @@
+//! Game history database.
+//!
+//! Every completed game is recorded in a SQLite database in the data
+//! directory, with who played it, the rules, the full game record, the
+//! result, and how long it took. The History screen lists these games,
+//! newest first, filtered by player and result, above a heatmap of how
+//! games went from each first move. An online game the relay recorded
+//! keeps a link to the relay's copy, to replay it from there.
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, Local};
+use directories::ProjectDirs;
+use rusqlite::{params, Connection};
+
+use crate::csv_export::CsvExport;
+use crate::game_record::GameRecord;
+use crate::Player;
+
+/// Most games the History screen lists at once.
+const LIST_LIMIT: usize = 200;
+
+/// A completed game as stored in the history.
+#[derive(Clone, Debug)]
+pub struct HistoryEntry {
+    /// Row id of the game in the database, or `None` before it is first
+    /// stored.
+    pub id: Option<i64>,
+    /// Name of the player who had X.
+    pub x_player: String,
+    /// Name of the player who had O.
+    pub o_player: String,
+    /// The rules the game was played under, such as "Standard".
+    pub variant: String,
+    /// The full game, for replaying or exporting it.
+    pub record: GameRecord,
+    /// Seconds the game took, counting only the players' turns.
+    pub duration_secs: u64,
+    /// When the game finished.
+    pub played_at: DateTime<Local>,
+    /// Link to the copy of the game the relay recorded, if it did.
+    pub recording: Option<String>,
+}
+
+/// How the games that opened on one cell went for the player who opened
+/// them.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct OpeningStats {
+    pub games: u32,
+    pub wins: u32,
+}
+
+impl OpeningStats {
+    /// Returns the share of these games the opener won, or `None` when
+    /// there are none.
+    pub fn win_rate(&self) -> Option<f32> {
+        (self.games > 0).then(|| self.wins as f32 / self.games as f32)
+    }
+}
+
+/// Which results the History screen shows.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ResultFilter {
+    #[default]
+    All,
+    XWins,
+    OWins,
+    Draws,
+}
+
+impl ResultFilter {
+    /// Returns the next filter, wrapping from Draws back to All.
+    pub fn next(self) -> Self {
+        match self {
+            ResultFilter::All => ResultFilter::XWins,
+            ResultFilter::XWins => ResultFilter::OWins,
+            ResultFilter::OWins => ResultFilter::Draws,
+            ResultFilter::Draws => ResultFilter::All,
+        }
+    }
+
+    /// Whether a game won by `winner`, or drawn when `None`, passes the
+    /// filter.
+    fn matches(self, winner: Option<Player>) -> bool {
+        match self {
+            ResultFilter::All => true,
+            ResultFilter::XWins => winner == Some(Player::X),
+            ResultFilter::OWins => winner == Some(Player::O),
+            ResultFilter::Draws => winner.is_none(),
+        }
+    }
+}
+
+/// The history database.
+pub struct History {
+    connection: Connection,
+}
+
+impl History {
+    /// Opens the history database, creating it on first use.
+    pub fn open() -> Option<Self> {
+        let path = history_path()?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).ok()?;
+        }
+        let connection = Connection::open(path).ok()?;
+        connection
+            .execute(
+                "CREATE TABLE IF NOT EXISTS games (
+                    id INTEGER PRIMARY KEY,
+                    x_player TEXT NOT NULL,
+                    o_player TEXT NOT NULL,
+                    variant TEXT NOT NULL,
+                    record TEXT NOT NULL,
+                    duration_secs INTEGER NOT NULL,
+                    played_at TEXT NOT NULL
+                )",
+                [],
+            )
+            .ok()?;
+        // Databases made before recordings lack their column, and adding
+        // it to one that has it fails harmlessly
+        let _ = connection.execute("ALTER TABLE games ADD COLUMN recording TEXT", []);
+        Some(Self { connection })
+    }
+
+    /// Stores a completed game, returning its row id.
+    ///
+    /// An entry with the id of a game already stored replaces it, which
+    /// keeps a game that was taken back and finished again from being
+    /// recorded twice.
+    pub fn record(&self, entry: &HistoryEntry) -> rusqlite::Result<i64> {
+        let record = serde_json::to_string(&entry.record)
+            .map_err(|error| rusqlite::Error::ToSqlConversionFailure(error.into()))?;
+        self.connection.execute(
+            "INSERT OR REPLACE INTO games
+                (id, x_player, o_player, variant, record, duration_secs, played_at, recording)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                entry.id,
+                entry.x_player,
+                entry.o_player,
+                entry.variant,
+                record,
+                entry.duration_secs,
+                entry.played_at.to_rfc3339(),
+                entry.recording,
+            ],
+        )?;
+        Ok(self.connection.last_insert_rowid())
+    }
+
+    /// Links the stored game with row id `id` to the relay's recording of
+    /// it.
+    pub fn set_recording(&self, id: i64, link: &str) -> rusqlite::Result<()> {
+        self.connection.execute(
+            "UPDATE games SET recording = ?1 WHERE id = ?2",
+            params![link, id],
+        )?;
+        Ok(())
+    }
+
+    /// Lists up to [`LIST_LIMIT`] stored games, newest first, played by
+    /// `player` (when given) and with a result passing `result`.
+    pub fn list(
+        &self,
+        player: Option<&str>,
+        result: ResultFilter,
+    ) -> rusqlite::Result<Vec<HistoryEntry>> {
+        self.query(player, result, Some(LIST_LIMIT))
+    }
+
+    /// Lists every stored game passing the same filters as [`Self::list`],
+    /// for exporting.
+    pub fn list_all(
+        &self,
+        player: Option<&str>,
+        result: ResultFilter,
+    ) -> rusqlite::Result<Vec<HistoryEntry>> {
+        self.query(player, result, None)
+    }
+
+    fn query(
+        &self,
+        player: Option<&str>,
+        result: ResultFilter,
+        limit: Option<usize>,
+    ) -> rusqlite::Result<Vec<HistoryEntry>> {
+        let mut statement = self.connection.prepare(
+            "SELECT id, x_player, o_player, variant, record, duration_secs, played_at, recording
+                FROM games
+                WHERE ?1 IS NULL OR x_player = ?1 OR o_player = ?1
+                ORDER BY played_at DESC",
+        )?;
+        let rows = statement.query_map(params![player], |row| {
+            let record: String = row.get(4)?;
+            let played_at: String = row.get(6)?;
+            Ok(HistoryEntry {
+                id: row.get(0)?,
+                x_player: row.get(1)?,
+                o_player: row.get(2)?,
+                variant: row.get(3)?,
+                record: serde_json::from_str(&record).map_err(|error| {
+                    rusqlite::Error::FromSqlConversionFailure(
+                        4,
+                        rusqlite::types::Type::Text,
+                        error.into(),
+                    )
+                })?,
+                duration_secs: row.get(5)?,
+                played_at: DateTime::parse_from_rfc3339(&played_at)
+                    .map(|time| time.with_timezone(&Local))
+                    .unwrap_or_default(),
+                recording: row.get(7)?,
+            })
+        })?;
+
+        let mut entries = Vec::new();
+        for entry in rows {
+            let entry = entry?;
+            if result.matches(entry.record.winner()) {
+                entries.push(entry);
+            }
+            if Some(entries.len()) == limit {
+                break;
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Tallies every stored game by the cell it opened on, counting the
+    /// games `player` opened when given, or all games otherwise, and how
+    /// many of them the opener won.
+    pub fn openings(&self, player: Option<&str>) -> rusqlite::Result<[[OpeningStats; 3]; 3]> {
+        let mut statement = self.connection.prepare(
+            "SELECT x_player, o_player, record
+                FROM games
+                WHERE ?1 IS NULL OR x_player = ?1 OR o_player = ?1",
+        )?;
+        let rows = statement.query_map(params![player], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+
+        let mut openings = [[OpeningStats::default(); 3]; 3];
+        for row in rows {
+            let (x_player, o_player, record) = row?;
+            // Games whose record no longer reads are left out
+            let Ok(record) = serde_json::from_str::<GameRecord>(&record) else {
+                continue;
+            };
+            let Some(&(row, col, opener)) = record.moves.first() else {
+                continue;
+            };
+            let name = match opener {
+                Player::X => &x_player,
+                Player::O => &o_player,
+            };
+            if player.is_some_and(|player| player != name.as_str()) {
+                continue;
+            }
+            let stats = &mut openings[row][col];
+            stats.games += 1;
+            if record.winner() == Some(opener) {
+                stats.wins += 1;
+            }
+        }
+        Ok(openings)
+    }
+
+    /// Lists every player name that appears in the history, sorted.
+    pub fn players(&self) -> rusqlite::Result<Vec<String>> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT x_player FROM games UNION SELECT o_player FROM games ORDER BY 1")?;
+        let names = statement.query_map([], |row| row.get(0))?;
+        names.collect()
+    }
+}
+
+/// Location of the history database in the platform data directory.
+pub fn history_path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "tic-tac-toe").map(|dirs| dirs.data_dir().join("history.sqlite3"))
+}
+
+/// The History screen's filters and the games passing them.
+#[derive(Debug, Default)]
+pub struct HistoryView {
+    /// Only games this player took part in, or every game when `None`.
+    pub player: Option<String>,
+    /// Which results are shown.
+    pub result: ResultFilter,
+    /// Every player in the history, for cycling the player filter.
+    pub players: Vec<String>,
+    /// The games passing the filters, newest first.
+    pub entries: Vec<HistoryEntry>,
+    /// How games went from each first move, for the player filter.
+    pub openings: [[OpeningStats; 3]; 3],
+    /// The columns and delimiter for exporting the games as CSV.
+    pub csv: CsvExport,
+}
+
+impl HistoryView {
+    /// Reloads the player list and the games passing the filters.
+    pub fn refresh(&mut self, history: &History) {
+        self.players = history.players().unwrap_or_default();
+        self.entries = history
+            .list(self.player.as_deref(), self.result)
+            .unwrap_or_default();
+        self.openings = history.openings(self.player.as_deref()).unwrap_or_default();
+    }
+
+    /// Moves the player filter to the next player, then back to everyone.
+    pub fn next_player(&mut self) {
+        let next = match &self.player {
+            None => 0,
+            Some(current) => self
+                .players
+                .iter()
+                .position(|name| name == current)
+                .map_or(self.players.len(), |index| index + 1),
+        };
+        self.player = self.players.get(next).cloned();
+    }
+}
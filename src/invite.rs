@@ -0,0 +1,43 @@
+//! Invite links for network games.
+//!
+//! A host shows its invite as text and as a QR code. The link names
+//! either a LAN host, like `tictactoe://lan#192.168.1.5:7878`, or an
+//! online room, like `tictactoe://room#K7M2QX`; pasting it joins the game.
+
+use std::fmt;
+use std::net::SocketAddr;
+
+use crate::online;
+
+/// Text LAN invites start with.
+const LAN_PREFIX: &str = "tictactoe://lan#";
+
+/// Text online room invites start with.
+const ROOM_PREFIX: &str = "tictactoe://room#";
+
+/// Where to join a game.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Invite {
+    /// A game hosted on the local network.
+    Lan(SocketAddr),
+    /// An online room, by its code.
+    Room(String),
+}
+
+impl fmt::Display for Invite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Invite::Lan(address) => write!(f, "{LAN_PREFIX}{address}"),
+            Invite::Room(code) => write!(f, "{ROOM_PREFIX}{code}"),
+        }
+    }
+}
+
+/// Reads an invite link, or returns `None` if `text` is not one.
+pub fn parse(text: &str) -> Option<Invite> {
+    let text = text.trim();
+    if let Some(address) = text.strip_prefix(LAN_PREFIX) {
+        return address.parse().ok().map(Invite::Lan);
+    }
+    online::parse_room_code(text.strip_prefix(ROOM_PREFIX)?).map(Invite::Room)
+}
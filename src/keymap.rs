@@ -0,0 +1,180 @@
+//! Keyboard shortcuts.
+//!
+//! Every shortcut is declared once here, together with the group and
+//! description shown in the shortcut overlay, so the overlay always lists
+//! exactly the bindings that are registered.
+
+use gpui::{Action, App, KeyBinding};
+
+use crate::{
+    CopyPosition, CycleFocus, FocusDown, FocusUp, LoadGame, NewGame, PastePosition, PlayCell1,
+    PlayCell2, PlayCell3, PlayCell4, PlayCell5, PlayCell6, PlayCell7, PlayCell8, PlayCell9,
+    PlayFocused, Redo, Rematch, Reset, SaveGame, SaveScreenshot, StepBack, StepForward, ToggleChat,
+    ToggleEventLog, TogglePin, ToggleSettings, ToggleShortcuts, Undo,
+};
+
+/// Key context set on the game view; all shortcuts are bound within it.
+pub const KEY_CONTEXT: &str = "TicTacToe";
+
+/// A registered shortcut and how it is described to the player.
+pub struct Shortcut {
+    /// Overlay section the shortcut is listed under.
+    pub group: &'static str,
+    /// Keystroke in GPUI's binding syntax, such as `secondary-n`.
+    pub keystroke: &'static str,
+    /// What the shortcut does.
+    pub description: &'static str,
+    binding: KeyBinding,
+}
+
+impl Shortcut {
+    fn new(
+        group: &'static str,
+        keystroke: &'static str,
+        description: &'static str,
+        action: impl Action,
+    ) -> Self {
+        Self {
+            group,
+            keystroke,
+            description,
+            binding: KeyBinding::new(keystroke, action, Some(KEY_CONTEXT)),
+        }
+    }
+
+    /// Formats the keystroke for display, such as `Ctrl+N`.
+    pub fn display_keystroke(&self) -> String {
+        self.keystroke
+            .split('-')
+            .map(|key| match key {
+                "secondary" if cfg!(target_os = "macos") => "Cmd".to_string(),
+                "secondary" | "ctrl" => "Ctrl".to_string(),
+                "shift" => "Shift".to_string(),
+                "alt" => "Alt".to_string(),
+                "escape" => "Esc".to_string(),
+                "left" => "Left".to_string(),
+                "right" => "Right".to_string(),
+                "up" => "Up".to_string(),
+                "down" => "Down".to_string(),
+                "enter" => "Enter".to_string(),
+                "space" => "Space".to_string(),
+                "tab" => "Tab".to_string(),
+                key => key.to_uppercase(),
+            })
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+}
+
+/// Returns every shortcut, in the order shown in the overlay.
+pub fn shortcuts() -> Vec<Shortcut> {
+    vec![
+        Shortcut::new("Board", "secondary-n", "New game", NewGame),
+        Shortcut::new(
+            "Board",
+            "secondary-shift-n",
+            "Rematch with sides swapped",
+            Rematch,
+        ),
+        Shortcut::new("Board", "r", "New game", Reset),
+        Shortcut::new("Board", "7", "Play the top-left cell", PlayCell7),
+        Shortcut::new("Board", "8", "Play the top cell", PlayCell8),
+        Shortcut::new("Board", "9", "Play the top-right cell", PlayCell9),
+        Shortcut::new("Board", "4", "Play the left cell", PlayCell4),
+        Shortcut::new("Board", "5", "Play the center cell", PlayCell5),
+        Shortcut::new("Board", "6", "Play the right cell", PlayCell6),
+        Shortcut::new("Board", "1", "Play the bottom-left cell", PlayCell1),
+        Shortcut::new("Board", "2", "Play the bottom cell", PlayCell2),
+        Shortcut::new("Board", "3", "Play the bottom-right cell", PlayCell3),
+        Shortcut::new("Board", "secondary-z", "Undo the last move", Undo),
+        Shortcut::new("Board", "secondary-y", "Redo an undone move", Redo),
+        Shortcut::new(
+            "Board",
+            "secondary-c",
+            "Copy the position as text",
+            CopyPosition,
+        ),
+        Shortcut::new(
+            "Board",
+            "secondary-v",
+            "Paste a position, share link, or invite",
+            PastePosition,
+        ),
+        Shortcut::new(
+            "Replay",
+            "left",
+            "Step back through the timeline, or move the focus left",
+            StepBack,
+        ),
+        Shortcut::new(
+            "Replay",
+            "right",
+            "Step forward through the timeline, or move the focus right",
+            StepForward,
+        ),
+        Shortcut::new("Focus", "up", "Move the focus up", FocusUp),
+        Shortcut::new("Focus", "down", "Move the focus down", FocusDown),
+        Shortcut::new(
+            "Focus",
+            "tab",
+            "Move the focus between the board and Play Again",
+            CycleFocus,
+        ),
+        Shortcut::new(
+            "Focus",
+            "shift-tab",
+            "Move the focus between the board and Play Again",
+            CycleFocus,
+        ),
+        Shortcut::new(
+            "Focus",
+            "enter",
+            "Play the focused cell or press Play Again",
+            PlayFocused,
+        ),
+        Shortcut::new(
+            "Focus",
+            "space",
+            "Play the focused cell or press Play Again",
+            PlayFocused,
+        ),
+        Shortcut::new(
+            "Menus",
+            "escape",
+            "Open or close the settings menu",
+            ToggleSettings,
+        ),
+        Shortcut::new("Menus", "secondary-s", "Save the game", SaveGame),
+        Shortcut::new("Menus", "secondary-o", "Load a saved game", LoadGame),
+        Shortcut::new(
+            "Menus",
+            "secondary-shift-s",
+            "Save a screenshot of the board",
+            SaveScreenshot,
+        ),
+        Shortcut::new(
+            "Menus",
+            "secondary-l",
+            "Show or hide the event log",
+            ToggleEventLog,
+        ),
+        Shortcut::new(
+            "Menus",
+            "secondary-shift-c",
+            "Show or hide the network game chat",
+            ToggleChat,
+        ),
+        Shortcut::new(
+            "Menus",
+            "secondary-shift-t",
+            "Pin the window on top",
+            TogglePin,
+        ),
+        Shortcut::new("Menus", "?", "Show or hide this list", ToggleShortcuts),
+    ]
+}
+
+/// Registers every shortcut with the application.
+pub fn bind_keys(cx: &mut App) {
+    cx.bind_keys(shortcuts().into_iter().map(|shortcut| shortcut.binding));
+}
@@ -0,0 +1,15 @@
+//! The game engine behind Tic Tac Toe.
+//!
+//! The rules, the [`game::Game`] interface every variant implements, the
+//! computer opponent, game results, game events, and the network protocol
+//! live here apart from the GPUI frontend in `main.rs`, so they can be
+//! tested on their own and reused by other frontends and the relay
+//! server.
+
+pub mod ai;
+pub mod engine;
+pub mod game;
+pub mod game_event;
+pub mod game_result;
+pub mod protocol;
+pub mod rules;
@@ -0,0 +1,43 @@
+//! Diagnostic logging.
+//!
+//! Moves, computer searches, rendering, and network traffic are traced to
+//! a log file in the data directory, so a bug report can attach what led
+//! up to the problem. A new file is started each day and only the last
+//! week of files is kept. `--log-level` picks how much is written.
+
+use std::path::PathBuf;
+
+use directories::ProjectDirs;
+use tracing::level_filters::LevelFilter;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+/// Number of daily log files kept before the oldest is deleted.
+const MAX_LOG_FILES: usize = 7;
+
+/// Starts writing log records at `level` and above to the log file.
+///
+/// Records are written on a background thread, which flushes them once
+/// the returned guard is dropped, so it must be held until the program
+/// exits. Returns `None` when there is no data directory to log to, in
+/// which case nothing is logged.
+pub fn init(level: LevelFilter) -> Option<WorkerGuard> {
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("tic-tac-toe")
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(log_dir()?)
+        .ok()?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    tracing_subscriber::fmt()
+        .with_writer(writer)
+        .with_ansi(false)
+        .with_max_level(level)
+        .init();
+    Some(guard)
+}
+
+fn log_dir() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "tic-tac-toe").map(|dirs| dirs.data_dir().join("logs"))
+}
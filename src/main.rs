@@ -4,261 +4,7768 @@
 //!
 //! ## Features
 //! - Two-player gameplay (X and O)
+//! - Single-player mode against a minimax opponent with three difficulty
+//!   levels, which thinks in the background
 //! - Win detection for rows, columns, and diagonals
 //! - Draw detection
-//! - Visual feedback with colored cells
+//! - Visual feedback with colored cells, and a note saying why a click
+//!   on the board was refused
+//! - Outline around the line that decided the game
+//! - Hint button that marks the best move with a pulsing border
 //! - Reset button to play again
+//! - Undo and redo, from buttons or the keyboard
+//! - Resign button, with confirmation, conceding the game to the opponent
+//! - Confirmation before abandoning a game in progress, which can be
+//!   turned off with "Don't ask again"
+//! - Rematch button that gives the other player the first move, agreed
+//!   by both sides in a network game, which keeps a running score
+//! - Player cards with a pulsing marker on the side to move, and the time
+//!   each player has spent thinking
+//! - Misère rule option where completing a line loses
+//! - Gravity rule option where marks fall to the bottom of their column
+//! - Toroidal rule option where lines wrap around the board's edges
+//! - Blocked cells variant with one to three unusable cells, laid out from
+//!   a seed that can be copied and pasted to share the layout
+//! - Expiring-marks rule option where each player keeps at most three
+//!   marks, with the next to disappear pulsing as a warning
+//! - Optional "loser starts" rule for the next game
+//! - Optional random start decided by an animated, seeded coin flip,
+//!   remembered between launches and saved with the game
+//! - Puzzle Rush: timed tactics puzzles with strikes and a high-score table
+//! - Position editor for setting up and playing from custom positions
+//! - Optional a-c / 1-3 coordinate labels, also used by the move log
+//! - Timeline slider for previewing earlier positions of the game
+//! - Replay view for finished games with first/back/forward/last controls
+//!   and a graph of the engine's evaluation after every move, worked out
+//!   in the background when the replay opens, and a move list where each
+//!   move can be marked `!!`, `!` or `?` and commented on, saved with the
+//!   game and in its notation, and an analysis mode taking over any
+//!   position to try other moves against the engine, keeping the lines
+//!   tried as a tree of variations
+//! - Ghost marks replaying the previous game's moves turn by turn
+//! - Blind mode where marks fade from view a second after being placed
+//! - 2v2 team mode where partners alternate each side's moves
+//! - A flipped board, turned half a turn in this window only, so each
+//!   side of a 2v2 or network game can view it from their end
+//! - Ultimate Tic Tac Toe, played on nine small boards that send each
+//!   move to the board matching the cell just played
+//! - Qubic, played on a 4x4x4 cube drawn as four layers side by side
+//! - Notakto on one to three boards, where both players place X and
+//!   whoever completes the last line loses
+//! - The computer opponent in Ultimate, Qubic, and Notakto, with a
+//!   shallower search than on the classic board
+//! - Pentago, where each turn places a mark and then rotates a quadrant
+//!   of the 6x6 board
+//! - Connect-K on a larger board, four in a row on 6x6 or five on 15x15
+//!   as in Gomoku, against a person or a computer that takes and blocks
+//!   wins
+//! - Simul mode with two boards side by side, against the Easy computer
+//!   on the left and the Hard one on the right, moving on each in turn
+//! - Campaign of six themed computer opponents growing stronger, each with
+//!   a twist to the rules, earning up to three stars per level and saving
+//!   progress with the profile playing X
+//! - Daily Challenge against the computer, the same for everyone, whose
+//!   rules, blocked cells, difficulty, and first player are picked from
+//!   the date, with a streak of days completed and each result ranked
+//!   against past dailies
+//! - Speedrun mode beating the computer on Easy, Medium, and Hard in turn,
+//!   with the best time for each split kept and a live delta against it
+//! - Best-of-3, 5, or 7 match series with a running score, alternating
+//!   who moves first and starting each game automatically
+//! - Optional chess clocks giving each player a time budget, shown either
+//!   side of the board, where running out loses the game, with an
+//!   optional Fischer increment added after every move, in local games
+//! - Blitz per-move time limit with a shrinking progress bar, losing the
+//!   game or playing a random move when time runs out, in local games
+//! - Player profiles with persistent Elo ratings, updated after every rated
+//!   game against another profile or the computer
+//! - Lifetime statistics per profile: results, win streaks, and favorite
+//!   opening square, shown on the pre-game players screen
+//! - Game history database recording every completed game, with a History
+//!   screen filtered by player and result, and a heatmap of the win rate
+//!   from each first move
+//! - CSV export of the filtered history and per-player totals for
+//!   spreadsheets, with a choice of columns and delimiter
+//! - Export of the current or any past game in a portable `.ttt` text
+//!   notation with PGN-style headers
+//! - Copy and paste of positions as short text like `X.O.X.... O`, with
+//!   pasted positions checked before play
+//! - Share links like `tictactoe://game#BgAUARgC` that pack a whole game
+//!   into a short URL, copied with a button and opened for review by
+//!   pasting them
+//! - Hosted replays: a finished game uploaded to the relay server, with a
+//!   `tictactoe://replay/` link copied that opens it in the replay view of
+//!   whoever pastes it
+//! - The window title showing while the computer thinks or the network is
+//!   waited on, and marking a turn that came up while the window was in
+//!   the background, so it can be seen from the taskbar or dock
+//! - Opt-in recording of finished online games on the relay server, each
+//!   replayable from its entry on the History screen
+//! - Import of `.ttt` files straight into the replay view, with the line
+//!   of any error in malformed files
+//! - LAN play over TCP, hosting on one machine and joining from another
+//!   by its address
+//! - Discovery of LAN games over mDNS, listing nearby hosts such as
+//!   "Alice's game – 192.168.1.5" to join with a click
+//! - Online play through a WebSocket relay, from a lobby where one player
+//!   creates a room and shares its six-character code for the other to
+//!   type in, with the connection and the opponent's presence shown above
+//!   the board
+//! - Bundled relay server (`cargo run --bin server`) that pairs players in
+//!   rooms and checks every move before passing it on
+//! - Chat for LAN and online games in a collapsible panel, with the time
+//!   of each line and one-click 👍, 😮, and GG buttons
+//! - Offer Draw and Resign buttons during play, with agreed draws,
+//!   resignations, and timeouts told apart in the result
+//! - Takeback requests in network games, which the opponent accepts or
+//!   declines in a dialog before the move is taken back on both sides
+//! - Invite links and QR codes for hosted LAN games and online rooms,
+//!   joined by pasting the link
+//! - A versioned network protocol, with a dialog explaining when the
+//!   other side runs an incompatible version of the game
+//! - Automatic rejoining of an online room after the connection drops,
+//!   retrying with a growing delay and picking the game up from the
+//!   relay's copy of the moves
+//! - Spectators for online rooms, who watch by room code and see every
+//!   move live without being able to play, counted in a badge the players
+//!   see
+//! - Round-robin tournaments for 3-8 local players with standings
+//! - Single-elimination brackets for 4 or 8 local players, drawn as a tree
+//!   with a champion screen at the end
+//! - Collapsible, timestamped log of moves and results, resized by
+//!   dragging the splitter above it
+//! - Keyboard shortcuts, listed in an overlay by pressing `?`
+//! - Mouse-free play: numpad keys 1–9 place marks by their position on
+//!   the board, `R` starts a new game, and `Esc` opens the settings menu
+//! - Keyboard focus ring moved over the board with the arrow keys, with
+//!   Enter or Space to play the focused cell and Tab to reach Play Again
+//! - Settings panel, saved to a TOML file and applied at startup
+//! - First-run setup wizard choosing the default opponent and the player's
+//!   name, whose profile is seated at X in new sessions
+//! - Export of the settings, profiles, game history, and best scores as a
+//!   single data bundle, and import of one to restore them on another
+//!   machine
+//! - Choice of interface font from the installed fonts, with
+//!   dyslexia-friendly families listed first
+//! - Screenshots of the board saved as PNG images at a fixed size
+//! - SVG export of the board, with the completed line struck through
+//! - Saving games to JSON files and loading them back, with unfinished
+//!   games autosaved and offered for resuming on the next launch
+//! - Pin toggle to keep the window above other windows, on macOS and
+//!   Windows
+//! - Board that scales with the window and remembers its place per display
+//! - Terminal play with `--cli`, against the computer or another player,
+//!   for use over SSH and in scripts
+//! - `simulate` command that plays computer-vs-computer games in parallel
+//!   and prints win, draw, and game length statistics
+//! - `--bot <path>` to play against an external engine in any language
+//!   that speaks a small `position` / `go` / `bestmove` text protocol
+//! - Launch options `--vs-ai easy|medium|hard`, `--load game.ttt`,
+//!   `--size 3|6|15`, and `--fullscreen`, with `--help` listing every
+//!   option; `--theme` only takes `dark`, the one theme there is
+//! - Diagnostic log of moves, computer searches, and network traffic in
+//!   daily rotated files in the data directory, with `--log-level`
 
+mod analysis;
+mod annotation;
+mod args;
+mod blocked;
+mod bot;
+mod bundle;
+mod campaign;
+mod chat;
+mod cli;
+mod coin_flip;
+mod connect_k;
+mod csv_export;
+mod daily;
+mod discovery;
+mod editor;
+mod event_log;
+mod export;
+mod game_record;
+mod history;
+mod invite;
+mod keymap;
+mod logging;
+mod net;
+mod notakto;
+mod notation;
+mod online;
+mod pentago;
+mod profiles;
+mod puzzle;
+mod qr;
+mod qubic;
+mod screenshot;
+mod series;
+mod settings;
+mod setup;
+mod share;
+mod simul;
+mod simulate;
+mod speedrun;
+mod team;
+mod tournament;
+mod ultimate;
+mod variant_view;
+mod variants;
+mod window_state;
+
+use std::collections::BTreeMap;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use clap::Parser;
 use gpui::{
-    div, prelude::*, px, rgb, size, App, Application, Bounds, Context, ElementId, MouseButton,
-    Window, WindowBounds, WindowOptions,
+    actions, canvas, div, ease_in_out, pattern_slash, prelude::*, pulsating_between, px, relative,
+    rgb, rgba, size, Animation, AnimationExt, App, Application, Bounds, ClipboardItem, Context,
+    DisplayId, Div, ElementId, FocusHandle, Focusable, Hsla, KeyDownEvent, MouseButton,
+    MouseMoveEvent, MouseUpEvent, PathPromptOptions, Pixels, Size, Stateful, Task, TitlebarOptions,
+    Window, WindowBounds, WindowKind, WindowOptions,
+};
+use tic_tac_toe::ai::{self, Difficulty, Opponent, COMPUTER_SIDE};
+use tic_tac_toe::engine::{
+    find_line, find_run, Board, Cell, GameState, MoveError, Outcome, Player, Position, WinLine,
+};
+use tic_tac_toe::game::{Game, Status};
+use tic_tac_toe::game_event::{GameEvent, Observers};
+use tic_tac_toe::game_result::{self, GameResult};
+use tic_tac_toe::rules::{self, RuleSet, Topology};
+use tracing::{debug, info, info_span, trace_span, warn};
+
+use analysis::Analysis;
+use annotation::{Annotation, Glyph};
+use args::{Args, Command, Launch};
+use bot::Bot;
+use bundle::Bundle;
+use campaign::Campaign;
+use chat::Chat;
+use coin_flip::CoinFlip;
+use connect_k::ConnectK;
+use csv_export::Column;
+use daily::{Challenge, Dailies, DailyResult};
+use discovery::Browser;
+use editor::PositionEditor;
+use event_log::{EventLog, LogEvent};
+use game_record::GameRecord;
+use history::{History, HistoryEntry, HistoryView, ResultFilter};
+use invite::Invite;
+use net::{Connection, Event, Host, Message, Reconnecting, Rematch, RemoteGame, Score, Takeback};
+use notakto::Notakto;
+use notation::Notation;
+use online::{Entry, Seat};
+use pentago::Pentago;
+use profiles::Profiles;
+use puzzle::{PuzzleKind, PuzzleRush, MAX_STRIKES};
+use qr::Qr;
+use qubic::QubicBoard;
+use series::Series;
+use settings::{MoveTimeout, Settings, READABLE_FONTS};
+use setup::{Setup, Step};
+use simul::Simul;
+use speedrun::Speedrun;
+use team::TeamMatch;
+use tournament::{
+    Bracket, Format, Tournament, TournamentSetup, BRACKET_SIZES, MAX_PLAYERS, MIN_PLAYERS,
 };
+use ultimate::UltimateBoard;
+use variants::{BoardLayout, Variant, VariantRegistry};
+use window_state::WindowState;
+
+/// Title of the window, which waits and missed turns are added to.
+const APP_TITLE: &str = "Tic Tac Toe";
+
+/// Time between turns of the coin while flipping for the first move.
+const COIN_FLIP_INTERVAL: Duration = Duration::from_millis(120);
+
+/// How long a mark stays visible in blind mode.
+const BLIND_FADE_DELAY: Duration = Duration::from_secs(1);
+
+/// Width of the row labels drawn beside the board.
+const COORDINATE_LABEL_WIDTH: Pixels = px(20.0);
+
+/// Smallest side length of a board cell, also used before the board's
+/// region has first been laid out.
+const MIN_CELL_SIZE: Pixels = px(48.0);
+
+/// Room kept around the three cells of the board for the gaps between
+/// them, the coordinate labels, and the torus frame.
+const BOARD_MARGIN: Pixels = px(64.0);
+
+/// Interval at which the thinking-time display refreshes.
+const THINKING_CLOCK_TICK: Duration = Duration::from_secs(1);
+
+/// Length of one pulse of the current-turn marker.
+const TURN_PULSE_DURATION: Duration = Duration::from_millis(1200);
+
+/// Length of one pulse of the hint border.
+const HINT_PULSE_DURATION: Duration = Duration::from_millis(900);
+
+/// How long a mark takes to fall to its cell under gravity.
+const DROP_DURATION: Duration = Duration::from_millis(300);
+
+/// Pause after a series game ends before the next one starts.
+const SERIES_NEXT_GAME_DELAY: Duration = Duration::from_secs(2);
 
-/// Represents a player in the game.
+/// Interval at which the blitz progress bar shrinks and the per-move
+/// limit is checked.
+const BLITZ_TICK: Duration = Duration::from_millis(100);
+
+/// Interval at which a LAN or online game checks for a joining player
+/// and for messages from the other side.
+const NETWORK_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Interval at which the LAN options look for games found on the
+/// network.
+const DISCOVERY_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Wait before the first attempt to rejoin an online room after the
+/// connection drops. Each further attempt waits twice as long as the
+/// last, up to [`MAX_RECONNECT_DELAY`].
+const RECONNECT_DELAY: Duration = Duration::from_millis(500);
+
+/// Longest wait between attempts to rejoin an online room.
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(8);
+
+/// Attempts made to rejoin an online room before giving up.
+const MAX_RECONNECT_ATTEMPTS: u32 = 8;
+
+/// Interval at which the Puzzle Rush clock counts down.
+const PUZZLE_RUSH_TICK: Duration = Duration::from_secs(1);
+
+actions!(
+    tic_tac_toe,
+    [
+        CopyPosition,
+        CycleFocus,
+        FocusDown,
+        FocusUp,
+        LoadGame,
+        NewGame,
+        PastePosition,
+        PlayCell1,
+        PlayCell2,
+        PlayCell3,
+        PlayCell4,
+        PlayCell5,
+        PlayCell6,
+        PlayCell7,
+        PlayCell8,
+        PlayCell9,
+        PlayFocused,
+        Redo,
+        Rematch,
+        Reset,
+        SaveGame,
+        SaveScreenshot,
+        StepBack,
+        StepForward,
+        ToggleEventLog,
+        ToggleChat,
+        TogglePin,
+        ToggleSettings,
+        ToggleShortcuts,
+        Undo
+    ]
+);
+
+/// What keyboard focus is on.
 #[derive(Clone, Copy, Debug, PartialEq)]
-enum Player {
-    X,
-    O,
+enum KeyFocus {
+    /// A board cell, as it is on the real board rather than as shown.
+    Cell(Position),
+    /// The Play Again button shown once a game is over.
+    ResetButton,
 }
 
-/// Represents the state of a cell on the board.
+/// Something that would throw away the game in progress.
 #[derive(Clone, Copy, Debug, PartialEq)]
-enum Cell {
-    /// The cell is empty and available for play.
-    Empty,
-    /// The cell is occupied by a player.
-    Player(Player),
+enum Abandon {
+    /// Starting a new game.
+    NewGame,
+    /// Loading a saved game.
+    LoadGame,
+    /// Importing a game from notation.
+    ImportGame,
+    /// Closing the window.
+    Quit,
+}
+
+/// The prompt asking whether to abandon the game in progress.
+#[derive(Clone, Copy, Debug)]
+struct AbandonPrompt {
+    /// What happens if the player confirms.
+    action: Abandon,
+    /// Whether "Don't ask again" is ticked.
+    dont_ask_again: bool,
 }
 
 /// The main game state for Tic Tac Toe.
 #[derive(Debug)]
 struct TicTacToe {
-    /// 3x3 game board represented as a 2D array.
-    board: [[Cell; 3]; 3],
-    /// The player whose turn it is.
-    current_player: Player,
-    /// Whether the game has ended (win or draw).
-    game_over: bool,
-    /// The winner of the game, if any.
-    winner: Option<Player>,
+    /// The game on the board, which checks every move against the rules.
+    game: GameState,
+    /// The position the current game started from.
+    initial_board: Board,
+    /// Moves played this game, in order.
+    moves: Vec<(Position, Player)>,
+    /// Undone moves that can be redone, the next one last.
+    redo_stack: Vec<(Position, Player)>,
+    /// Number of moves shown while previewing an earlier position on the
+    /// timeline, or `None` for live play.
+    preview_ply: Option<usize>,
+    /// Whether the finished game is being stepped through in the replay
+    /// view.
+    replaying: bool,
+    /// The engine's evaluation of every position in the replayed game,
+    /// once worked out.
+    evaluations: Option<Vec<f32>>,
+    /// Task evaluating the replayed game's positions in the background.
+    evaluation_task: Option<Task<()>>,
+    /// Annotations made on the game's moves, by the index of the move.
+    annotations: BTreeMap<usize, Annotation>,
+    /// Whether typing goes to the comment on the move shown in the replay
+    /// view.
+    editing_comment: bool,
+    /// Variations being tried against the engine from a position of the
+    /// replayed game, shown on the board instead of the game itself.
+    analysis: Option<Analysis>,
+    /// Moves of the previous game, replayed as ghost marks.
+    previous_moves: Vec<(Position, Player)>,
+    /// Whether the previous game's moves are shown as ghost marks.
+    show_ghost: bool,
+    /// Cell suggested by the Hint button for the player to move.
+    hint: Option<Position>,
+    /// Whether coordinate labels are drawn along the board edges.
+    show_coordinates: bool,
+    /// Whether the board is drawn rotated half a turn, so a player can see
+    /// it from their end, such as the O side in 2v2 or a network game.
+    /// Only this window's view changes; the other player's is untouched.
+    board_flipped: bool,
+    /// Where keyboard focus is, drawn as a ring, once the arrow keys or Tab
+    /// have been used. Using the mouse on the board clears it.
+    key_focus: Option<KeyFocus>,
+    /// The player who made the first move of the current game.
+    starting_player: Player,
+    /// Time each player has spent on their completed turns this game,
+    /// indexed by [`Player::index`].
+    thinking_time: [Duration; 2],
+    /// When the current turn began.
+    turn_started: Instant,
+    /// Task refreshing the thinking-time display while a turn runs.
+    thinking_clock_task: Option<Task<()>>,
+    /// Task enforcing the blitz per-move time limit.
+    move_timer_task: Option<Task<()>>,
+    /// How the game ended, once it has.
+    result: Option<GameResult>,
+    /// Time each player has on the clock this game, or `None` without
+    /// clocks.
+    clock_budget: Option<Duration>,
+    /// Time added to a player's clock after each of their moves this game.
+    clock_increment: Duration,
+    /// Whether the resign confirmation prompt is showing.
+    confirming_resign: bool,
+    /// The player offering a draw, until the offer is answered or lapses
+    /// with the next move.
+    draw_offer: Option<Player>,
+    /// The prompt confirming that the game in progress may be abandoned.
+    abandon_prompt: Option<AbandonPrompt>,
+    /// Whether marks fade from view shortly after being placed.
+    blind_mode: bool,
+    /// Cells whose marks have faded from view in blind mode.
+    hidden: [[bool; 3]; 3],
+    /// Tasks fading out recently placed marks in blind mode.
+    fade_tasks: Vec<Task<()>>,
+    /// The rules deciding who wins when a line is completed.
+    rules: RuleSet,
+    /// Whether the loser of the previous game moves first in the next one.
+    loser_starts: bool,
+    /// Whether new games start with some cells blocked off.
+    blocked_cells: bool,
+    /// Seed of the current game's blocked-cell layout, if it has one.
+    layout_seed: Option<u64>,
+    /// Seed of the coin flip that chose the current game's first player,
+    /// if one did, kept so the flip replays identically.
+    coin_seed: Option<u64>,
+    /// The coin flip in progress, during which the board is locked.
+    coin_flip: Option<CoinFlip>,
+    /// Task animating the coin flip.
+    coin_flip_task: Option<Task<()>>,
+    /// The Puzzle Rush run in progress, replacing normal play.
+    puzzle_rush: Option<PuzzleRush>,
+    /// Task counting down the Puzzle Rush clock.
+    puzzle_rush_task: Option<Task<()>>,
+    /// The tournament name-entry screen, shown instead of the board.
+    tournament_setup: Option<TournamentSetup>,
+    /// The tournament in progress, deciding who plays each game.
+    tournament: Option<Tournament>,
+    /// The single-elimination bracket in progress, deciding who plays each
+    /// game.
+    bracket: Option<Bracket>,
+    /// The position editor, shown instead of the board while open.
+    editor: Option<PositionEditor>,
+    /// The Ultimate Tic Tac Toe game, played instead of the normal board.
+    ultimate: Option<UltimateBoard>,
+    /// The Qubic game, played instead of the normal board.
+    qubic: Option<QubicBoard>,
+    /// The Notakto game, played instead of the normal board.
+    notakto: Option<Notakto>,
+    /// The Pentago game, played instead of the normal board.
+    pentago: Option<Pentago>,
+    /// The Connect-K game, played instead of the normal board.
+    connect_k: Option<ConnectK>,
+    /// The simul against several computers, played instead of the normal
+    /// board.
+    simul: Option<Simul>,
+    /// The variants listed on the menu.
+    variants: VariantRegistry,
+    /// The 2v2 team session in progress, deciding which seat moves.
+    team_match: Option<TeamMatch>,
+    /// The best-of-N series in progress, if any.
+    series: Option<Series>,
+    /// Task starting the next series or speedrun game after a short pause.
+    series_task: Option<Task<()>>,
+    /// The speedrun against the computer in progress, if any.
+    speedrun: Option<Speedrun>,
+    /// Who plays the computer's side of the board.
+    opponent: Opponent,
+    /// Task searching for the computer's move, during which the board is
+    /// locked.
+    computer_task: Option<Task<()>>,
+    /// External engine from `--bot` that plays the computer's side in
+    /// place of the built-in search.
+    bot: Option<Arc<Mutex<Bot>>>,
+    /// Timestamped record of the session's events.
+    event_log: EventLog,
+    /// Subscribers to moves, results, new games, and changes of turn: the
+    /// event log and the scores and statistics kept on finished games.
+    observers: Observers<Self>,
+    /// Whether the event log panel is expanded.
+    show_event_log: bool,
+    /// Whether the splitter above the event log is being dragged.
+    dragging_split: bool,
+    /// Size of the region the board was laid out in on the last frame,
+    /// which the cells are sized to fill.
+    board_area: Option<Size<Pixels>>,
+    /// Whether the keyboard shortcut overlay is showing.
+    show_shortcuts: bool,
+    /// Whether the settings panel is open.
+    show_settings: bool,
+    /// Whether the player profiles panel is open.
+    show_profiles: bool,
+    /// The first-run setup wizard, while it is open.
+    setup: Option<Setup>,
+    /// Whether the settings panel is asking to confirm replacing the
+    /// saved data with an imported bundle.
+    confirming_import: bool,
+    /// Whether the campaign's level list is open.
+    show_campaign: bool,
+    /// The campaign level being played, if any.
+    campaign: Option<Campaign>,
+    /// Best stars on each campaign level this session, kept while no
+    /// profile is seated at X to save them with.
+    guest_campaign: Vec<u8>,
+    /// The Daily Challenge being played, if any.
+    daily: Option<Challenge>,
+    /// Whether the Daily Challenge game is practice, its day already
+    /// having a result.
+    daily_practice: bool,
+    /// The result of every Daily Challenge played.
+    dailies: Dailies,
+    /// The profile name being typed in the profiles panel.
+    profile_input: String,
+    /// The saved player profiles.
+    profiles: Profiles,
+    /// Name of the profile playing each side, indexed by [`Player::index`].
+    seats: [Option<String>; 2],
+    /// Whether the finished game has already been recorded in the seated
+    /// profiles.
+    recorded: bool,
+    /// The game history database, if it could be opened.
+    history: Option<History>,
+    /// Row id of the current game in the history, once it has finished.
+    history_id: Option<i64>,
+    /// The History screen, when open.
+    history_view: Option<HistoryView>,
+    /// Whether the network panel for hosting and joining games is open.
+    show_lan: bool,
+    /// Whether the network panel shows the LAN options instead of room
+    /// codes.
+    show_lan_options: bool,
+    /// The room code, or host address under the LAN options, being typed
+    /// in the network panel.
+    network_input: String,
+    /// Whether the chat panel of a network game is expanded.
+    show_chat: bool,
+    /// The chat line being typed.
+    chat_input: String,
+    /// The LAN game being hosted while no one has joined yet.
+    lan_host: Option<Host>,
+    /// Whether a connection to a host or relay is being made.
+    connecting: bool,
+    /// The window title last set, so it is only changed when it differs.
+    window_title: String,
+    /// Whether the computer or network was being waited on when the
+    /// window title was last set.
+    was_waiting: bool,
+    /// Whether a wait ended while the window was in the background, so
+    /// its title says it is the player's turn until it is brought back.
+    missed_turn: bool,
+    /// Whether the game is being uploaded to the relay for replaying.
+    hosting_replay: bool,
+    /// Link to the game as hosted on the relay, once uploaded and copied.
+    hosted_link: Option<String>,
+    /// The online game being rejoined after its connection dropped.
+    reconnecting: Option<Reconnecting>,
+    /// The LAN or online game in progress.
+    remote: Option<RemoteGame>,
+    /// Task connecting to a host, or polling the network while hosting or
+    /// playing; dropping it stops either.
+    remote_task: Option<Task<()>>,
+    /// Looks for LAN games while the LAN options are shown.
+    lan_browser: Option<Browser>,
+    /// Task polling the LAN browser; dropping it stops the polling.
+    browse_task: Option<Task<()>>,
+    /// Installed font families listed by the open font picker.
+    font_picker: Option<Vec<String>>,
+    /// Why the last save or load failed, shown until the next attempt.
+    file_error: Option<String>,
+    /// Why the last click on the board was refused, shown until the next
+    /// move.
+    move_error: Option<MoveError>,
+    /// The unfinished game found at launch, offered for resuming.
+    resume_prompt: Option<GameRecord>,
+    /// The protocol version of a network peer that could not be played
+    /// with, shown in a dialog until dismissed.
+    incompatible_version: Option<u32>,
+    /// Whether the window is pinned above other windows.
+    pinned: bool,
+    /// Focus handle used to receive keyboard shortcuts.
+    focus_handle: FocusHandle,
+    /// Remembered window bounds for each display.
+    window_state: WindowState,
+    /// The player's saved preferences.
+    settings: Settings,
 }
 
-impl TicTacToe {
-    /// Creates a new game with an empty board and X as the starting player.
-    fn new() -> Self {
-        Self {
-            board: [[Cell::Empty; 3]; 3],
-            current_player: Player::X,
-            game_over: false,
-            winner: None,
-        }
+impl TicTacToe {
+    /// Creates a new game with an empty board and X as the starting player.
+    fn new(
+        window_state: WindowState,
+        launch: Launch,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut game = Self {
+            game: GameState::new(Player::X, [[Cell::Empty; 3]; 3], RuleSet::default()),
+            initial_board: [[Cell::Empty; 3]; 3],
+            moves: Vec::new(),
+            redo_stack: Vec::new(),
+            preview_ply: None,
+            replaying: false,
+            evaluations: None,
+            evaluation_task: None,
+            annotations: BTreeMap::new(),
+            editing_comment: false,
+            analysis: None,
+            previous_moves: Vec::new(),
+            show_ghost: false,
+            hint: None,
+            show_coordinates: false,
+            board_flipped: false,
+            key_focus: None,
+            starting_player: Player::X,
+            thinking_time: [Duration::ZERO; 2],
+            turn_started: Instant::now(),
+            thinking_clock_task: None,
+            move_timer_task: None,
+            result: None,
+            clock_budget: None,
+            clock_increment: Duration::ZERO,
+            confirming_resign: false,
+            draw_offer: None,
+            abandon_prompt: None,
+            blind_mode: false,
+            hidden: [[false; 3]; 3],
+            fade_tasks: Vec::new(),
+            rules: RuleSet::default(),
+            loser_starts: false,
+            blocked_cells: false,
+            layout_seed: None,
+            coin_seed: None,
+            coin_flip: None,
+            coin_flip_task: None,
+            puzzle_rush: None,
+            puzzle_rush_task: None,
+            tournament_setup: None,
+            tournament: None,
+            bracket: None,
+            ultimate: None,
+            qubic: None,
+            notakto: None,
+            simul: None,
+            pentago: None,
+            connect_k: None,
+            variants: VariantRegistry::builtin(),
+            team_match: None,
+            series: None,
+            series_task: None,
+            speedrun: None,
+            opponent: if launch.vs_ai.is_some() || launch.bot.is_some() {
+                Opponent::Computer
+            } else {
+                Opponent::Human
+            },
+            computer_task: None,
+            bot: launch.bot.map(|bot| Arc::new(Mutex::new(bot))),
+            editor: None,
+            event_log: EventLog::default(),
+            observers: Observers::default(),
+            show_event_log: false,
+            dragging_split: false,
+            board_area: None,
+            show_shortcuts: false,
+            show_settings: false,
+            show_profiles: false,
+            setup: None,
+            confirming_import: false,
+            show_campaign: false,
+            campaign: None,
+            guest_campaign: Vec::new(),
+            daily: None,
+            daily_practice: false,
+            dailies: Dailies::load(),
+            profile_input: String::new(),
+            profiles: Profiles::load(),
+            seats: [None, None],
+            recorded: false,
+            history: History::open(),
+            history_id: None,
+            history_view: None,
+            show_lan: false,
+            show_lan_options: false,
+            network_input: String::new(),
+            show_chat: false,
+            chat_input: String::new(),
+            lan_host: None,
+            connecting: false,
+            window_title: APP_TITLE.to_string(),
+            was_waiting: false,
+            missed_turn: false,
+            hosting_replay: false,
+            hosted_link: None,
+            remote: None,
+            reconnecting: None,
+            remote_task: None,
+            lan_browser: None,
+            browse_task: None,
+            font_picker: None,
+            file_error: None,
+            move_error: None,
+            resume_prompt: GameRecord::load_autosave(),
+            incompatible_version: None,
+            pinned: false,
+            focus_handle: cx.focus_handle(),
+            window_state,
+            settings: Settings::load(),
+        };
+        if !game.settings.setup_done {
+            game.setup = Some(Setup::new(&game.settings));
+        }
+        if game.opponent == Opponent::Human {
+            game.opponent = game.settings.opponent;
+        }
+        // A profile removed since setup is not brought back
+        game.seats[Player::X.index()] = game
+            .settings
+            .player_name
+            .clone()
+            .filter(|name| game.profiles.get(name).is_some());
+        game.observers.subscribe(Self::log_event);
+        game.observers.subscribe(Self::record_scores);
+        game.emit(GameEvent::Reset { first: Player::X });
+        if let Some(difficulty) = launch.vs_ai {
+            game.settings.difficulty = difficulty;
+        }
+        // A game or board opened from the command line replaces the autosave
+        // offer
+        if let Some(record) = launch.game {
+            game.resume_prompt = None;
+            game.restore_game(record);
+            game.play_computer_turn(cx);
+        } else if let Some(connect_k) = launch.size.and_then(|size| ConnectK::new(size, Player::X))
+        {
+            game.resume_prompt = None;
+            game.connect_k = Some(connect_k);
+        } else if game.settings.random_start {
+            game.flip_for_first_move(cx);
+        }
+        game.attach_window(window, cx);
+        game.start_thinking_clock(cx);
+        game.start_move_timer(cx);
+        game
+    }
+
+    /// Returns what the player is waiting on, if anything: the computer
+    /// searching for its move, or the network.
+    fn wait_status(&self) -> Option<&'static str> {
+        if self.computer_task.is_some() {
+            return Some("Computer thinking…");
+        }
+        if self.connecting {
+            return Some("Connecting…");
+        }
+        if self.reconnecting.is_some() {
+            return Some("Reconnecting…");
+        }
+        let remote = self.remote.as_ref()?;
+        let side = remote.side?;
+        if !remote.opponent_present {
+            Some("Waiting for opponent…")
+        } else if self.game.to_move() != side && !self.game_over() {
+            Some("Opponent's turn…")
+        } else {
+            None
+        }
+    }
+
+    /// Shows any wait in the window title, which the taskbar, dock menu,
+    /// and window switcher show, and marks a turn or game end that came up
+    /// while the window was in the background until it is brought back.
+    ///
+    /// GPUI offers no taskbar progress, dock bounce, or attention request,
+    /// so the title is the one place outside the window this can go.
+    fn update_title(&mut self, window: &mut Window) {
+        let status = self.wait_status();
+        if window.is_window_active() {
+            self.missed_turn = false;
+        } else if self.was_waiting && status.is_none() {
+            self.missed_turn = true;
+        }
+        self.was_waiting = status.is_some();
+
+        let title = match status {
+            Some(status) => format!("{status} — {APP_TITLE}"),
+            None if self.missed_turn && self.game_over() => format!("● Game over — {APP_TITLE}"),
+            None if self.missed_turn => format!("● Your turn — {APP_TITLE}"),
+            None => APP_TITLE.to_string(),
+        };
+        if title != self.window_title {
+            window.set_window_title(&title);
+            self.window_title = title;
+        }
+    }
+
+    /// Tracks the bounds of the window hosting the game.
+    ///
+    /// Bounds are recorded per display whenever the window moves or is
+    /// resized, and written to disk when it changes display or closes.
+    /// Closing the window mid-game asks for confirmation first.
+    fn attach_window(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.window_state.record(window, cx);
+        cx.observe_window_bounds(window, |this, window, cx| {
+            if this.window_state.record(window, cx) {
+                this.window_state.save();
+            }
+            cx.notify();
+        })
+        .detach();
+        // Coming back to the window clears a missed turn from its title
+        cx.observe_window_activation(window, |_, _, cx| cx.notify())
+            .detach();
+
+        let view = cx.entity().downgrade();
+        window.on_window_should_close(cx, move |_, cx| {
+            view.update(cx, |this, cx| {
+                this.window_state.save();
+                if this.should_confirm_abandon() {
+                    this.prompt_abandon(Abandon::Quit);
+                    cx.notify();
+                    return false;
+                }
+                true
+            })
+            .unwrap_or(true)
+        });
+    }
+
+    /// Redraws every second while a turn is running so the player to
+    /// move sees their thinking time count up, and their game clock count
+    /// down.
+    fn start_thinking_clock(&mut self, cx: &mut Context<Self>) {
+        self.thinking_clock_task = Some(cx.spawn(async move |this, cx| loop {
+            cx.background_executor().timer(THINKING_CLOCK_TICK).await;
+            let stopped = this
+                .update(cx, |this, cx| {
+                    if !this.game_over() && this.coin_flip.is_none() {
+                        this.check_clock(cx);
+                        cx.notify();
+                    }
+                })
+                .is_err();
+            if stopped {
+                break;
+            }
+        }));
+    }
+
+    /// Returns the time `player` has spent thinking this game, including
+    /// the turn in progress.
+    fn thinking_time(&self, player: Player) -> Duration {
+        let mut time = self.thinking_time[player.index()];
+        if player == self.game.to_move() && !self.game_over() && self.coin_flip.is_none() {
+            time += self.turn_started.elapsed();
+        }
+        time
+    }
+
+    /// Returns the time left on `player`'s game clock, or `None` when the
+    /// game is played without clocks. Each move the player has made adds
+    /// the increment to their budget.
+    ///
+    /// Clocks only run for the normal board, not for Puzzle Rush or the
+    /// alternate boards, and not in network games: the protocol carries no
+    /// clock, so running out here would end the game on one side only.
+    fn time_left(&self, player: Player) -> Option<Duration> {
+        let on_main_board = self.remote.is_none()
+            && self.puzzle_rush.is_none()
+            && self.ultimate.is_none()
+            && self.qubic.is_none()
+            && self.notakto.is_none()
+            && self.pentago.is_none()
+            && self.connect_k.is_none()
+            && self.simul.is_none();
+        let budget = self.clock_budget.filter(|_| on_main_board)?;
+        let moves = self
+            .moves
+            .iter()
+            .filter(|&&(_, mover)| mover == player)
+            .count();
+        let earned = self.clock_increment * moves as u32;
+        Some((budget + earned).saturating_sub(self.thinking_time(player)))
+    }
+
+    /// Ends the game in the opponent's favor once the player to move has
+    /// run out of time.
+    fn check_clock(&mut self, cx: &mut Context<Self>) {
+        if self.game_over() || self.time_left(self.game.to_move()) != Some(Duration::ZERO) {
+            return;
+        }
+        self.lose_on_time(cx);
+    }
+
+    /// Ends the game in the opponent's favor because the player to move
+    /// ran out of time.
+    fn lose_on_time(&mut self, cx: &mut Context<Self>) {
+        self.end_turn();
+        self.computer_task = None;
+        self.confirming_resign = false;
+        self.draw_offer = None;
+        self.event_log.push(LogEvent::TimedOut(self.game.to_move()));
+        self.end_game(GameResult::Timeout {
+            winner: self.game.to_move().other(),
+        });
+        self.autosave();
+        self.continue_series(cx);
+    }
+
+    /// Redraws the blitz progress bar as it shrinks, and acts once the
+    /// player to move runs over the per-move limit.
+    fn start_move_timer(&mut self, cx: &mut Context<Self>) {
+        self.move_timer_task = Some(cx.spawn(async move |this, cx| loop {
+            cx.background_executor().timer(BLITZ_TICK).await;
+            let stopped = this
+                .update(cx, |this, cx| {
+                    if let Some(left) = this.move_time_left() {
+                        if left.is_zero() {
+                            this.move_timed_out(cx);
+                        }
+                        cx.notify();
+                    }
+                })
+                .is_err();
+            if stopped {
+                break;
+            }
+        }));
+    }
+
+    /// Returns the time left for the current move in blitz play, or `None`
+    /// when no move is being timed.
+    ///
+    /// The computer's moves, the alternate boards, and network games are
+    /// not timed; a random move played here for a network player would
+    /// never reach the other side.
+    fn move_time_left(&self) -> Option<Duration> {
+        let limit = Duration::from_secs(self.settings.move_limit_secs?);
+        let timing = !self.game_over()
+            && self.remote.is_none()
+            && self.coin_flip.is_none()
+            && self.computer_task.is_none()
+            && self.preview_ply.is_none()
+            && self.puzzle_rush.is_none()
+            && self.editor.is_none()
+            && self.ultimate.is_none()
+            && self.qubic.is_none()
+            && self.notakto.is_none()
+            && self.pentago.is_none()
+            && self.connect_k.is_none()
+            && self.simul.is_none();
+        timing.then(|| limit.saturating_sub(self.turn_started.elapsed()))
+    }
+
+    /// Handles the player to move running over the per-move limit, either
+    /// ending the game in the opponent's favor or playing a random move
+    /// for them.
+    fn move_timed_out(&mut self, cx: &mut Context<Self>) {
+        let random = ai::choose_move(
+            self.game.board(),
+            self.game.to_move(),
+            Difficulty::Easy,
+            self.rules,
+        );
+        match (self.settings.move_timeout, random) {
+            (MoveTimeout::RandomMove, Some(position)) => {
+                let _ = self.make_move(position, cx);
+            }
+            _ => self.lose_on_time(cx),
+        }
+    }
+
+    /// Charges the turn just finished to the player to move and starts
+    /// timing the next one.
+    fn end_turn(&mut self) {
+        self.thinking_time[self.game.to_move().index()] += self.turn_started.elapsed();
+        self.turn_started = Instant::now();
+    }
+
+    /// Handles a click on a board cell.
+    ///
+    /// During analysis the click plays a move of the variation against the
+    /// engine. In Puzzle Rush the click answers the current puzzle; otherwise it
+    /// plays a move, dropped down the clicked column under gravity. In
+    /// blind mode, picking an occupied cell or full column forfeits the
+    /// turn, and a newly placed mark fades out after a moment.
+    fn click_cell(&mut self, position: Position, cx: &mut Context<Self>) {
+        if let Some(analysis) = self.analysis.as_mut() {
+            analysis.play(position);
+            return;
+        }
+        if self.preview_ply.is_some() || self.computer_task.is_some() || self.reconnecting.is_some()
+        {
+            return;
+        }
+        // In a LAN or online game the board only takes clicks on this
+        // side's turn, once the other player is there, and never from a
+        // spectator
+        if let Some(remote) = &self.remote {
+            if remote.side.is_none() || !remote.opponent_present {
+                return;
+            }
+            if remote.side != Some(self.game.to_move()) && !self.game_over() {
+                self.move_error = Some(MoveError::NotYourTurn);
+                return;
+            }
+            match self.make_move(position, cx) {
+                Ok(landed) => {
+                    // Moving instead of answering turns a takeback down
+                    if let Some(Takeback::Offered(_)) = self.takeback() {
+                        self.answer_takeback(false);
+                    }
+                    self.set_takeback(None);
+                    self.send_remote(&Message::Move {
+                        row: landed.row(),
+                        col: landed.col(),
+                    });
+                }
+                Err(err) => self.move_error = Some(err),
+            }
+            return;
+        }
+        let Some(rush) = self.puzzle_rush.as_mut() else {
+            if !self.rules.gravity && position.cell(self.game.board()) == Cell::Blocked {
+                return;
+            }
+            match self.make_move(position, cx) {
+                Ok(landed) if self.blind_mode && landed.cell(self.game.board()) != Cell::Empty => {
+                    self.fade_mark(landed, cx);
+                }
+                Ok(_) => {}
+                Err(MoveError::CellOccupied) if self.blind_mode => self.forfeit_turn(cx),
+                Err(err) => self.move_error = Some(err),
+            }
+            return;
+        };
+        if position.cell(self.game.board()) == Cell::Empty {
+            rush.answer(position.row(), position.col());
+            self.show_puzzle();
+        }
+    }
+
+    /// Attempts to make a move at the specified position, returning the
+    /// cell the mark lands on, or says why it is not allowed.
+    ///
+    /// A new move discards any undone moves, and against the computer
+    /// starts its reply.
+    fn make_move(
+        &mut self,
+        position: Position,
+        cx: &mut Context<Self>,
+    ) -> Result<Position, MoveError> {
+        let landed = self.place_mark(position)?;
+        self.redo_stack.clear();
+        self.play_computer_turn(cx);
+        self.continue_series(cx);
+        Ok(landed)
+    }
+
+    /// Places the current player's mark at the specified position,
+    /// returning the cell it lands on.
+    ///
+    /// The mark is only placed if the game is not over, the coin for the
+    /// first move has landed, and [`GameState::play`] allows it, which
+    /// also decides whether the move ends the game.
+    fn place_mark(&mut self, position: Position) -> Result<Position, MoveError> {
+        if self.game_over() {
+            return Err(MoveError::GameOver);
+        }
+        if self.coin_flip.is_some() {
+            return Err(MoveError::NotYourTurn);
+        }
+        let player = self.game.to_move();
+        let mut game = self.game;
+        let position = game.play(position)?;
+        let (row, col) = (position.row(), position.col());
+
+        let _span = info_span!("move", ?player, cell = %cell_name(row, col)).entered();
+        info!("mark placed");
+        self.end_turn();
+        self.game = game;
+        self.hint = None;
+        self.draw_offer = None;
+        self.move_error = None;
+        self.moves.push((position, player));
+        self.emit(GameEvent::MovePlayed { player, position });
+        if let Some(teams) = self.team_match.as_mut() {
+            teams.advance_seat(player);
+        }
+
+        match self.game.outcome() {
+            Outcome::Won(winner, _) => self.end_game(GameResult::Win(winner)),
+            Outcome::Draw => self.end_game(GameResult::Draw),
+            Outcome::InProgress => self.emit(GameEvent::TurnChanged(self.game.to_move())),
+        }
+        self.autosave();
+        Ok(position)
+    }
+
+    /// Whether the last move can be taken back.
+    ///
+    /// Resignations, agreed draws, and timeouts stand, and a finished 2v2,
+    /// series, or profile game has already been added to the scores.
+    fn can_undo(&self) -> bool {
+        !self.moves.is_empty()
+            && !self.result.is_some_and(GameResult::is_decided_off_board)
+            && !(self.game_over() && self.recorded)
+            && !(self.game_over() && (self.team_match.is_some() || self.series.is_some()))
+            && self.puzzle_rush.is_none()
+            && self.speedrun.is_none()
+            && self.coin_flip.is_none()
+            && self.computer_task.is_none()
+            && self.preview_ply.is_none()
+            && self.remote.is_none()
+    }
+
+    /// Whether an undone move can be played again.
+    fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty() && self.computer_task.is_none() && self.preview_ply.is_none()
+    }
+
+    /// Takes back the last move, restoring the turn and clearing any
+    /// result. Against the computer, its reply is taken back too so the
+    /// human is to move again.
+    fn undo(&mut self, cx: &mut Context<Self>) {
+        if !self.can_undo() {
+            return;
+        }
+        self.take_back();
+        if self.opponent == Opponent::Computer
+            && self.game.to_move() == COMPUTER_SIDE
+            && !self.moves.is_empty()
+        {
+            self.take_back();
+        }
+        self.play_computer_turn(cx);
+    }
+
+    /// Removes the last move from the board and puts it on the redo stack.
+    fn take_back(&mut self) {
+        let Some((position, player)) = self.moves.pop() else {
+            return;
+        };
+        self.redo_stack.push((position, player));
+        // Rebuilding brings back a mark the move had expired
+        self.game = self.state_at(self.moves.len());
+        if self.game.to_move() != player {
+            self.game.pass();
+        }
+        self.hidden[position.row()][position.col()] = false;
+        self.hint = None;
+        self.result = None;
+        self.replaying = false;
+        self.emit(GameEvent::MoveTakenBack { player, position });
+        self.emit(GameEvent::TurnChanged(player));
+        self.turn_started = Instant::now();
+        if let Some(teams) = self.team_match.as_mut() {
+            teams.advance_seat(player);
+        }
+        self.autosave();
+    }
+
+    /// Plays the most recently undone move again. Against the computer,
+    /// its undone reply is replayed too.
+    fn redo(&mut self, cx: &mut Context<Self>) {
+        if !self.can_redo() {
+            return;
+        }
+        self.replay_undone();
+        if self.opponent == Opponent::Computer && self.game.to_move() == COMPUTER_SIDE {
+            self.replay_undone();
+        }
+        self.play_computer_turn(cx);
+    }
+
+    /// Places the next move from the redo stack.
+    fn replay_undone(&mut self) {
+        if let Some((position, _)) = self.redo_stack.pop() {
+            let _ = self.place_mark(position);
+        }
+    }
+
+    /// Starts the computer's reply when it is the computer's turn.
+    ///
+    /// The search runs on the background executor so rendering never
+    /// waits on it, and the move is played once it finishes. Starting a
+    /// new game drops the task, abandoning the search.
+    fn play_computer_turn(&mut self, cx: &mut Context<Self>) {
+        if self.opponent != Opponent::Computer
+            || self.game_over()
+            || self.coin_flip.is_some()
+            || self.game.to_move() != COMPUTER_SIDE
+            || self.computer_task.is_some()
+        {
+            return;
+        }
+        let board = *self.game.board();
+        if let Some(bot) = self
+            .bot
+            .clone()
+            .filter(|_| self.speedrun.is_none() && self.daily.is_none())
+        {
+            self.computer_task = Some(cx.spawn(async move |this, cx| {
+                let reply = cx
+                    .background_executor()
+                    .spawn(async move {
+                        let _span = info_span!("bot_move").entered();
+                        let mut bot = bot.lock().expect("the bot lock is never poisoned");
+                        bot.best_move(&board, COMPUTER_SIDE)
+                    })
+                    .await;
+                this.update(cx, |this, cx| {
+                    this.computer_task = None;
+                    match reply {
+                        Ok(position) => {
+                            if let Err(err) = this.make_move(position, cx) {
+                                this.file_error = Some(format!("Refused the bot's move: {err}"));
+                            }
+                        }
+                        Err(err) => this.file_error = Some(format!("The bot did not move: {err}")),
+                    }
+                    cx.notify();
+                })
+                .ok();
+            }));
+            return;
+        }
+        let difficulty = self.computer_difficulty();
+        let rules = self.rules;
+        self.computer_task = Some(cx.spawn(async move |this, cx| {
+            let best = cx
+                .background_executor()
+                .spawn(async move {
+                    let _span = info_span!("computer_move", ?difficulty).entered();
+                    ai::choose_move(&board, COMPUTER_SIDE, difficulty, rules)
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                this.computer_task = None;
+                if let Some(position) = best {
+                    let _ = this.make_move(position, cx);
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    /// Returns the engine's best move for the player to move, or `None`
+    /// when there is no live game to play on.
+    fn best_move(&self) -> Option<Position> {
+        if self.game_over() || self.coin_flip.is_some() || self.puzzle_rush.is_some() {
+            return None;
+        }
+        ai::best_move(self.game.board(), self.game.to_move(), self.rules)
+    }
+
+    /// Passes the turn to the opponent without placing a mark.
+    fn forfeit_turn(&mut self, cx: &mut Context<Self>) {
+        if self.game_over() || self.coin_flip.is_some() {
+            return;
+        }
+        self.end_turn();
+        self.hint = None;
+        self.emit(GameEvent::TurnForfeited(self.game.to_move()));
+        if let Some(teams) = self.team_match.as_mut() {
+            teams.advance_seat(self.game.to_move());
+        }
+        self.game.pass();
+        self.emit(GameEvent::TurnChanged(self.game.to_move()));
+        self.autosave();
+        self.play_computer_turn(cx);
+    }
+
+    /// Hides the mark at the given cell once the blind-mode delay passes.
+    fn fade_mark(&mut self, position: Position, cx: &mut Context<Self>) {
+        self.fade_tasks.push(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(BLIND_FADE_DELAY).await;
+            this.update(cx, |this, cx| {
+                this.hidden[position.row()][position.col()] = true;
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    /// Replays the first `ply` moves from the starting position.
+    fn state_at(&self, ply: usize) -> GameState {
+        let mut game = GameState::new(self.starting_player, self.initial_board, self.game.rules());
+        for &(position, player) in self.moves.iter().take(ply) {
+            // A blind-mode forfeit, or a custom position's first move,
+            // passed the turn without a mark
+            if game.to_move() != player {
+                game.pass();
+            }
+            let _ = game.play(position);
+        }
+        game
+    }
+
+    /// Whether the rule options are locked: while a game with moves on
+    /// the board is in progress, since its moves were checked under the
+    /// rules it started with, and throughout a network game, whose rules X
+    /// sends with each game it starts, or a campaign level or Daily
+    /// Challenge, whose rules are part of the level or day.
+    fn rules_locked(&self) -> bool {
+        self.remote.is_some()
+            || self.campaign.is_some()
+            || self.daily.is_some()
+            || (!self.moves.is_empty() && !self.game_over())
+    }
+
+    /// Changes the rules and starts a new game under them, so a game is
+    /// always played, saved, and replayed under one set of rules. Does
+    /// nothing while the rules are locked.
+    fn change_rules(&mut self, change: impl FnOnce(&mut RuleSet), cx: &mut Context<Self>) {
+        if self.rules_locked() {
+            return;
+        }
+        change(&mut self.rules);
+        self.start_game(Player::X);
+        self.play_computer_turn(cx);
+    }
+
+    /// Rebuilds the board as it stood after the first `ply` moves.
+    fn board_at(&self, ply: usize) -> Board {
+        *self.state_at(ply).board()
+    }
+
+    /// Moves the timeline to `ply`, returning to live play on the last
+    /// notch.
+    fn scrub_to(&mut self, ply: usize) {
+        self.analysis = None;
+        self.preview_ply = (ply < self.moves.len()).then_some(ply);
+    }
+
+    /// Whether the game has ended, on the board or off it, or the Puzzle
+    /// Rush run has finished.
+    fn game_over(&self) -> bool {
+        self.result.is_some()
+            || self
+                .puzzle_rush
+                .as_ref()
+                .is_some_and(PuzzleRush::is_finished)
+    }
+
+    /// Returns the winner of the finished game, if it had one.
+    fn winner(&self) -> Option<Player> {
+        self.result.and_then(GameResult::winner)
+    }
+
+    /// Returns how the game stands on the board, with the line that
+    /// decided it. A game ended by resignation, agreement, or the clock is
+    /// in progress here, as no line decided it.
+    fn outcome(&self) -> Outcome {
+        self.game.outcome()
+    }
+
+    /// Returns the side that resigns or offers draws from this window: the
+    /// player to move, or this side in a network game. Spectators have
+    /// none.
+    fn own_side(&self) -> Option<Player> {
+        match &self.remote {
+            Some(remote) => remote.side,
+            None => Some(self.game.to_move()),
+        }
+    }
+
+    /// Resigns the game for [`Self::own_side`], telling the opponent in a
+    /// network game.
+    fn resign(&mut self) {
+        let Some(player) = self.own_side().filter(|_| !self.game_over()) else {
+            return;
+        };
+        self.send_remote(&Message::Resign { player });
+        self.concede(player);
+    }
+
+    /// Ends the game with `player` conceding to their opponent.
+    fn concede(&mut self, player: Player) {
+        if self.game_over() {
+            return;
+        }
+        self.end_turn();
+        self.confirming_resign = false;
+        self.draw_offer = None;
+        self.event_log.push(LogEvent::Resigned(player));
+        self.end_game(GameResult::Resignation {
+            winner: player.other(),
+        });
+        self.autosave();
+    }
+
+    /// Offers a draw on behalf of [`Self::own_side`].
+    fn offer_draw(&mut self) {
+        let Some(player) = self.own_side().filter(|_| !self.game_over()) else {
+            return;
+        };
+        self.draw_offer = Some(player);
+        self.send_remote(&Message::DrawOffer);
+    }
+
+    /// Answers the pending draw offer, ending the game on acceptance.
+    fn answer_draw(&mut self, accepted: bool) {
+        if self.draw_offer.take().is_none() {
+            return;
+        }
+        self.send_remote(&Message::DrawAnswer { accepted });
+        if accepted {
+            self.agree_draw();
+        }
+    }
+
+    /// Ends the game as a draw both players agreed to.
+    fn agree_draw(&mut self) {
+        if self.game_over() {
+            return;
+        }
+        self.end_turn();
+        self.confirming_resign = false;
+        self.draw_offer = None;
+        self.event_log.push(LogEvent::DrawAgreed);
+        self.end_game(GameResult::AgreedDraw);
+        self.autosave();
+    }
+
+    /// Whether a draw offer awaits an answer from this window.
+    fn draw_offer_pending(&self) -> bool {
+        self.draw_offer.is_some_and(|offerer| {
+            self.remote.is_none() || self.own_side() == Some(offerer.other())
+        })
+    }
+
+    /// Ends the game with the given result.
+    fn end_game(&mut self, result: GameResult) {
+        let winner = result.winner();
+        info!(?result, "game over");
+        self.result = Some(result);
+        self.emit(match winner {
+            Some(winner) => GameEvent::GameWon(winner),
+            None => GameEvent::Draw,
+        });
+    }
+
+    /// Passes `event` to the game's subscribers.
+    fn emit(&mut self, event: GameEvent) {
+        Observers::emit(self, event, |this| &mut this.observers);
+    }
+
+    /// Subscriber that writes the game's events to the event log.
+    ///
+    /// A result decided off the board was already logged with its reason,
+    /// such as a resignation, so only wins and draws on the board are.
+    fn log_event(&mut self, event: &GameEvent) {
+        let decided_off_board = self.result.is_some_and(GameResult::is_decided_off_board);
+        let event = match *event {
+            GameEvent::Reset { first } => LogEvent::NewGame { first },
+            GameEvent::MovePlayed { player, position } => LogEvent::Move {
+                player,
+                row: position.row(),
+                col: position.col(),
+            },
+            GameEvent::MoveTakenBack { player, position } => LogEvent::Undone {
+                player,
+                row: position.row(),
+                col: position.col(),
+            },
+            GameEvent::TurnForfeited(player) => LogEvent::Forfeit(player),
+            GameEvent::GameWon(winner) if !decided_off_board => LogEvent::Won(winner),
+            GameEvent::Draw if !decided_off_board => LogEvent::Draw,
+            GameEvent::GameWon(_) | GameEvent::Draw | GameEvent::TurnChanged(_) => return,
+        };
+        self.event_log.push(event);
+    }
+
+    /// Subscriber that adds each finished game to the running scores, the
+    /// profiles' statistics, and the history database.
+    fn record_scores(&mut self, event: &GameEvent) {
+        let winner = match *event {
+            GameEvent::GameWon(winner) => Some(winner),
+            GameEvent::Draw => None,
+            _ => return,
+        };
+        if let Some(teams) = self.team_match.as_mut() {
+            teams.record(winner);
+        }
+        if let Some(series) = self.series.as_mut() {
+            series.record(winner);
+        }
+        if let Some(run) = self.speedrun.as_mut() {
+            run.record(winner);
+        }
+        self.record_campaign(winner);
+        self.record_daily(winner);
+        if let Some(remote) = self.remote.as_mut() {
+            remote.score.record(winner);
+        }
+        self.record_profiles(winner);
+        self.record_history();
+    }
+
+    /// Awards the campaign level's stars for the finished game, keeping the
+    /// best on each level with the profile seated at X, or for the session
+    /// when there is none.
+    fn record_campaign(&mut self, winner: Option<Player>) {
+        let Some(campaign) = self.campaign.as_mut() else {
+            return;
+        };
+        let player = COMPUTER_SIDE.other();
+        let moves = self
+            .moves
+            .iter()
+            .filter(|&&(_, mover)| mover == player)
+            .count();
+        let stars = campaign::stars(winner.map(|winner| winner == player), moves);
+        campaign.stars = Some(stars);
+        let level = campaign.level;
+        let progress = match self.seats[player.index()]
+            .as_deref()
+            .and_then(|name| self.profiles.get_mut(name))
+        {
+            Some(profile) => &mut profile.campaign,
+            None => &mut self.guest_campaign,
+        };
+        campaign::award(progress, level, stars);
+        self.profiles.save();
+    }
+
+    /// Returns the best stars on each campaign level for the profile
+    /// seated at X, or for the session when there is none.
+    fn campaign_progress(&self) -> &[u8] {
+        self.seats[COMPUTER_SIDE.other().index()]
+            .as_deref()
+            .and_then(|name| self.profiles.get(name))
+            .map_or(&self.guest_campaign, |profile| &profile.campaign)
+    }
+
+    /// Starts the campaign level at `level` against the computer, under
+    /// the level's rules, with the player moving first.
+    fn start_campaign_level(&mut self, level: usize) {
+        self.leave_modes();
+        self.show_campaign = false;
+        let campaign = Campaign::new(level);
+        self.opponent = Opponent::Computer;
+        self.rules = campaign.current().rules;
+        self.campaign = Some(campaign);
+        self.start_game(COMPUTER_SIDE.other());
+    }
+
+    /// Keeps the finished game as the result of its day's challenge, if
+    /// the day has none yet.
+    fn record_daily(&mut self, winner: Option<Player>) {
+        let Some(challenge) = self.daily.filter(|_| !self.daily_practice) else {
+            return;
+        };
+        let player = COMPUTER_SIDE.other();
+        let outcome = match winner {
+            Some(winner) if winner == player => daily::Outcome::Won,
+            Some(_) => daily::Outcome::Lost,
+            None => daily::Outcome::Drew,
+        };
+        let moves = self
+            .moves
+            .iter()
+            .filter(|&&(_, mover)| mover == player)
+            .count();
+        let result = DailyResult {
+            outcome,
+            moves,
+            time: self.thinking_time[player.index()],
+        };
+        if self.dailies.record(challenge.date, result) {
+            info!(date = %challenge.date, ?result, "daily challenge recorded");
+        }
+    }
+
+    /// Starts today's Daily Challenge against the computer, or leaves the
+    /// one being played for a new game.
+    fn toggle_daily(&mut self, cx: &mut Context<Self>) {
+        let enable = self.daily.is_none();
+        self.leave_modes();
+        if !enable {
+            self.start_game(Player::X);
+            return;
+        }
+        let challenge = Challenge::for_date(chrono::Local::now().date_naive());
+        self.opponent = Opponent::Computer;
+        self.rules = challenge.rules;
+        self.daily = Some(challenge);
+        self.start_game(challenge.first);
+        self.play_computer_turn(cx);
+    }
+
+    /// Stores the finished game in the history database.
+    ///
+    /// A game that was taken back and finished again replaces its earlier
+    /// entry. Puzzle Rush answers are not games and are left out.
+    fn record_history(&mut self) {
+        let Some(history) = self.history.as_ref().filter(|_| self.puzzle_rush.is_none()) else {
+            return;
+        };
+        let entry = HistoryEntry {
+            id: self.history_id,
+            x_player: self.player_name(Player::X),
+            o_player: self.player_name(Player::O),
+            variant: self.variant_name(),
+            record: self.game_record(),
+            duration_secs: self.thinking_time.iter().sum::<Duration>().as_secs(),
+            played_at: chrono::Local::now(),
+            // The relay says where it recorded the game once it has
+            recording: None,
+        };
+        if let Ok(id) = history.record(&entry) {
+            self.history_id = Some(id);
+        }
+    }
+
+    /// Returns the name of whoever is playing `player`'s side: a seated
+    /// profile, the computer, the tournament or team player, or just the
+    /// side.
+    fn player_name(&self, player: Player) -> String {
+        if let Some(name) = &self.seats[player.index()] {
+            return name.clone();
+        }
+        if self.opponent == Opponent::Computer && player == COMPUTER_SIDE {
+            let name = if self.bot.is_some() {
+                "Bot"
+            } else {
+                "Computer"
+            };
+            return name.to_string();
+        }
+        let pairing = self
+            .tournament
+            .as_ref()
+            .and_then(Tournament::current_pairing)
+            .or_else(|| self.bracket.as_ref().and_then(Bracket::current_pairing));
+        if let Some((x, o)) = pairing {
+            return match player {
+                Player::X => x.to_string(),
+                Player::O => o.to_string(),
+            };
+        }
+        if let Some(teams) = &self.team_match {
+            return teams.team(player).name.clone();
+        }
+        format!("Player {player:?}")
+    }
+
+    /// Describes the rules the game is played under, such as "Misère,
+    /// Gravity", or "Standard" without any.
+    fn variant_name(&self) -> String {
+        let variants = [
+            (self.rules.misere, "Misère"),
+            (self.rules.gravity, "Gravity"),
+            (self.rules.topology == Topology::Torus, "Torus"),
+            (self.rules.expiring, "Expiring"),
+            (self.layout_seed.is_some(), "Blocked"),
+            (self.blind_mode, "Blind"),
+        ];
+        let names: Vec<_> = variants
+            .iter()
+            .filter(|(active, _)| *active)
+            .map(|(_, name)| *name)
+            .collect();
+        if names.is_empty() {
+            "Standard".to_string()
+        } else {
+            names.join(", ")
+        }
+    }
+
+    /// Opens the History screen on every game.
+    fn open_history(&mut self) {
+        let Some(history) = &self.history else {
+            self.file_error = Some("The game history could not be opened".into());
+            return;
+        };
+        let mut view = HistoryView::default();
+        view.refresh(history);
+        self.history_view = Some(view);
+    }
+
+    /// Changes the History screen's filters with `change`, then reloads the
+    /// games passing them.
+    fn filter_history(&mut self, change: impl FnOnce(&mut HistoryView)) {
+        let (Some(history), Some(view)) = (&self.history, self.history_view.as_mut()) else {
+            return;
+        };
+        change(view);
+        view.refresh(history);
+    }
+
+    /// Returns how strongly the computer plays: as the campaign level or
+    /// speedrun split calls for, or as set in the settings.
+    fn computer_difficulty(&self) -> Difficulty {
+        if let Some(campaign) = &self.campaign {
+            return campaign.current().difficulty;
+        }
+        if let Some(challenge) = &self.daily {
+            return challenge.difficulty;
+        }
+        self.speedrun
+            .as_ref()
+            .map_or(self.settings.difficulty, Speedrun::difficulty)
+    }
+
+    /// Returns the rating `player` is playing at: their profile's, or the
+    /// computer's fixed rating for its difficulty.
+    fn seat_rating(&self, player: Player) -> Option<f64> {
+        if self.opponent == Opponent::Computer && player == COMPUTER_SIDE {
+            return Some(profiles::computer_rating(self.computer_difficulty()));
+        }
+        let name = self.seats[player.index()].as_deref()?;
+        self.profiles.get(name).map(|profile| profile.rating)
+    }
+
+    /// Records a finished game in the seated profiles' statistics and, when
+    /// both sides have a rating, updates their ratings.
+    ///
+    /// Only normal play counts: not Puzzle Rush, tournaments, or team play.
+    /// The computer's rating stays fixed.
+    fn record_profiles(&mut self, winner: Option<Player>) {
+        let normal_play = self.puzzle_rush.is_none()
+            && self.tournament.is_none()
+            && self.bracket.is_none()
+            && self.team_match.is_none();
+        let same_profile = self.seats[0].is_some() && self.seats[0] == self.seats[1];
+        if !normal_play || same_profile || self.seats.iter().all(Option::is_none) {
+            return;
+        }
+
+        for player in [Player::X, Player::O] {
+            let opening = self
+                .moves
+                .iter()
+                .find(|&&(_, mover)| mover == player)
+                .map(|&(position, _)| (position.row(), position.col()));
+            if let Some(profile) = self.seats[player.index()]
+                .as_deref()
+                .and_then(|name| self.profiles.get_mut(name))
+            {
+                profile
+                    .stats
+                    .record(winner.map(|winner| winner == player), opening);
+            }
+        }
+        self.recorded = true;
+
+        if let (Some(x), Some(o)) = (self.seat_rating(Player::X), self.seat_rating(Player::O)) {
+            self.rate_game(winner, x, o);
+        }
+        self.profiles.save();
+    }
+
+    /// Moves the seated profiles' ratings after a game between sides rated
+    /// `x` and `o`.
+    fn rate_game(&mut self, winner: Option<Player>, x: f64, o: f64) {
+        for (player, rating, opponent) in [(Player::X, x, o), (Player::O, o, x)] {
+            let score = match winner {
+                Some(winner) if winner == player => 1.0,
+                Some(_) => 0.0,
+                None => 0.5,
+            };
+            let change = profiles::rating_change(rating, opponent, score);
+            if let Some(profile) = self.seats[player.index()]
+                .as_deref()
+                .and_then(|name| self.profiles.get_mut(name))
+            {
+                profile.rating += change;
+            }
+        }
+    }
+
+    /// Seats the profile called `name` on `player`'s side, or unseats it if
+    /// it is already there.
+    fn toggle_seat(&mut self, player: Player, name: &str) {
+        let seat = &mut self.seats[player.index()];
+        *seat = (seat.as_deref() != Some(name)).then(|| name.to_string());
+    }
+
+    /// Saves the setup wizard's answers and starts a new game with them:
+    /// against the chosen opponent, with the player's profile, created if
+    /// need be, seated at X.
+    fn finish_setup(&mut self) {
+        let Some(setup) = self.setup.take() else {
+            return;
+        };
+        setup.apply(&mut self.settings);
+        self.settings.save();
+        if let Some(name) = self.settings.player_name.clone() {
+            self.profiles.add(&name);
+            self.profiles.save();
+            self.seats[Player::X.index()] = Some(name);
+        }
+        self.leave_modes();
+        self.opponent = self.settings.opponent;
+        self.start_game(Player::X);
+    }
+
+    /// Closes the setup wizard without changing anything, so it is not
+    /// shown again.
+    fn skip_setup(&mut self) {
+        self.setup = None;
+        self.settings.setup_done = true;
+        self.settings.save();
+    }
+
+    /// Deletes a profile, freeing any side it was seated on.
+    fn remove_profile(&mut self, name: &str) {
+        self.profiles.remove(name);
+        self.profiles.save();
+        for seat in &mut self.seats {
+            if seat.as_deref() == Some(name) {
+                *seat = None;
+            }
+        }
+    }
+
+    /// Starts the next game of the series or speedrun after a short pause,
+    /// unless the game is still going or the series or run is over.
+    fn continue_series(&mut self, cx: &mut Context<Self>) {
+        if !self.game_over() || self.next_series_first().is_none() {
+            return;
+        }
+        self.series_task = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(SERIES_NEXT_GAME_DELAY).await;
+            let _ = this.update(cx, |this, cx| {
+                // Skip if the game was already restarted or the series left
+                let Some(first) = this.next_series_first().filter(|_| this.game_over()) else {
+                    return;
+                };
+                this.start_game(first);
+                this.play_computer_turn(cx);
+                cx.notify();
+            });
+        }));
+    }
+
+    /// Returns who moves first in the next game of an undecided series or
+    /// an unfinished speedrun, where the player always opens, or `None`
+    /// when neither is going.
+    fn next_series_first(&self) -> Option<Player> {
+        if let Some(series) = self.series.as_ref() {
+            return series.champion().is_none().then(|| series.next_first());
+        }
+        let run = self.speedrun.as_ref()?;
+        (!run.is_finished()).then_some(COMPUTER_SIDE.other())
+    }
+
+    /// Starts a speedrun against the computer, or leaves the one in
+    /// progress.
+    fn toggle_speedrun(&mut self) {
+        let enable = self.speedrun.is_none();
+        self.leave_modes();
+        if enable {
+            self.opponent = Opponent::Computer;
+            self.speedrun = Some(Speedrun::start());
+        }
+        self.start_game(COMPUTER_SIDE.other());
+    }
+
+    /// Cycles series play through best of 3, 5, and 7 and back off,
+    /// starting a new series from the first game.
+    fn cycle_series(&mut self, cx: &mut Context<Self>) {
+        let length = series::next_length(self.series.as_ref().map(|series| series.best_of));
+        self.series = length.map(Series::new);
+        self.series_task = None;
+        self.start_game(Player::X);
+        self.play_computer_turn(cx);
+    }
+
+    /// Resets the game to its initial state.
+    ///
+    /// With the "loser starts" rule enabled, the loser of the finished game
+    /// (or O after a draw) moves first instead of X. Otherwise a random
+    /// start flips a coin for the first move. In a series the first move
+    /// alternates instead, and a decided series starts over.
+    fn reset(&mut self, cx: &mut Context<Self>) {
+        // The game in a dropped online room resumes once it is rejoined
+        if self.reconnecting.is_some() {
+            return;
+        }
+        // In a LAN or online game X starts every game, so O asks for one
+        // instead and a spectator waits for it
+        match self.remote.as_ref().map(|remote| remote.side) {
+            Some(Some(Player::O)) => {
+                self.send_remote(&Message::NewGame);
+                return;
+            }
+            Some(None) => return,
+            _ => {}
+        }
+        if let Some(series) = self.series.as_mut() {
+            if series.champion().is_some() {
+                *series = Series::new(series.best_of);
+            }
+            let first = series.next_first();
+            self.series_task = None;
+            self.start_game(first);
+        } else if let Some(challenge) = self.daily {
+            self.start_game(challenge.first);
+        } else if self.loser_starts && self.game_over() {
+            self.start_game(self.winner().map_or(Player::O, Player::other));
+        } else if self.settings.random_start && self.remote.is_none() {
+            self.flip_for_first_move(cx);
+        } else {
+            self.start_game(Player::X);
+        }
+        self.send_remote_start();
+        self.play_computer_turn(cx);
+    }
+
+    /// Starts a new game whose first player is decided by a coin flip.
+    ///
+    /// The board stays locked while the coin is animated, and unlocks once
+    /// it has landed on the player to move first.
+    fn flip_for_first_move(&mut self, cx: &mut Context<Self>) {
+        let seed = rand::random();
+        let flip = CoinFlip::new(seed);
+        self.start_game(flip.result);
+        self.coin_seed = Some(seed);
+        self.coin_flip = Some(flip);
+        self.coin_flip_task = Some(cx.spawn(async move |this, cx| loop {
+            cx.background_executor().timer(COIN_FLIP_INTERVAL).await;
+            let landed = this
+                .update(cx, |this, cx| {
+                    let landed = match this.coin_flip.as_mut() {
+                        Some(flip) if !flip.has_landed() => {
+                            flip.step();
+                            false
+                        }
+                        _ => {
+                            // The first turn starts once the coin lands
+                            this.coin_flip = None;
+                            this.turn_started = Instant::now();
+                            this.play_computer_turn(cx);
+                            true
+                        }
+                    };
+                    cx.notify();
+                    landed
+                })
+                .unwrap_or(true);
+            if landed {
+                break;
+            }
+        }));
+    }
+
+    /// Starts a new game with the other player moving first, or with a
+    /// coin flip deciding when random starts are on.
+    ///
+    /// In a network game this asks for a rematch, or agrees to the one the
+    /// opponent asked for. Once both sides have, X starts it.
+    fn rematch(&mut self, cx: &mut Context<Self>) {
+        if self.reconnecting.is_some() {
+            return;
+        }
+        if let Some(remote) = self.remote.as_mut() {
+            if remote.side.is_none() || remote.rematch == Some(Rematch::Requested) {
+                return;
+            }
+            let agreed = remote.rematch == Some(Rematch::Offered);
+            remote.rematch = Some(Rematch::Requested);
+            let side = remote.side;
+            self.send_remote(&Message::Rematch);
+            if !agreed || side != Some(Player::X) {
+                return;
+            }
+        }
+        if self.settings.random_start && self.remote.is_none() {
+            self.flip_for_first_move(cx);
+        } else {
+            self.start_game(self.starting_player.other());
+        }
+        self.send_remote_start();
+        self.play_computer_turn(cx);
+    }
+
+    /// Clears the board and starts a new game with `first` to move.
+    fn start_game(&mut self, first: Player) {
+        if !self.moves.is_empty() {
+            self.previous_moves = std::mem::take(&mut self.moves);
+        }
+        if let Some(remote) = self.remote.as_mut() {
+            remote.rematch = None;
+        }
+        self.redo_stack.clear();
+        self.game = GameState::new(first, [[Cell::Empty; 3]; 3], self.rules);
+        self.initial_board = *self.game.board();
+        self.exit_replay();
+        self.annotations.clear();
+        self.hosted_link = None;
+        self.hint = None;
+        self.starting_player = first;
+        self.thinking_time = [Duration::ZERO; 2];
+        self.turn_started = Instant::now();
+        self.result = None;
+        self.draw_offer = None;
+        self.move_error = None;
+        self.recorded = false;
+        self.history_id = None;
+        self.clock_budget = self
+            .settings
+            .clock_minutes
+            .map(|minutes| Duration::from_secs(minutes * 60));
+        self.clock_increment =
+            Duration::from_secs(self.settings.clock_increment_secs.unwrap_or_default());
+        self.confirming_resign = false;
+        self.coin_seed = None;
+        self.coin_flip = None;
+        self.coin_flip_task = None;
+        self.computer_task = None;
+        self.hidden = [[false; 3]; 3];
+        self.fade_tasks.clear();
+        self.layout_seed = None;
+        self.daily_practice = false;
+        if let Some(challenge) = self.daily {
+            self.daily_practice = self.dailies.result(challenge.date).is_some();
+            if let Some(seed) = challenge.layout {
+                self.apply_layout(seed);
+            }
+        } else if self.blocked_cells {
+            self.apply_layout(rand::random());
+        }
+        info!(?first, rules = ?self.rules, "new game");
+        // The Play Again button goes away, so its focus returns to the board
+        if self.key_focus == Some(KeyFocus::ResetButton) {
+            self.key_focus = Some(KeyFocus::Cell(Position::CENTER));
+        }
+        self.emit(GameEvent::Reset { first });
+        self.autosave();
+    }
+
+    /// Blocks off the cells picked by `seed` on the empty starting board.
+    fn apply_layout(&mut self, seed: u64) {
+        self.layout_seed = Some(seed);
+        self.initial_board = blocked::layout(seed);
+        self.game = GameState::new(self.game.to_move(), self.initial_board, self.rules);
+    }
+
+    /// Switches the blocked cells variant on or off, starting a new game.
+    fn toggle_blocked_cells(&mut self, cx: &mut Context<Self>) {
+        self.blocked_cells = !self.blocked_cells;
+        self.start_game(Player::X);
+        self.play_computer_turn(cx);
+    }
+
+    /// Copies the current layout's seed so it can be shared.
+    fn copy_layout_seed(&self, cx: &mut Context<Self>) {
+        if let Some(seed) = self.layout_seed {
+            cx.write_to_clipboard(ClipboardItem::new_string(blocked::format_seed(seed)));
+        }
+    }
+
+    /// Starts a new game on the layout whose seed is on the clipboard.
+    fn paste_layout_seed(&mut self, cx: &mut Context<Self>) {
+        let seed = cx
+            .read_from_clipboard()
+            .and_then(|item| item.text())
+            .and_then(|text| blocked::parse_seed(&text));
+        let Some(seed) = seed else {
+            self.file_error = Some("The clipboard does not hold a layout seed".into());
+            return;
+        };
+        self.file_error = None;
+        self.start_game(Player::X);
+        self.apply_layout(seed);
+        self.play_computer_turn(cx);
+    }
+
+    /// Starts a Puzzle Rush run, or leaves the one in progress for a new
+    /// game.
+    fn toggle_puzzle_rush(&mut self, cx: &mut Context<Self>) {
+        if self.puzzle_rush.is_some() {
+            self.leave_modes();
+            self.start_game(Player::X);
+        } else {
+            self.leave_modes();
+            self.start_puzzle_rush(cx);
+        }
+    }
+
+    /// Switches 2v2 team play on or off, starting a new game.
+    fn toggle_team_mode(&mut self) {
+        let enable = self.team_match.is_none();
+        self.leave_modes();
+        if enable {
+            self.team_match = Some(TeamMatch::new());
+        }
+        self.start_game(Player::X);
+    }
+
+    /// Switches between playing the computer and another person, starting
+    /// a new game.
+    fn toggle_computer_opponent(&mut self) {
+        let enable = self.opponent == Opponent::Human;
+        self.leave_modes();
+        if enable {
+            self.opponent = Opponent::Computer;
+        }
+        self.start_game(Player::X);
+    }
+
+    /// Returns which board is being played on.
+    fn layout(&self) -> BoardLayout {
+        if self.ultimate.is_some() {
+            BoardLayout::Ultimate
+        } else if self.qubic.is_some() {
+            BoardLayout::Qubic
+        } else if self.notakto.is_some() {
+            BoardLayout::Notakto
+        } else if self.pentago.is_some() {
+            BoardLayout::Pentago
+        } else if self.connect_k.is_some() {
+            BoardLayout::ConnectK
+        } else if self.simul.is_some() {
+            BoardLayout::Simul
+        } else {
+            BoardLayout::Grid
+        }
+    }
+
+    /// Switches `variant` on or off, starting a new game.
+    fn toggle_variant(&mut self, variant: Variant) {
+        let enable = self.layout() != variant.layout;
+        self.leave_modes();
+        if enable {
+            (variant.setup)(self);
+        }
+        self.start_game(Player::X);
+    }
+
+    /// Leaves Puzzle Rush, tournaments, team play, computer play, and the
+    /// Ultimate, Qubic, Notakto, Pentago, Connect-K, and Simul variants.
+    fn leave_modes(&mut self) {
+        self.pentago = None;
+        self.connect_k = None;
+        self.simul = None;
+        self.ultimate = None;
+        self.qubic = None;
+        self.notakto = None;
+        self.opponent = Opponent::Human;
+        self.computer_task = None;
+        self.puzzle_rush = None;
+        self.puzzle_rush_task = None;
+        self.tournament_setup = None;
+        self.tournament = None;
+        self.bracket = None;
+        self.team_match = None;
+        self.series = None;
+        self.series_task = None;
+        self.speedrun = None;
+        if self.campaign.take().is_some() {
+            self.rules = RuleSet::default();
+        }
+        if self.daily.take().is_some() {
+            self.rules = RuleSet::default();
+        }
+        self.editor = None;
+    }
+
+    /// Opens the position editor on the current board, or closes it and
+    /// starts a new game.
+    fn toggle_editor(&mut self) {
+        if self.editor.is_some() {
+            self.leave_modes();
+            self.start_game(Player::X);
+        } else {
+            self.leave_modes();
+            self.editor = Some(PositionEditor::new(*self.game.board(), self.game.to_move()));
+        }
+    }
+
+    /// Starts play from the edited position if it is valid.
+    fn play_from_editor(&mut self, cx: &mut Context<Self>) {
+        let Some(editor) = self.editor else {
+            return;
+        };
+        let Ok(first) = editor.validate() else {
+            return;
+        };
+        self.editor = None;
+        self.play_position(editor, first, cx);
+    }
+
+    /// Starts a game from a checked position, with `first` as the player
+    /// who made the first of its marks.
+    fn play_position(&mut self, editor: PositionEditor, first: Player, cx: &mut Context<Self>) {
+        self.start_game(first);
+        self.layout_seed = None;
+        self.game = GameState::new(editor.to_move, editor.board, self.rules);
+        self.initial_board = editor.board;
+        self.emit(GameEvent::TurnChanged(editor.to_move));
+        self.event_log.push(LogEvent::CustomPosition {
+            to_move: editor.to_move,
+        });
+        self.play_computer_turn(cx);
+    }
+
+    /// Opens the tournament setup screen, or leaves the tournament in
+    /// progress for a new game.
+    fn toggle_tournament(&mut self) {
+        let open =
+            self.tournament_setup.is_none() && self.tournament.is_none() && self.bracket.is_none();
+        self.leave_modes();
+        if open {
+            self.tournament_setup = Some(TournamentSetup::default());
+        }
+        self.start_game(Player::X);
+    }
+
+    /// Starts the tournament with the entered players.
+    fn start_tournament(&mut self) {
+        let Some(setup) = self.tournament_setup.take_if(|setup| setup.can_start()) else {
+            return;
+        };
+        match setup.format {
+            Format::RoundRobin => self.tournament = Some(Tournament::round_robin(setup.names)),
+            Format::Bracket => self.bracket = Some(Bracket::new(setup.names)),
+        }
+        self.start_game(Player::X);
+    }
+
+    /// Records the finished tournament game and starts the next pairing.
+    fn next_tournament_game(&mut self) {
+        let winner = self.winner();
+        if let Some(bracket) = self.bracket.as_mut() {
+            bracket.record(winner);
+            if bracket.champion().is_none() {
+                self.start_game(Player::X);
+            }
+            return;
+        }
+        let Some(tournament) = self.tournament.as_mut() else {
+            return;
+        };
+        tournament.record(winner);
+        if !tournament.is_complete() {
+            self.start_game(Player::X);
+        }
+    }
+
+    /// Handles typing on the tournament setup screen.
+    fn handle_key_down(&mut self, event: &KeyDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+        let keystroke = &event.keystroke;
+        if let Some(setup) = self.setup.as_mut() {
+            match (setup.step, keystroke.key.as_str()) {
+                (Step::Opponent, "enter") => setup.step = Step::Name,
+                (Step::Name, "enter") => self.finish_setup(),
+                (Step::Name, "backspace") => {
+                    setup.name.pop();
+                }
+                (Step::Name, _)
+                    if !keystroke.modifiers.control && !keystroke.modifiers.platform =>
+                {
+                    if let Some(text) = &keystroke.key_char {
+                        setup.type_name(text);
+                    }
+                }
+                _ => {}
+            }
+            return;
+        }
+        if self.show_lan && self.remote.is_none() {
+            match keystroke.key.as_str() {
+                "enter" if self.show_lan_options => self.join_lan(cx),
+                "enter" => self.join_room(Entry::Join, cx),
+                "backspace" => {
+                    self.network_input.pop();
+                }
+                _ if !keystroke.modifiers.control && !keystroke.modifiers.platform => {
+                    if let Some(text) = &keystroke.key_char {
+                        self.network_input.push_str(text);
+                    }
+                }
+                _ => {}
+            }
+            return;
+        }
+        // Typing goes to the open chat, except for spectators, who only
+        // read it
+        if self.show_chat
+            && self
+                .remote
+                .as_ref()
+                .is_some_and(|remote| remote.side.is_some())
+        {
+            match keystroke.key.as_str() {
+                "enter" => {
+                    let text = std::mem::take(&mut self.chat_input);
+                    self.send_chat(&text);
+                }
+                "backspace" => {
+                    self.chat_input.pop();
+                }
+                _ if !keystroke.modifiers.control && !keystroke.modifiers.platform => {
+                    if let Some(text) = &keystroke.key_char {
+                        if self.chat_input.chars().count() < chat::MAX_LINE_LEN {
+                            self.chat_input.push_str(text);
+                        }
+                    }
+                }
+                _ => {}
+            }
+            return;
+        }
+        if self.editing_comment {
+            let Some(index) = self.annotated_move() else {
+                self.editing_comment = false;
+                return;
+            };
+            let annotation = self.annotations.entry(index).or_default();
+            match keystroke.key.as_str() {
+                "enter" | "escape" => self.editing_comment = false,
+                "backspace" => {
+                    annotation.comment.pop();
+                }
+                _ if !keystroke.modifiers.control && !keystroke.modifiers.platform => {
+                    if let Some(text) = &keystroke.key_char {
+                        // Braces would end the comment early in notation
+                        annotation
+                            .comment
+                            .extend(text.chars().filter(|&c| c != '{' && c != '}'));
+                    }
+                }
+                _ => {}
+            }
+            if annotation.is_empty() {
+                self.annotations.remove(&index);
+            }
+            return;
+        }
+        if self.show_profiles {
+            match keystroke.key.as_str() {
+                "enter" => {
+                    self.profiles.add(&self.profile_input);
+                    self.profiles.save();
+                    self.profile_input.clear();
+                }
+                "backspace" => {
+                    self.profile_input.pop();
+                }
+                _ if !keystroke.modifiers.control && !keystroke.modifiers.platform => {
+                    if let Some(text) = &keystroke.key_char {
+                        self.profile_input.push_str(text);
+                    }
+                }
+                _ => {}
+            }
+            return;
+        }
+        let Some(setup) = self.tournament_setup.as_mut() else {
+            return;
+        };
+        match keystroke.key.as_str() {
+            "enter" => setup.add_name(),
+            "backspace" => {
+                setup.input.pop();
+            }
+            _ if !keystroke.modifiers.control && !keystroke.modifiers.platform => {
+                if let Some(text) = &keystroke.key_char {
+                    setup.input.push_str(text);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Starts hosting a LAN game and waits for another player to join.
+    fn host_lan(&mut self, cx: &mut Context<Self>) {
+        self.disconnect();
+        match Host::listen(net::DEFAULT_PORT, &self.host_name()) {
+            Ok(host) => {
+                self.lan_host = Some(host);
+                self.poll_remote(cx);
+            }
+            Err(err) => self.file_error = Some(format!("Could not host: {err}")),
+        }
+    }
+
+    /// Returns the name a hosted LAN game is advertised under: the
+    /// profile playing X, or else the name of the account running the
+    /// game.
+    fn host_name(&self) -> String {
+        self.seats[Player::X.index()]
+            .clone()
+            .or_else(|| std::env::var("USER").ok())
+            .or_else(|| std::env::var("USERNAME").ok())
+            .unwrap_or_else(|| "Someone".to_string())
+    }
+
+    /// Shows the LAN options and starts looking for games hosted on the
+    /// network. The search stops once the options are hidden or a game
+    /// begins.
+    fn show_lan_options(&mut self, cx: &mut Context<Self>) {
+        self.show_lan_options = true;
+        // Without discovery, addresses can still be typed in
+        self.lan_browser = Browser::start().ok();
+        if self.lan_browser.is_none() {
+            return;
+        }
+        self.browse_task = Some(cx.spawn(async move |this, cx| loop {
+            cx.background_executor()
+                .timer(DISCOVERY_POLL_INTERVAL)
+                .await;
+            let browsing = this
+                .update(cx, |this, cx| {
+                    let browsing = this.show_lan
+                        && this.show_lan_options
+                        && this.remote.is_none()
+                        && this.lan_host.is_none();
+                    match this.lan_browser.as_mut() {
+                        Some(browser) if browsing => {
+                            if browser.poll() {
+                                cx.notify();
+                            }
+                        }
+                        _ => this.lan_browser = None,
+                    }
+                    browsing
+                })
+                .unwrap_or(false);
+            if !browsing {
+                break;
+            }
+        }));
+    }
+
+    /// Joins the LAN game hosted at the address typed in the LAN panel.
+    fn join_lan(&mut self, cx: &mut Context<Self>) {
+        self.disconnect();
+        self.connecting = true;
+        let address = self.network_input.clone();
+        let connecting = cx
+            .background_executor()
+            .spawn(async move { net::join(&address) });
+        self.remote_task = Some(cx.spawn(async move |this, cx| {
+            let result = connecting.await;
+            this.update(cx, |this, cx| {
+                this.connecting = false;
+                match result {
+                    Ok(connection) => {
+                        this.connect_remote(connection, Some(Player::O), None, true);
+                        this.poll_remote(cx);
+                    }
+                    Err(err) => this.report_connection_error("Could not join", &err),
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    /// Joins the game an invite link points to, showing the network panel
+    /// while connecting.
+    fn accept_invite(&mut self, invite: Invite, cx: &mut Context<Self>) {
+        self.show_lan = true;
+        match invite {
+            Invite::Lan(address) => {
+                self.show_lan_options = true;
+                self.network_input = address.to_string();
+                self.join_lan(cx);
+            }
+            Invite::Room(code) => {
+                self.show_lan_options = false;
+                self.network_input = code.clone();
+                self.enter_room(code, Entry::Join, cx);
+            }
+        }
+    }
+
+    /// Creates an online room under a new code, to be shared with the
+    /// opponent.
+    fn create_room(&mut self, cx: &mut Context<Self>) {
+        self.enter_room(online::new_room_code(), Entry::Create, cx);
+    }
+
+    /// Enters the online room whose code is typed in the network panel,
+    /// to play when `entry` is [`Entry::Join`] or to look on when it is
+    /// [`Entry::Watch`].
+    fn join_room(&mut self, entry: Entry, cx: &mut Context<Self>) {
+        match online::parse_room_code(&self.network_input) {
+            Some(code) => self.enter_room(code, entry, cx),
+            None => {
+                self.file_error = Some(format!(
+                    "Room codes are {} letters and digits",
+                    online::ROOM_CODE_LEN
+                ));
+            }
+        }
+    }
+
+    /// Creates, joins, or watches the online room `room` through the relay
+    /// named in the settings, asking it to record the games if the
+    /// settings say to.
+    fn enter_room(&mut self, room: String, entry: Entry, cx: &mut Context<Self>) {
+        self.disconnect();
+        self.connecting = true;
+        self.file_error = None;
+        let url = self.settings.relay_url.clone();
+        let record = self.settings.record_online_games;
+        let joining = cx.background_executor().spawn({
+            let room = room.clone();
+            async move { online::join(&url, &room, entry, record) }
+        });
+        self.remote_task = Some(cx.spawn(async move |this, cx| {
+            let result = joining.await;
+            this.update(cx, |this, cx| {
+                this.connecting = false;
+                match result {
+                    Ok(seat) => {
+                        this.connect_remote(
+                            Connection::Online(seat.relay),
+                            seat.side,
+                            Some(room),
+                            seat.opponent_present,
+                        );
+                        this.poll_remote(cx);
+                    }
+                    Err(err) => this.report_connection_error("Could not join room", &err),
+                }
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    /// Starts a LAN or online game over `connection`, playing `side` or
+    /// watching when it is `None`. X starts the first game straight away,
+    /// and a spectator's board is cleared until the relay sends the game
+    /// under way.
+    fn connect_remote(
+        &mut self,
+        connection: Connection,
+        side: Option<Player>,
+        room: Option<String>,
+        opponent_present: bool,
+    ) {
+        self.leave_modes();
+        // The lobby stays open with the room code until the opponent is in
+        self.show_lan = side.is_some() && !opponent_present;
+        self.file_error = None;
+        self.remote = Some(RemoteGame {
+            side,
+            connection,
+            room,
+            opponent_present,
+            spectators: 0,
+            chat: Chat::default(),
+            takeback: None,
+            rematch: None,
+            score: Score::default(),
+        });
+        if side != Some(Player::O) {
+            self.start_game(Player::X);
+            self.send_remote_start();
+        }
+    }
+
+    /// Checks the network on a timer until the game ends.
+    fn poll_remote(&mut self, cx: &mut Context<Self>) {
+        self.remote_task = Some(cx.spawn(async move |this, cx| loop {
+            cx.background_executor().timer(NETWORK_POLL_INTERVAL).await;
+            let open = this
+                .update(cx, |this, cx| {
+                    let open = this.receive_remote(cx);
+                    cx.notify();
+                    open
+                })
+                .unwrap_or(false);
+            if !open {
+                break;
+            }
+        }));
+    }
+
+    /// Accepts a joining player while hosting and handles any messages
+    /// from the other side. Returns whether the game is still open.
+    fn receive_remote(&mut self, cx: &mut Context<Self>) -> bool {
+        if let Some(host) = &self.lan_host {
+            match host.accept() {
+                Ok(Some(connection)) => {
+                    self.lan_host = None;
+                    self.connect_remote(connection, Some(Player::X), None, true);
+                }
+                Ok(None) => return true,
+                Err(err) => {
+                    self.lan_host = None;
+                    self.file_error = Some(format!("Could not host: {err}"));
+                    return false;
+                }
+            }
+        }
+        let Some(remote) = self.remote.as_mut() else {
+            return false;
+        };
+        let events = match remote.connection.receive() {
+            Ok(events) => events,
+            Err(err) => {
+                // An online game tries to rejoin its room, while a LAN game
+                // has nowhere to resume from
+                match self.remote.take() {
+                    Some(RemoteGame {
+                        room: Some(room),
+                        side,
+                        chat,
+                        score,
+                        ..
+                    }) if net::version_mismatch(&err).is_none() => self.reconnect(
+                        Reconnecting {
+                            room,
+                            side,
+                            chat,
+                            score,
+                            attempts: 0,
+                        },
+                        cx,
+                    ),
+                    _ => self.report_connection_error("Lost the connection", &err),
+                }
+                return false;
+            }
+        };
+        for event in events {
+            match event {
+                Event::Message(message) => {
+                    debug!(?message, "received");
+                    self.handle_remote_message(message, cx);
+                }
+                Event::OpponentJoined => {
+                    if let Some(remote) = self.remote.as_mut() {
+                        remote.opponent_present = true;
+                    }
+                    self.show_lan = false;
+                    // X starts a fresh game for each new arrival, since
+                    // they missed any game already under way
+                    self.reset(cx);
+                }
+                Event::OpponentReturned => {
+                    if let Some(remote) = self.remote.as_mut() {
+                        remote.opponent_present = true;
+                    }
+                }
+                Event::OpponentLeft => {
+                    if let Some(remote) = self.remote.as_mut() {
+                        remote.opponent_present = false;
+                    }
+                }
+                Event::Resync {
+                    first,
+                    board,
+                    rules,
+                    moves,
+                    ending,
+                } => self.resync(first, board, rules, moves, ending),
+                Event::Spectators(count) => {
+                    if let Some(remote) = self.remote.as_mut() {
+                        remote.spectators = count;
+                    }
+                }
+                Event::Rejected(reason) => {
+                    warn!(%reason, "relay refused a message");
+                    self.file_error = Some(format!("The relay refused the last message: {reason}"));
+                }
+                Event::Recorded(id) => self.link_recording(&id),
+            }
+        }
+        true
+    }
+
+    /// Applies a message from the other player, or for a spectator from
+    /// either player. Moves made out of turn or on a taken cell are
+    /// ignored.
+    fn handle_remote_message(&mut self, message: Message, cx: &mut Context<Self>) {
+        let Some(side) = self.remote.as_ref().map(|remote| remote.side) else {
+            return;
+        };
+        match message {
+            Message::Start {
+                first,
+                board,
+                rules,
+            } if side != Some(Player::X) => {
+                self.leave_modes();
+                self.rules = rules;
+                self.start_game(first);
+                self.layout_seed = None;
+                self.game = GameState::new(first, board, rules);
+                self.initial_board = board;
+            }
+            Message::Move { row, col } => {
+                // Refused in the same order as the relay refuses moves
+                let result = if side == Some(self.game.to_move()) {
+                    Err(MoveError::NotYourTurn)
+                } else if self.game_over() {
+                    Err(MoveError::GameOver)
+                } else {
+                    Position::new(row, col)
+                        .ok_or(MoveError::OutOfBounds)
+                        .and_then(|position| self.make_move(position, cx))
+                };
+                match result {
+                    Ok(_) => self.set_takeback(None),
+                    Err(err) => {
+                        self.file_error = Some(format!("Refused a move from the other side: {err}"))
+                    }
+                }
+            }
+            Message::TakebackRequest { moves } if side.is_some() => {
+                self.set_takeback(Some(Takeback::Offered(moves)));
+            }
+            // Only an answer to this side's own request counts, while
+            // spectators follow both players
+            Message::TakebackAnswer { moves, accepted }
+                if side.is_none() || self.takeback() == Some(Takeback::Requested) =>
+            {
+                if accepted {
+                    self.roll_back_move(moves);
+                }
+                // Spectators only follow the outcome
+                if side.is_some() {
+                    self.set_takeback((!accepted).then_some(Takeback::Declined));
+                }
+            }
+            // A player may only resign their own side, and only answer a
+            // draw this side offered, while spectators follow both players
+            Message::Resign { player } if side != Some(player) => self.concede(player),
+            Message::DrawOffer if side.is_some() => self.draw_offer = side.map(Player::other),
+            Message::DrawAnswer { accepted } if side.is_none() || self.draw_offer == side => {
+                self.draw_offer = None;
+                if accepted {
+                    self.agree_draw();
+                }
+            }
+            Message::Rematch if side.is_some() => {
+                let agreed = self.rematch_state() == Some(Rematch::Requested);
+                if let Some(remote) = self.remote.as_mut() {
+                    remote.rematch = Some(Rematch::Offered);
+                }
+                if agreed && side == Some(Player::X) {
+                    self.start_game(self.starting_player.other());
+                    self.send_remote_start();
+                }
+            }
+            Message::NewGame if side == Some(Player::X) => self.reset(cx),
+            Message::Chat { from, text } => {
+                let show_chat = self.show_chat;
+                if let Some(remote) = self.remote.as_mut() {
+                    remote.chat.push(from, text);
+                    if !show_chat {
+                        remote.chat.unread += 1;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Tells O about the game X has just started.
+    fn send_remote_start(&mut self) {
+        if self
+            .remote
+            .as_ref()
+            .is_some_and(|remote| remote.side == Some(Player::X))
+        {
+            self.send_remote(&Message::Start {
+                first: self.starting_player,
+                board: self.initial_board,
+                rules: self.rules,
+            });
+        }
+    }
+
+    /// Sends `message` to the other player, ending the game if it cannot
+    /// be delivered.
+    fn send_remote(&mut self, message: &Message) {
+        let Some(remote) = self.remote.as_mut() else {
+            return;
+        };
+        debug!(?message, "sending");
+        if let Err(err) = remote.connection.send(message) {
+            warn!(%err, "could not send");
+            // An online game notices on its next poll and rejoins, getting
+            // back any move lost here from the relay
+            if remote.room.is_none() {
+                self.disconnect();
+                self.file_error = Some(format!("Lost the connection: {err}"));
+            }
+        }
+    }
+
+    /// Shows why a connection failed after `context`, or opens the version
+    /// dialog when the other side speaks another protocol version.
+    fn report_connection_error(&mut self, context: &str, err: &io::Error) {
+        warn!(%err, "{context}");
+        match net::version_mismatch(err) {
+            Some(theirs) => self.incompatible_version = Some(theirs),
+            None => self.file_error = Some(format!("{context}: {err}")),
+        }
+    }
+
+    /// Tries to rejoin an online room after the connection dropped,
+    /// waiting longer after each failed attempt, and gives up after
+    /// [`MAX_RECONNECT_ATTEMPTS`].
+    fn reconnect(&mut self, reconnecting: Reconnecting, cx: &mut Context<Self>) {
+        let url = self.settings.relay_url.clone();
+        let room = reconnecting.room.clone();
+        let entry = reconnecting.side.map_or(Entry::Watch, Entry::Rejoin);
+        let record = self.settings.record_online_games;
+        self.reconnecting = Some(reconnecting);
+        self.remote_task = Some(cx.spawn(async move |this, cx| {
+            let mut delay = RECONNECT_DELAY;
+            for attempt in 1..=MAX_RECONNECT_ATTEMPTS {
+                cx.background_executor().timer(delay).await;
+                delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+                let result = cx
+                    .background_executor()
+                    .spawn({
+                        let url = url.clone();
+                        let room = room.clone();
+                        async move { online::join(&url, &room, entry, record) }
+                    })
+                    .await;
+                let done = this
+                    .update(cx, |this, cx| {
+                        let Some(reconnecting) = this.reconnecting.as_mut() else {
+                            return true;
+                        };
+                        reconnecting.attempts = attempt;
+                        let done = match result {
+                            Ok(seat) => {
+                                this.resume_remote(seat, cx);
+                                true
+                            }
+                            Err(err)
+                                if attempt == MAX_RECONNECT_ATTEMPTS
+                                    || net::version_mismatch(&err).is_some() =>
+                            {
+                                this.reconnecting = None;
+                                this.report_connection_error("Lost the connection", &err);
+                                true
+                            }
+                            Err(_) => false,
+                        };
+                        cx.notify();
+                        done
+                    })
+                    .unwrap_or(true);
+                if done {
+                    break;
+                }
+            }
+        }));
+    }
+
+    /// Carries on the online game being rejoined over `seat`. The relay
+    /// follows up with the game so far.
+    fn resume_remote(&mut self, seat: Seat, cx: &mut Context<Self>) {
+        let Some(reconnecting) = self.reconnecting.take() else {
+            return;
+        };
+        self.remote = Some(RemoteGame {
+            side: seat.side,
+            connection: Connection::Online(seat.relay),
+            room: Some(reconnecting.room),
+            opponent_present: seat.opponent_present,
+            spectators: 0,
+            chat: reconnecting.chat,
+            takeback: None,
+            rematch: None,
+            score: reconnecting.score,
+        });
+        self.poll_remote(cx);
+    }
+
+    /// Links the finished game's history entry to the relay's recording of
+    /// it under `id`.
+    fn link_recording(&mut self, id: &str) {
+        let (Some(history), Some(history_id)) = (&self.history, self.history_id) else {
+            return;
+        };
+        let link = online::recording_link(&self.settings.relay_url, id);
+        if let Err(err) = history.set_recording(history_id, &link) {
+            warn!(%err, "could not link the recorded game");
+        }
+    }
+
+    /// Fetches the game the relay recorded behind `link` and opens it in
+    /// the replay view.
+    fn open_recording(&mut self, link: online::ReplayLink, cx: &mut Context<Self>) {
+        self.file_error = None;
+        let fetch = cx
+            .background_executor()
+            .spawn(async move { online::fetch_recording(&link) });
+        cx.spawn(async move |this, cx| {
+            let result = fetch.await;
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(game) => {
+                        this.history_view = None;
+                        this.restore_game(game.into());
+                        this.start_replay(cx);
+                    }
+                    Err(err) => {
+                        this.file_error = Some(format!("Could not open the recording: {err}"))
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Replays the relay's copy of the game after rejoining a room, unless
+    /// the board already matches it, and applies the resignation or agreed
+    /// draw that ended it.
+    fn resync(
+        &mut self,
+        first: Player,
+        board: Board,
+        rules: RuleSet,
+        moves: Vec<(usize, usize, Player)>,
+        ending: Option<Message>,
+    ) {
+        let moves = positions(&moves);
+        if self.starting_player != first || self.initial_board != board || self.moves != moves {
+            self.replay_resync(first, board, rules, moves);
+        }
+        match ending {
+            Some(Message::Resign { player }) => self.concede(player),
+            Some(Message::DrawAnswer { accepted: true }) => self.agree_draw(),
+            _ => {}
+        }
+    }
+
+    /// Replaces the game with the one the relay holds.
+    fn replay_resync(
+        &mut self,
+        first: Player,
+        board: Board,
+        rules: RuleSet,
+        moves: Vec<(Position, Player)>,
+    ) {
+        self.rules = rules;
+        self.start_game(first);
+        self.layout_seed = None;
+        self.initial_board = board;
+        self.game = GameState::new(first, board, rules);
+        for (position, player) in moves {
+            if self.game.to_move() != player {
+                self.game.pass();
+            }
+            let _ = self.place_mark(position);
+        }
+    }
+
+    /// Where a takeback request in the network game stands.
+    fn takeback(&self) -> Option<Takeback> {
+        self.remote.as_ref().and_then(|remote| remote.takeback)
+    }
+
+    /// Where a rematch request in the network game stands.
+    fn rematch_state(&self) -> Option<Rematch> {
+        self.remote.as_ref().and_then(|remote| remote.rematch)
+    }
+
+    fn set_takeback(&mut self, takeback: Option<Takeback>) {
+        if let Some(remote) = self.remote.as_mut() {
+            remote.takeback = takeback;
+        }
+    }
+
+    /// Whether this player may ask to take back the last move: during a
+    /// network game in progress with the opponent there and no request
+    /// already open.
+    fn can_request_takeback(&self) -> bool {
+        self.remote.as_ref().is_some_and(|remote| {
+            remote.side.is_some()
+                && remote.opponent_present
+                && !matches!(
+                    remote.takeback,
+                    Some(Takeback::Requested | Takeback::Offered(_))
+                )
+        }) && !self.moves.is_empty()
+            && !self.game_over()
+    }
+
+    /// Asks the opponent to take back the last move.
+    fn request_takeback(&mut self) {
+        if !self.can_request_takeback() {
+            return;
+        }
+        self.set_takeback(Some(Takeback::Requested));
+        self.send_remote(&Message::TakebackRequest {
+            moves: self.moves.len(),
+        });
+    }
+
+    /// Answers the opponent's takeback request. On acceptance the last
+    /// move is taken back here as the answer goes out, and on their side
+    /// when it arrives.
+    fn answer_takeback(&mut self, accepted: bool) {
+        let Some(Takeback::Offered(moves)) = self.takeback() else {
+            return;
+        };
+        self.set_takeback(None);
+        self.send_remote(&Message::TakebackAnswer { moves, accepted });
+        if accepted {
+            self.roll_back_move(moves);
+        }
+    }
+
+    /// Takes back the last move of a network game if the board still holds
+    /// `moves` moves, so both sides roll back the same one.
+    fn roll_back_move(&mut self, moves: usize) {
+        if moves == 0 || self.moves.len() != moves {
+            return;
+        }
+        self.take_back();
+        // A network game has no redo
+        self.redo_stack.clear();
+    }
+
+    /// Sends `text` as a chat line, if this instance plays a side.
+    fn send_chat(&mut self, text: &str) {
+        let Some(text) = chat::prepare(text) else {
+            return;
+        };
+        let Some(remote) = self.remote.as_mut() else {
+            return;
+        };
+        let Some(side) = remote.side else {
+            return;
+        };
+        remote.chat.push(side, text.clone());
+        self.send_remote(&Message::Chat { from: side, text });
+    }
+
+    /// Stops hosting, joining, or playing a LAN or online game.
+    fn disconnect(&mut self) {
+        self.lan_host = None;
+        self.connecting = false;
+        self.reconnecting = None;
+        self.remote = None;
+        self.remote_task = None;
+    }
+
+    /// Starts a fresh Puzzle Rush run and its countdown.
+    fn start_puzzle_rush(&mut self, cx: &mut Context<Self>) {
+        self.coin_flip = None;
+        self.coin_flip_task = None;
+        self.puzzle_rush = Some(PuzzleRush::start());
+        self.show_puzzle();
+        self.puzzle_rush_task = Some(cx.spawn(async move |this, cx| loop {
+            cx.background_executor().timer(PUZZLE_RUSH_TICK).await;
+            let finished = this
+                .update(cx, |this, cx| {
+                    let Some(rush) = this.puzzle_rush.as_mut() else {
+                        return true;
+                    };
+                    rush.tick();
+                    this.show_puzzle();
+                    cx.notify();
+                    this.game_over()
+                })
+                .unwrap_or(true);
+            if finished {
+                break;
+            }
+        }));
+    }
+
+    /// Shows the current Puzzle Rush position on the board, locking it once
+    /// the run has finished.
+    fn show_puzzle(&mut self) {
+        let Some(rush) = &self.puzzle_rush else {
+            return;
+        };
+        self.game = GameState::new(rush.puzzle.to_move, rush.puzzle.board, self.rules);
+        self.layout_seed = None;
+        self.hidden = [[false; 3]; 3];
+        self.fade_tasks.clear();
+        self.result = None;
+        self.confirming_resign = false;
+    }
+
+    /// Starts a new game from the keyboard, asking first if that would
+    /// abandon the game in progress.
+    fn new_game(&mut self, _: &NewGame, _: &mut Window, cx: &mut Context<Self>) {
+        if self.should_confirm_abandon() {
+            self.prompt_abandon(Abandon::NewGame);
+        } else {
+            self.reset(cx);
+        }
+        cx.notify();
+    }
+
+    /// Whether leaving the current game should be confirmed: moves have
+    /// been played, there is no result yet, and the player has not turned
+    /// the prompt off.
+    fn should_confirm_abandon(&self) -> bool {
+        self.settings.confirm_abandon
+            && !self.moves.is_empty()
+            && !self.game_over()
+            && self.puzzle_rush.is_none()
+    }
+
+    /// Shows the prompt asking whether to abandon the game for `action`.
+    fn prompt_abandon(&mut self, action: Abandon) {
+        self.abandon_prompt = Some(AbandonPrompt {
+            action,
+            dont_ask_again: false,
+        });
+    }
+
+    /// Carries out the confirmed action, saving "Don't ask again" if it was
+    /// ticked.
+    fn confirm_abandon(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(prompt) = self.abandon_prompt.take() else {
+            return;
+        };
+        if prompt.dont_ask_again {
+            self.settings.confirm_abandon = false;
+            self.settings.save();
+        }
+        match prompt.action {
+            Abandon::NewGame => self.reset(cx),
+            Abandon::LoadGame => self.prompt_load_game(cx),
+            Abandon::ImportGame => self.prompt_import(cx),
+            Abandon::Quit => window.remove_window(),
+        }
+    }
+
+    /// Saves the game from the keyboard.
+    fn save_game(&mut self, _: &SaveGame, _: &mut Window, cx: &mut Context<Self>) {
+        self.prompt_save_game(cx);
+    }
+
+    /// Loads a saved game from the keyboard, asking first if that would
+    /// abandon the game in progress.
+    fn load_game(&mut self, _: &LoadGame, _: &mut Window, cx: &mut Context<Self>) {
+        self.request_load_game(cx);
+        cx.notify();
+    }
+
+    /// Returns a record of the current game for saving.
+    fn game_record(&self) -> GameRecord {
+        GameRecord {
+            rules: self.game.rules(),
+            initial_board: self.initial_board,
+            starting_player: self.starting_player,
+            coin_seed: self.coin_seed,
+            moves: coordinates(&self.moves),
+            current_player: self.game.to_move(),
+            result: self.result,
+            // Moves undone after being annotated take their notes with them
+            annotations: self
+                .annotations
+                .range(..self.moves.len())
+                .map(|(&index, annotation)| (index, annotation.clone()))
+                .collect(),
+        }
+    }
+
+    /// Asks where to save the game with the native file dialog, then
+    /// writes it there.
+    fn prompt_save_game(&mut self, cx: &mut Context<Self>) {
+        let record = self.game_record();
+        let path = cx.prompt_for_new_path(
+            &game_record::default_dir(),
+            Some(game_record::DEFAULT_FILE_NAME),
+        );
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(path))) = path.await else {
+                return;
+            };
+            let result = record.save(&path);
+            this.update(cx, |this, cx| {
+                this.file_error = result.err().map(|err| format!("Could not save: {err}"));
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Saves a screenshot of the board from the keyboard.
+    fn save_screenshot(&mut self, _: &SaveScreenshot, _: &mut Window, cx: &mut Context<Self>) {
+        self.prompt_screenshot(cx);
+    }
+
+    /// Asks where to save a screenshot of the board as shown, then draws
+    /// it there as a PNG image.
+    fn prompt_screenshot(&mut self, cx: &mut Context<Self>) {
+        let board: Board =
+            std::array::from_fn(|row| std::array::from_fn(|col| self.shown_cell(row, col)));
+        let path = cx.prompt_for_new_path(
+            &game_record::default_dir(),
+            Some(screenshot::DEFAULT_FILE_NAME),
+        );
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(path))) = path.await else {
+                return;
+            };
+            let result = screenshot::save(&board, &path);
+            this.update(cx, |this, cx| {
+                this.file_error = result
+                    .err()
+                    .map(|err| format!("Could not save screenshot: {err}"));
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Asks where to export the board as shown, then writes it there as
+    /// SVG.
+    fn prompt_svg_export(&mut self, cx: &mut Context<Self>) {
+        let board: Board =
+            std::array::from_fn(|row| std::array::from_fn(|col| self.shown_cell(row, col)));
+        let path =
+            cx.prompt_for_new_path(&game_record::default_dir(), Some(export::DEFAULT_FILE_NAME));
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(path))) = path.await else {
+                return;
+            };
+            let result = export::Svg(&board).save(&path);
+            this.update(cx, |this, cx| {
+                this.file_error = result.err().map(|err| format!("Could not export: {err}"));
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Asks where to export the player's data bundle with the native file
+    /// dialog, then gathers the saved files and writes them there.
+    fn prompt_export_data(&mut self, cx: &mut Context<Self>) {
+        let path =
+            cx.prompt_for_new_path(&game_record::default_dir(), Some(bundle::DEFAULT_FILE_NAME));
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(path))) = path.await else {
+                return;
+            };
+            let result = Bundle::collect().and_then(|bundle| bundle.save(&path));
+            this.update(cx, |this, cx| {
+                this.file_error = result
+                    .err()
+                    .map(|err| format!("Could not export data: {err}"));
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Asks which data bundle to import with the native file dialog, then
+    /// replaces the saved data with it.
+    fn prompt_import_data(&mut self, cx: &mut Context<Self>) {
+        self.confirming_import = false;
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: None,
+        });
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = paths.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+            let result = Bundle::load(&path);
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(bundle) => this.import_data(&bundle),
+                    Err(err) => this.file_error = Some(format!("Could not import data: {err}")),
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Writes `bundle` over the saved data, then reloads the settings,
+    /// profiles, history, and daily results from it.
+    fn import_data(&mut self, bundle: &Bundle) {
+        // The history database is closed while its file is replaced
+        self.history = None;
+        let result = bundle.restore();
+        self.history = History::open();
+        self.settings = Settings::load();
+        self.profiles = Profiles::load();
+        self.dailies = Dailies::load();
+        self.seats = [None, None];
+        self.seats[Player::X.index()] = self
+            .settings
+            .player_name
+            .clone()
+            .filter(|name| self.profiles.get(name).is_some());
+        if let Some(view) = self.history_view.as_mut() {
+            if let Some(history) = &self.history {
+                view.refresh(history);
+            }
+        }
+        match result {
+            Ok(count) => info!(count, "imported data bundle"),
+            Err(err) => self.file_error = Some(format!("Could not import data: {err}")),
+        }
+    }
+
+    /// Uploads the game in portable notation to the relay named in the
+    /// settings, then copies the link it can be replayed from.
+    fn host_replay(&mut self, cx: &mut Context<Self>) {
+        if self.hosting_replay {
+            return;
+        }
+        self.hosting_replay = true;
+        self.hosted_link = None;
+        self.file_error = None;
+        let url = self.settings.relay_url.clone();
+        let notation = self.notation().to_string();
+        let upload = cx
+            .background_executor()
+            .spawn(async move { online::upload_replay(&url, &notation) });
+        cx.spawn(async move |this, cx| {
+            let result = upload.await;
+            this.update(cx, |this, cx| {
+                this.hosting_replay = false;
+                match result {
+                    Ok(link) => {
+                        cx.write_to_clipboard(ClipboardItem::new_string(link.clone()));
+                        this.hosted_link = Some(link);
+                    }
+                    Err(err) => this.file_error = Some(format!("Could not host replay: {err}")),
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Fetches the game a hosted replay link points to and opens it in
+    /// the replay view.
+    fn open_hosted_replay(&mut self, link: online::ReplayLink, cx: &mut Context<Self>) {
+        self.file_error = None;
+        let fetch = cx
+            .background_executor()
+            .spawn(async move { online::fetch_replay(&link) });
+        cx.spawn(async move |this, cx| {
+            let result = fetch.await;
+            this.update(cx, |this, cx| {
+                let notation = result
+                    .map_err(|err| err.to_string())
+                    .and_then(|text| text.parse::<Notation>().map_err(|err| err.to_string()));
+                match notation {
+                    Ok(notation) => {
+                        this.restore_game(notation.record);
+                        this.start_replay(cx);
+                    }
+                    Err(err) => {
+                        this.file_error = Some(format!("Could not open replay link: {err}"))
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Returns the current game in portable notation.
+    fn notation(&self) -> Notation {
+        Notation {
+            x_player: self.player_name(Player::X),
+            o_player: self.player_name(Player::O),
+            date: chrono::Local::now().date_naive(),
+            variant: self.variant_name(),
+            record: self.game_record(),
+        }
+    }
+
+    /// Asks where to export `notation` with the native file dialog, then
+    /// writes it there.
+    fn prompt_export(&mut self, notation: Notation, cx: &mut Context<Self>) {
+        let path = cx.prompt_for_new_path(
+            &game_record::default_dir(),
+            Some(notation::DEFAULT_FILE_NAME),
+        );
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(path))) = path.await else {
+                return;
+            };
+            let result = notation.save(&path);
+            this.update(cx, |this, cx| {
+                this.file_error = result.err().map(|err| format!("Could not export: {err}"));
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Asks where to export the games passing the History screen's filters
+    /// as CSV with the native file dialog, then writes them and their
+    /// per-player totals there.
+    fn prompt_export_csv(&mut self, cx: &mut Context<Self>) {
+        let (Some(history), Some(view)) = (&self.history, &self.history_view) else {
+            return;
+        };
+        let entries = match history.list_all(view.player.as_deref(), view.result) {
+            Ok(entries) => entries,
+            Err(err) => {
+                self.file_error = Some(format!("Could not export: {err}"));
+                return;
+            }
+        };
+        let csv = view.csv.clone();
+        let path = cx.prompt_for_new_path(
+            &game_record::default_dir(),
+            Some(csv_export::DEFAULT_FILE_NAME),
+        );
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(path))) = path.await else {
+                return;
+            };
+            let result = csv.save(&entries, &path);
+            this.update(cx, |this, cx| {
+                this.file_error = result.err().map(|err| format!("Could not export: {err}"));
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Starts importing a game from notation, confirming first if a game
+    /// is in progress.
+    fn request_import(&mut self, cx: &mut Context<Self>) {
+        if self.should_confirm_abandon() {
+            self.prompt_abandon(Abandon::ImportGame);
+        } else {
+            self.prompt_import(cx);
+        }
+    }
+
+    /// Asks which `.ttt` file to import with the native file dialog, then
+    /// opens it in the replay view.
+    fn prompt_import(&mut self, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: None,
+        });
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = paths.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+            let result = Notation::load(&path);
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(notation) => {
+                        this.restore_game(notation.record);
+                        this.start_replay(cx);
+                    }
+                    Err(err) => this.file_error = Some(format!("Could not import: {err}")),
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Starts loading a saved game, confirming first if a game is in
+    /// progress.
+    fn request_load_game(&mut self, cx: &mut Context<Self>) {
+        if self.should_confirm_abandon() {
+            self.prompt_abandon(Abandon::LoadGame);
+        } else {
+            self.prompt_load_game(cx);
+        }
+    }
+
+    /// Asks which game to load with the native file dialog, then restores
+    /// it.
+    fn prompt_load_game(&mut self, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: None,
+        });
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = paths.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+            let result = GameRecord::load(&path);
+            this.update(cx, |this, cx| {
+                match result {
+                    Ok(record) => this.restore_game(record),
+                    Err(err) => this.file_error = Some(format!("Could not load: {err}")),
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Replaces the current game with a saved one, leaving any mode.
+    fn restore_game(&mut self, record: GameRecord) {
+        self.leave_modes();
+        self.rules = record.rules;
+        self.start_game(record.starting_player);
+        // The saved starting board already holds any blocked cells
+        self.layout_seed = None;
+        self.initial_board = record.initial_board;
+        self.coin_seed = record.coin_seed;
+        self.moves = positions(&record.moves);
+        self.game = self.state_at(self.moves.len());
+        if self.game.to_move() != record.current_player {
+            self.game.pass();
+        }
+        self.result = record.result;
+        self.annotations = record.annotations;
+        self.file_error = None;
+        self.emit(GameEvent::TurnChanged(self.game.to_move()));
+        self.event_log.push(LogEvent::Loaded {
+            to_move: self.game.to_move(),
+        });
+        self.autosave();
+    }
+
+    /// Autosaves an unfinished game of ordinary play, or clears the
+    /// autosave when there is no such game to resume.
+    ///
+    /// Puzzle Rush, tournaments, and team play are not autosaved, since a
+    /// single game of theirs cannot be resumed on its own.
+    fn autosave(&self) {
+        let resumable = !self.moves.is_empty()
+            && !self.game_over()
+            && self.puzzle_rush.is_none()
+            && self.tournament.is_none()
+            && self.bracket.is_none()
+            && self.team_match.is_none();
+        if resumable {
+            self.game_record().autosave();
+        } else {
+            game_record::clear_autosave();
+        }
+    }
+
+    /// Answers the launch prompt, resuming the autosaved game or
+    /// discarding it.
+    fn answer_resume_prompt(&mut self, resume: bool) {
+        let Some(record) = self.resume_prompt.take() else {
+            return;
+        };
+        if resume {
+            self.restore_game(record);
+        } else {
+            game_record::clear_autosave();
+        }
+    }
+
+    /// Takes back the last move from the keyboard.
+    fn undo_action(&mut self, _: &Undo, _: &mut Window, cx: &mut Context<Self>) {
+        self.undo(cx);
+        cx.notify();
+    }
+
+    /// Copies the current position and side to move to the clipboard as
+    /// text.
+    fn copy_position(&mut self, _: &CopyPosition, _: &mut Window, cx: &mut Context<Self>) {
+        let position = PositionEditor::new(*self.game.board(), self.game.to_move());
+        cx.write_to_clipboard(ClipboardItem::new_string(position.to_string()));
+    }
+
+    /// Starts playing from a position pasted from the clipboard, once it
+    /// has been checked, or opens a pasted share link for review.
+    fn paste_position(&mut self, _: &PastePosition, _: &mut Window, cx: &mut Context<Self>) {
+        let text = cx
+            .read_from_clipboard()
+            .and_then(|item| item.text())
+            .unwrap_or_default();
+        if let Some(invite) = invite::parse(&text) {
+            self.accept_invite(invite, cx);
+            cx.notify();
+            return;
+        }
+        if let Some(link) = online::parse_replay_link(&text) {
+            self.open_hosted_replay(link, cx);
+            cx.notify();
+            return;
+        }
+        if let Some(link) = online::parse_recording_link(&text) {
+            self.open_recording(link, cx);
+            cx.notify();
+            return;
+        }
+        if text.trim().starts_with(share::LINK_PREFIX) {
+            match share::decode(&text) {
+                Some(record) => {
+                    self.restore_game(record);
+                    self.start_replay(cx);
+                }
+                None => self.file_error = Some("Could not open share link".to_string()),
+            }
+            cx.notify();
+            return;
+        }
+        match text.parse::<PositionEditor>() {
+            Err(err) => self.file_error = Some(format!("Could not paste position: {err}")),
+            Ok(position) => match position.validate() {
+                Err(err) => self.file_error = Some(format!("Could not paste position: {err}")),
+                Ok(first) => {
+                    self.file_error = None;
+                    self.play_position(position, first, cx);
+                }
+            },
+        }
+        cx.notify();
+    }
+
+    /// Replays an undone move from the keyboard.
+    fn redo_action(&mut self, _: &Redo, _: &mut Window, cx: &mut Context<Self>) {
+        self.redo(cx);
+        cx.notify();
+    }
+
+    /// Starts a rematch from the keyboard once the game is over.
+    fn rematch_action(&mut self, _: &Rematch, _: &mut Window, cx: &mut Context<Self>) {
+        if self.game_over()
+            && self.puzzle_rush.is_none()
+            && self.tournament.is_none()
+            && self.bracket.is_none()
+        {
+            self.rematch(cx);
+            cx.notify();
+        }
+    }
+
+    /// Steps the timeline back one move from the keyboard, or moves the
+    /// focus left while a cell has it.
+    fn step_back(&mut self, _: &StepBack, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.move_focus(0, -1) {
+            self.step_timeline_back();
+        }
+        cx.notify();
+    }
+
+    /// Steps the timeline forward one move from the keyboard.
+    fn step_forward(&mut self, _: &StepForward, _: &mut Window, cx: &mut Context<Self>) {
+        if !self.move_focus(0, 1) {
+            self.step_timeline_forward();
+        }
+        cx.notify();
+    }
+
+    /// Moves the focus up a row, or onto the center cell if no cell has
+    /// it.
+    fn focus_up(&mut self, _: &FocusUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.focus_vertically(-1, cx);
+    }
+
+    /// Moves the focus down a row, or onto the center cell if no cell has
+    /// it.
+    fn focus_down(&mut self, _: &FocusDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.focus_vertically(1, cx);
+    }
+
+    fn focus_vertically(&mut self, d_row: isize, cx: &mut Context<Self>) {
+        if self.accepts_text() {
+            cx.propagate();
+            return;
+        }
+        if !self.move_focus(d_row, 0) {
+            self.key_focus = Some(KeyFocus::Cell(Position::CENTER));
+        }
+        cx.notify();
+    }
+
+    /// Moves a focused cell's focus by `d_row` and `d_col` as the board is
+    /// shown, stopping at the edges. Returns whether a cell had focus; it
+    /// does not while reviewing the timeline, so the arrows step through it.
+    fn move_focus(&mut self, d_row: isize, d_col: isize) -> bool {
+        let Some(KeyFocus::Cell(focused)) = self.key_focus else {
+            return false;
+        };
+        if self.preview_ply.is_some() {
+            return false;
+        }
+        let (d_row, d_col) = if self.board_flipped {
+            (-d_row, -d_col)
+        } else {
+            (d_row, d_col)
+        };
+        let step = |index: usize, delta: isize| (index as isize + delta).clamp(0, 2) as usize;
+        self.key_focus = Position::new(step(focused.row(), d_row), step(focused.col(), d_col))
+            .map(KeyFocus::Cell);
+        true
+    }
+
+    /// Moves focus between the board and the Play Again button, when it
+    /// is shown.
+    fn cycle_focus(&mut self, _: &CycleFocus, _: &mut Window, cx: &mut Context<Self>) {
+        if self.accepts_text() {
+            cx.propagate();
+            return;
+        }
+        self.key_focus = match self.key_focus {
+            Some(KeyFocus::Cell(..)) if self.reset_button_shown() => Some(KeyFocus::ResetButton),
+            Some(KeyFocus::Cell(focused)) => Some(KeyFocus::Cell(focused)),
+            Some(KeyFocus::ResetButton) | None => Some(KeyFocus::Cell(Position::CENTER)),
+        };
+        cx.notify();
+    }
+
+    /// Plays the focused cell, or presses the focused button.
+    fn play_focused(&mut self, _: &PlayFocused, _: &mut Window, cx: &mut Context<Self>) {
+        if self.accepts_text() {
+            cx.propagate();
+            return;
+        }
+        match self.key_focus {
+            Some(KeyFocus::Cell(focused))
+                if !self.show_settings
+                    && self.editor.is_none()
+                    && self.layout() == BoardLayout::Grid =>
+            {
+                self.click_cell(focused, cx);
+            }
+            Some(KeyFocus::ResetButton) if self.reset_button_shown() => self.reset(cx),
+            _ => {}
+        }
+        cx.notify();
+    }
+
+    /// Whether the Play Again button is shown below the board.
+    fn reset_button_shown(&self) -> bool {
+        self.game_over()
+            && !self.replaying
+            && self.puzzle_rush.is_none()
+            && self.tournament.is_none()
+            && self.bracket.is_none()
+    }
+
+    /// Steps the timeline back one move.
+    fn step_timeline_back(&mut self) {
+        let ply = self.preview_ply.unwrap_or(self.moves.len());
+        self.scrub_to(ply.saturating_sub(1));
+    }
+
+    /// Steps the timeline forward one move, returning to live play at the
+    /// end.
+    fn step_timeline_forward(&mut self) {
+        if let Some(ply) = self.preview_ply {
+            self.scrub_to(ply + 1);
+        }
+    }
+
+    /// Opens the replay view at the start of the finished game, and starts
+    /// evaluating its positions for the evaluation graph.
+    fn start_replay(&mut self, cx: &mut Context<Self>) {
+        self.replaying = true;
+        self.scrub_to(0);
+        self.evaluations = None;
+        let states: Vec<_> = (0..=self.moves.len())
+            .map(|ply| self.state_at(ply))
+            .collect();
+        self.evaluation_task = Some(cx.spawn(async move |this, cx| {
+            let evaluations = cx
+                .background_executor()
+                .spawn(async move {
+                    let _span = info_span!("evaluate_replay", plies = states.len()).entered();
+                    states.iter().map(ai::evaluate).collect::<Vec<_>>()
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                this.evaluation_task = None;
+                this.evaluations = Some(evaluations);
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    /// Returns the index of the move leading to the position shown in the
+    /// replay view, which is the one annotated, or `None` at the start.
+    fn annotated_move(&self) -> Option<usize> {
+        if !self.replaying {
+            return None;
+        }
+        self.preview_ply.unwrap_or(self.moves.len()).checked_sub(1)
+    }
+
+    /// Marks the move shown in the replay view with `glyph`, or clears the
+    /// mark if it already had that one.
+    fn toggle_glyph(&mut self, glyph: Glyph) {
+        let Some(index) = self.annotated_move() else {
+            return;
+        };
+        let annotation = self.annotations.entry(index).or_default();
+        annotation.glyph = (annotation.glyph != Some(glyph)).then_some(glyph);
+        if annotation.is_empty() {
+            self.annotations.remove(&index);
+        }
+    }
+
+    /// Takes over the position shown in the replay view to try other
+    /// moves against the engine.
+    fn start_analysis(&mut self) {
+        let ply = self.preview_ply.unwrap_or(self.moves.len());
+        self.editing_comment = false;
+        self.analysis = Some(Analysis::new(ply, self.state_at(ply)));
+    }
+
+    /// Leaves analysis for the game as it was played, at the position
+    /// analysis started from.
+    fn return_to_main_line(&mut self) {
+        if let Some(analysis) = self.analysis.take() {
+            self.scrub_to(analysis.from_ply);
+        }
+    }
+
+    /// Leaves the replay view, showing the final position again.
+    fn exit_replay(&mut self) {
+        self.replaying = false;
+        self.editing_comment = false;
+        self.analysis = None;
+        self.preview_ply = None;
+        self.evaluations = None;
+        self.evaluation_task = None;
+    }
+
+    /// Shows or hides the event log panel.
+    fn toggle_event_log(&mut self, _: &ToggleEventLog, _: &mut Window, cx: &mut Context<Self>) {
+        self.show_event_log = !self.show_event_log;
+        cx.notify();
+    }
+
+    /// Expands or collapses the chat panel, marking its lines read.
+    fn toggle_chat(&mut self, _: &ToggleChat, _: &mut Window, cx: &mut Context<Self>) {
+        self.show_chat = !self.show_chat;
+        if let Some(remote) = self.remote.as_mut() {
+            remote.chat.unread = 0;
+        }
+        cx.notify();
+    }
+
+    /// Shows or hides the keyboard shortcut overlay.
+    fn toggle_shortcuts(&mut self, _: &ToggleShortcuts, _: &mut Window, cx: &mut Context<Self>) {
+        self.show_shortcuts = !self.show_shortcuts;
+        cx.notify();
+    }
+
+    /// Opens or closes the settings menu from the keyboard.
+    fn toggle_settings(&mut self, _: &ToggleSettings, _: &mut Window, cx: &mut Context<Self>) {
+        self.show_settings = !self.show_settings;
+        self.confirming_import = false;
+        cx.notify();
+    }
+
+    /// Starts a new game from the keyboard, unless the key was typed into
+    /// a text field.
+    fn reset_action(&mut self, _: &Reset, window: &mut Window, cx: &mut Context<Self>) {
+        if self.accepts_text() {
+            cx.propagate();
+            return;
+        }
+        self.new_game(&NewGame, window, cx);
+    }
+
+    /// Plays the cell under numpad key `key`, 1 to 9, as the board is
+    /// shown: 7, 8, and 9 along the top row and 1, 2, and 3 along the
+    /// bottom.
+    fn play_numpad(&mut self, key: usize, cx: &mut Context<Self>) {
+        if self.accepts_text() {
+            cx.propagate();
+            return;
+        }
+        if self.show_settings || self.editor.is_some() || self.layout() != BoardLayout::Grid {
+            return;
+        }
+        let (row, col) = (2 - (key - 1) / 3, (key - 1) % 3);
+        let (row, col) = if self.board_flipped {
+            (2 - row, 2 - col)
+        } else {
+            (row, col)
+        };
+        if let Some(position) = Position::new(row, col) {
+            self.click_cell(position, cx);
+        }
+        cx.notify();
+    }
+
+    /// Whether typed characters go to a text field, such as the chat or a
+    /// name being entered, rather than to the board.
+    fn accepts_text(&self) -> bool {
+        (self.show_lan && self.remote.is_none())
+            || (self.show_chat
+                && self
+                    .remote
+                    .as_ref()
+                    .is_some_and(|remote| remote.side.is_some()))
+            || self.show_profiles
+            || self.tournament_setup.is_some()
+    }
+
+    /// Opens the font picker with the installed font families, putting
+    /// the dyslexia-friendly ones first.
+    fn open_font_picker(&mut self, cx: &mut Context<Self>) {
+        let mut fonts = cx.text_system().all_font_names();
+        fonts.sort();
+        fonts.dedup();
+        fonts.sort_by_key(|font| !READABLE_FONTS.contains(&font.as_str()));
+        self.font_picker = Some(fonts);
+    }
+
+    /// Switches the interface to `font`, or back to the default when
+    /// `None`, and remembers the choice.
+    fn choose_font(&mut self, font: Option<String>) {
+        self.settings.font = font;
+        self.settings.save();
+        self.font_picker = None;
+    }
+
+    /// Toggles whether the window stays above other windows.
+    ///
+    /// GPUI fixes a window's kind when it is opened, so a window of the new
+    /// kind is opened on this game at the same bounds, and the old window
+    /// is only closed once it is up. The app is never left without a
+    /// window, which would quit it on platforms that exit when their last
+    /// window closes.
+    fn toggle_pin(&mut self, _: &TogglePin, window: &mut Window, cx: &mut Context<Self>) {
+        if !PIN_SUPPORTED {
+            self.file_error = Some("Pinning the window is not supported here".to_string());
+            cx.notify();
+            return;
+        }
+        self.pinned = !self.pinned;
+        self.window_state.save();
+        let display_id = window.display(cx).map(|display| display.id());
+        let options = window_options(display_id, window.bounds(), self.pinned);
+        let old_window = window.window_handle();
+        let view = cx.entity();
+        cx.defer(move |cx| {
+            let opened = cx.open_window(options, |window, cx| {
+                view.update(cx, |this, cx| this.attach_window(window, cx));
+                window.focus(&view.focus_handle(cx));
+                view.clone()
+            });
+            match opened {
+                Ok(_) => {
+                    old_window
+                        .update(cx, |_, window, _| window.remove_window())
+                        .ok();
+                }
+                Err(err) => view.update(cx, |this, cx| {
+                    this.pinned = !this.pinned;
+                    this.file_error = Some(format!("Could not pin the window: {err}"));
+                    cx.notify();
+                }),
+            }
+        });
+    }
+}
+
+impl Focusable for TicTacToe {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for TicTacToe {
+    /// Renders the game UI in the chosen font, with any open overlay on
+    /// top.
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let _span = trace_span!("render").entered();
+        self.update_title(window);
+        div()
+            .relative()
+            .size_full()
+            .when_some(self.settings.font.clone(), |el, font| el.font_family(font))
+            .child(self.render_game(window, cx))
+            .when(self.show_settings, |el| el.child(self.render_settings(cx)))
+            .when(self.show_profiles, |el| el.child(self.render_profiles(cx)))
+            .when(self.show_lan, |el| el.child(self.render_lan(cx)))
+            .when(self.history_view.is_some(), |el| {
+                el.child(self.render_history(cx))
+            })
+            .when(self.show_campaign, |el| el.child(self.render_campaign(cx)))
+            .when(self.font_picker.is_some(), |el| {
+                el.child(self.render_font_picker(cx))
+            })
+            .when_some(self.setup.as_ref(), |el, setup| {
+                el.child(self.render_setup(setup, cx))
+            })
+            .when(self.resume_prompt.is_some(), |el| {
+                el.child(self.render_resume_prompt(cx))
+            })
+            .when_some(self.abandon_prompt, |el, prompt| {
+                el.child(self.render_abandon_prompt(prompt, cx))
+            })
+            .when(
+                matches!(self.takeback(), Some(Takeback::Offered(_))),
+                |el| el.child(self.render_takeback_prompt(cx)),
+            )
+            .when_some(
+                self.draw_offer.filter(|_| self.draw_offer_pending()),
+                |el, offerer| el.child(self.render_draw_prompt(offerer, cx)),
+            )
+            .when_some(self.incompatible_version, |el, theirs| {
+                el.child(self.render_version_dialog(theirs, cx))
+            })
+            .when(self.show_shortcuts, |el| {
+                el.child(self.render_shortcuts(cx))
+            })
+    }
+}
+
+impl TicTacToe {
+    /// Renders the game UI including the status, board, and reset button.
+    fn render_game(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Div {
+        // The event log takes its share of the height below the splitter
+        let log_height = if self.show_event_log {
+            window.viewport_size().height * self.window_state.split_ratio()
+        } else {
+            px(0.0)
+        };
+        let cell_size = cell_size(window, self.board_area);
+
+        // Build the game board rows, with coordinate labels when enabled.
+        // Rows and columns are walked in view order, which is reversed when
+        // the board is flipped; cells and labels keep their real coordinates.
+        let show_coordinates = self.show_coordinates;
+        let flipped = self.board_flipped;
+        let view_order = move |index: usize| if flipped { 2 - index } else { index };
+        let mut rows: Vec<_> = Vec::new();
+        if show_coordinates {
+            rows.push(
+                div()
+                    .flex()
+                    .gap_2()
+                    .text_color(rgb(0xcccccc))
+                    .child(div().w(COORDINATE_LABEL_WIDTH))
+                    .children((0..3).map(view_order).map(|col| {
+                        div()
+                            .w(cell_size)
+                            .flex()
+                            .justify_center()
+                            .child(column_label(col))
+                    })),
+            );
+        }
+        for row in (0..3).map(view_order) {
+            let mut cells: Vec<_> = Vec::new();
+            for col in (0..3).map(view_order) {
+                if let Some(position) = Position::new(row, col) {
+                    cells.push(self.render_cell(position, cell_size, cx));
+                }
+            }
+            rows.push(
+                div()
+                    .flex()
+                    .gap_2()
+                    .items_center()
+                    .when(show_coordinates, |el| {
+                        el.child(
+                            div()
+                                .w(COORDINATE_LABEL_WIDTH)
+                                .text_color(rgb(0xcccccc))
+                                .child(row_label(row)),
+                        )
+                    })
+                    .children(cells),
+            );
+        }
+
+        // Create the reset button (shown only when game is over)
+        let reset_button = action_button("reset-button", "Play Again")
+            .when(self.key_focus == Some(KeyFocus::ResetButton), |el| {
+                el.border_2().border_color(rgb(0xffffff))
+            })
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, cx| {
+                    this.reset(cx);
+                }),
+            );
+
+        // Create the rematch button, which swaps who moves first. Over the
+        // network it shows where the request to the opponent stands
+        let rematch_label = match self.rematch_state() {
+            None => "Rematch",
+            Some(Rematch::Requested) => "Rematch Requested...",
+            Some(Rematch::Offered) => "Accept Rematch",
+        };
+        let rematch_button = action_button("rematch-button", rematch_label).on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _event, _window, cx| {
+                this.rematch(cx);
+            }),
+        );
+
+        // Create the replay button, which steps through the finished game
+        let replay_button = action_button("replay-button", "Replay").on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _event, _window, cx| {
+                this.start_replay(cx);
+            }),
+        );
+
+        // Rule options start a new game, and are dimmed while they cannot
+        let rules_locked = self.rules_locked();
+
+        // Create the rule option where completing a line loses
+        let misere_button = small_button("misere-button", "Misère", self.rules.misere)
+            .when(rules_locked, |el| el.opacity(0.5))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, cx| {
+                    this.change_rules(|rules| rules.misere = !rules.misere, cx);
+                }),
+            );
+
+        // Create the rule option where marks fall to the bottom of a column
+        let gravity_button = small_button("gravity-button", "Gravity", self.rules.gravity)
+            .when(rules_locked, |el| el.opacity(0.5))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, cx| {
+                    this.change_rules(|rules| rules.gravity = !rules.gravity, cx);
+                }),
+            );
+
+        // Create the variant option that blocks off random cells
+        let blocked_button = small_button("blocked-button", "Blocked", self.blocked_cells)
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, cx| {
+                    this.toggle_blocked_cells(cx);
+                }),
+            );
+
+        // Create the rule option where lines wrap around the edges
+        let torus = self.rules.topology == Topology::Torus;
+        let torus_button = small_button("torus-button", "Torus", torus)
+            .when(rules_locked, |el| el.opacity(0.5))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, cx| {
+                    this.change_rules(
+                        |rules| {
+                            rules.topology = match rules.topology {
+                                Topology::Flat => Topology::Torus,
+                                Topology::Torus => Topology::Flat,
+                            }
+                        },
+                        cx,
+                    );
+                }),
+            );
+
+        // Create the rule option where old marks expire
+        let expiring_button = small_button("expiring-button", "Expiring", self.rules.expiring)
+            .when(rules_locked, |el| el.opacity(0.5))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, cx| {
+                    this.change_rules(|rules| rules.expiring = !rules.expiring, cx);
+                }),
+            );
+
+        // Create the session option letting the loser start the next game
+        let loser_starts_button =
+            small_button("loser-starts-button", "Loser Starts", self.loser_starts).on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, _cx| {
+                    this.loser_starts = !this.loser_starts;
+                }),
+            );
+
+        // Create the option flipping a coin for the first move of each game
+        let random_start_button = small_button(
+            "random-start-button",
+            "Random Start",
+            self.settings.random_start,
+        )
+        .on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _event, _window, _cx| {
+                this.settings.random_start = !this.settings.random_start;
+                this.settings.save();
+            }),
+        );
+
+        // Create the button that opens the player profiles
+        let profiles_button = small_button("profiles-button", "Players", self.show_profiles)
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, _cx| {
+                    this.show_profiles = true;
+                }),
+            );
+
+        // Create the button that opens the game history
+        let history_button = small_button("history-button", "History", self.history_view.is_some())
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, _cx| {
+                    this.open_history();
+                }),
+            );
+
+        // Create the button that expands or collapses the event log
+        let log_button = small_button("log-button", "Log", self.show_event_log).on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _event, _window, _cx| {
+                this.show_event_log = !this.show_event_log;
+            }),
+        );
+
+        // Create the button that enters or leaves Puzzle Rush
+        let puzzle_rush_button = small_button(
+            "puzzle-rush-button",
+            "Puzzle Rush",
+            self.puzzle_rush.is_some(),
+        )
+        .on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _event, _window, cx| {
+                this.toggle_puzzle_rush(cx);
+            }),
+        );
+
+        // Create the button that opens the campaign's level list
+        let campaign_button = small_button(
+            "campaign-button",
+            "Campaign",
+            self.show_campaign || self.campaign.is_some(),
+        )
+        .on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _event, _window, _cx| {
+                this.show_campaign = true;
+            }),
+        );
+
+        // Create the button that starts or leaves today's Daily Challenge
+        let daily_button = small_button("daily-button", "Daily", self.daily.is_some())
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, cx| {
+                    this.toggle_daily(cx);
+                }),
+            );
+
+        // Create the button that starts or leaves a speedrun
+        let speedrun_button = small_button("speedrun-button", "Speedrun", self.speedrun.is_some())
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, _cx| {
+                    this.toggle_speedrun();
+                }),
+            );
+
+        // Create the button that opens or leaves a tournament
+        let tournament_button = small_button(
+            "tournament-button",
+            "Tournament",
+            self.tournament_setup.is_some() || self.tournament.is_some() || self.bracket.is_some(),
+        )
+        .on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _event, _window, _cx| {
+                this.toggle_tournament();
+            }),
+        );
+
+        // Create the button that switches to playing the computer
+        let computer_button = small_button(
+            "computer-button",
+            "vs Computer",
+            self.opponent == Opponent::Computer,
+        )
+        .on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _event, _window, _cx| {
+                this.toggle_computer_opponent();
+            }),
+        );
+
+        // Create the button that switches 2v2 team play on or off
+        let team_button = small_button("team-button", "2v2", self.team_match.is_some())
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, _cx| {
+                    this.toggle_team_mode();
+                }),
+            );
+
+        // Create the button cycling through series lengths
+        let series_label = match self.series.as_ref().map(|series| series.best_of) {
+            None => "Series",
+            Some(3) => "Best of 3",
+            Some(5) => "Best of 5",
+            Some(_) => "Best of 7",
+        };
+        let series_button = small_button("series-button", series_label, self.series.is_some())
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, cx| {
+                    this.cycle_series(cx);
+                }),
+            );
+
+        // Create the option that fades marks from view
+        let blind_button = small_button("blind-button", "Blind", self.blind_mode).on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _event, _window, _cx| {
+                this.blind_mode = !this.blind_mode;
+            }),
+        );
+
+        // Create the option that shows the previous game as ghost marks
+        let ghost_button = small_button("ghost-button", "Ghost", self.show_ghost).on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _event, _window, _cx| {
+                this.show_ghost = !this.show_ghost;
+            }),
+        );
+
+        // Create a button switching to each registered variant
+        let layout = self.layout();
+        let variant_buttons: Vec<_> = self
+            .variants
+            .iter()
+            .map(|&variant| {
+                let id = format!("{}-button", variant.name.to_lowercase());
+                small_button(
+                    ElementId::Name(id.into()),
+                    variant.name,
+                    layout == variant.layout,
+                )
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event, _window, _cx| {
+                        this.toggle_variant(variant);
+                    }),
+                )
+            })
+            .collect();
+
+        // Create the button that opens or closes the position editor
+        let editor_button = small_button("editor-button", "Editor", self.editor.is_some())
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, _cx| {
+                    this.toggle_editor();
+                }),
+            );
+
+        // Create the option that labels the board edges with coordinates
+        let coordinates_button =
+            small_button("coordinates-button", "Coords", self.show_coordinates).on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, _cx| {
+                    this.show_coordinates = !this.show_coordinates;
+                }),
+            );
+
+        // Create the option that turns the board around for the O side
+        let flip_button = small_button("flip-button", "Flip", self.board_flipped).on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _event, _window, _cx| {
+                this.board_flipped = !this.board_flipped;
+            }),
+        );
+
+        // Create the buttons that save the game and load a saved one
+        let save_button = small_button("save-button", "Save", false).on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _event, _window, cx| {
+                this.prompt_save_game(cx);
+            }),
+        );
+        let load_button = small_button("load-button", "Load", false).on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _event, _window, cx| {
+                this.request_load_game(cx);
+            }),
+        );
+        let import_button = small_button("import-button", "Import", false).on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _event, _window, cx| {
+                this.request_import(cx);
+            }),
+        );
+        let screenshot_button = small_button("screenshot-button", "Screenshot", false)
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, cx| {
+                    this.prompt_screenshot(cx);
+                }),
+            );
+        let svg_button = small_button("svg-button", "SVG", false).on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _event, _window, cx| {
+                this.prompt_svg_export(cx);
+            }),
+        );
+        let share_button = small_button("share-button", "Share", false).on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _event, _window, cx| {
+                let link = share::encode(&this.game_record());
+                cx.write_to_clipboard(ClipboardItem::new_string(link));
+            }),
+        );
+        let host_replay_button = small_button(
+            "host-replay-button",
+            if self.hosting_replay {
+                "Hosting…"
+            } else {
+                "Host Replay"
+            },
+            self.hosted_link.is_some(),
+        )
+        .on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _event, _window, cx| {
+                this.host_replay(cx);
+            }),
+        );
+        let lan_button = small_button("lan-button", "Network", self.remote.is_some())
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, _cx| {
+                    this.show_lan = true;
+                }),
+            );
+        let export_button = small_button("export-button", "Export", false).on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _event, _window, cx| {
+                let notation = this.notation();
+                this.prompt_export(notation, cx);
+            }),
+        );
+
+        // Create the pin button that keeps the window on top
+        let pin_button = small_button(
+            "pin-button",
+            if self.pinned { "Unpin" } else { "Pin" },
+            self.pinned,
+        )
+        .on_mouse_down(
+            MouseButton::Left,
+            cx.listener(|this, _event, window, cx| {
+                this.toggle_pin(&TogglePin, window, cx);
+            }),
+        );
+
+        // Create the button that opens the settings panel
+        let settings_button = small_button("settings-button", "Settings", self.show_settings)
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, _cx| {
+                    this.show_settings = true;
+                }),
+            );
+
+        let toolbar = div()
+            .flex()
+            .flex_wrap()
+            .w_full()
+            .justify_end()
+            .gap_2()
+            .child(computer_button)
+            .child(puzzle_rush_button)
+            .child(campaign_button)
+            .child(daily_button)
+            .child(speedrun_button)
+            .child(tournament_button)
+            .child(team_button)
+            .child(series_button)
+            .child(lan_button)
+            .children(variant_buttons)
+            .child(editor_button)
+            .child(blind_button)
+            .child(ghost_button)
+            .child(coordinates_button)
+            .child(flip_button)
+            .child(misere_button)
+            .child(gravity_button)
+            .child(torus_button)
+            .child(blocked_button)
+            .child(expiring_button)
+            .child(random_start_button)
+            .child(loser_starts_button)
+            .child(save_button)
+            .child(load_button)
+            .child(import_button)
+            .child(export_button)
+            .child(screenshot_button)
+            .child(svg_button)
+            .child(share_button)
+            .when(self.game_over() && !self.moves.is_empty(), |el| {
+                el.child(host_replay_button)
+            })
+            .child(log_button)
+            .child(profiles_button)
+            .child(history_button)
+            .child(settings_button)
+            .when(PIN_SUPPORTED, |el| el.child(pin_button));
+
+        // Main container
+        let container = div()
+            .key_context(keymap::KEY_CONTEXT)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::new_game))
+            .on_action(cx.listener(Self::save_game))
+            .on_action(cx.listener(Self::load_game))
+            .on_action(cx.listener(Self::save_screenshot))
+            .on_action(cx.listener(Self::undo_action))
+            .on_action(cx.listener(Self::redo_action))
+            .on_action(cx.listener(Self::copy_position))
+            .on_action(cx.listener(Self::paste_position))
+            .on_action(cx.listener(Self::rematch_action))
+            .on_action(cx.listener(Self::step_back))
+            .on_action(cx.listener(Self::step_forward))
+            .on_action(cx.listener(Self::toggle_event_log))
+            .on_action(cx.listener(Self::toggle_chat))
+            .on_action(cx.listener(Self::toggle_pin))
+            .on_action(cx.listener(Self::toggle_shortcuts))
+            .on_action(cx.listener(Self::toggle_settings))
+            .on_action(cx.listener(Self::reset_action))
+            .on_action(cx.listener(Self::focus_up))
+            .on_action(cx.listener(Self::focus_down))
+            .on_action(cx.listener(Self::cycle_focus))
+            .on_action(cx.listener(Self::play_focused))
+            .on_action(cx.listener(|this, _: &PlayCell1, _, cx| this.play_numpad(1, cx)))
+            .on_action(cx.listener(|this, _: &PlayCell2, _, cx| this.play_numpad(2, cx)))
+            .on_action(cx.listener(|this, _: &PlayCell3, _, cx| this.play_numpad(3, cx)))
+            .on_action(cx.listener(|this, _: &PlayCell4, _, cx| this.play_numpad(4, cx)))
+            .on_action(cx.listener(|this, _: &PlayCell5, _, cx| this.play_numpad(5, cx)))
+            .on_action(cx.listener(|this, _: &PlayCell6, _, cx| this.play_numpad(6, cx)))
+            .on_action(cx.listener(|this, _: &PlayCell7, _, cx| this.play_numpad(7, cx)))
+            .on_action(cx.listener(|this, _: &PlayCell8, _, cx| this.play_numpad(8, cx)))
+            .on_action(cx.listener(|this, _: &PlayCell9, _, cx| this.play_numpad(9, cx)))
+            .on_key_down(cx.listener(Self::handle_key_down))
+            .on_mouse_move(cx.listener(Self::drag_split))
+            .on_mouse_up(MouseButton::Left, cx.listener(Self::end_split_drag))
+            .flex()
+            .flex_col()
+            .gap_4()
+            .bg(rgb(0x2d2d2d))
+            .size_full()
+            .justify_center()
+            .items_center()
+            .p_4()
+            .child(toolbar);
+
+        if self.tournament_setup.is_some() {
+            return container.child(self.render_tournament_setup(cx));
+        }
+        if let Some(champion) = self.bracket.as_ref().and_then(Bracket::champion) {
+            return container.child(self.render_bracket_champion(champion, cx));
+        }
+        if self.editor.is_some() {
+            let editor = self.render_editor(cell_size, cx);
+            return container.child(self.board_region(editor, cx));
+        }
+        let variant = match self.layout() {
+            BoardLayout::Grid => None,
+            BoardLayout::Ultimate => Some(self.render_ultimate(cell_size, cx).into_any_element()),
+            BoardLayout::Qubic => Some(self.render_qubic(cell_size, cx).into_any_element()),
+            BoardLayout::Notakto => Some(self.render_notakto(cell_size, cx).into_any_element()),
+            BoardLayout::Pentago => Some(self.render_pentago(cell_size, cx).into_any_element()),
+            BoardLayout::ConnectK => Some(self.render_connect_k(cell_size, cx).into_any_element()),
+            BoardLayout::Simul => Some(self.render_simul(cell_size, cx).into_any_element()),
+        };
+        if let Some(variant) = variant {
+            return container.child(self.board_region(variant, cx));
+        }
+
+        let playing =
+            self.puzzle_rush.is_none() && self.tournament.is_none() && self.bracket.is_none();
+        let game_over = self.game_over() && playing;
+        let in_progress = !self.game_over()
+            && self.coin_flip.is_none()
+            && self.computer_task.is_none()
+            && self.puzzle_rush.is_none();
+
+        container
+            .when(self.tournament.is_some(), |el| {
+                el.child(self.render_tournament_info(cx))
+            })
+            .when(self.bracket.is_some(), |el| {
+                el.child(self.render_bracket(cx))
+            })
+            .when_some(self.team_match.as_ref(), |el, teams| {
+                el.child(render_team_scoreboard(teams))
+            })
+            .when_some(self.series.as_ref(), |el, series| {
+                el.child(render_series_header(series))
+            })
+            .when_some(self.campaign, |el, campaign| {
+                el.child(self.render_campaign_header(campaign, cx))
+            })
+            .when_some(self.daily, |el, challenge| {
+                el.child(self.render_daily_header(challenge))
+            })
+            .when_some(self.speedrun.as_ref(), |el, run| {
+                el.child(render_speedrun_header(run))
+            })
+            .when_some(self.reconnecting.as_ref(), |el, reconnecting| {
+                el.child(div().text_color(rgb(0xffd43b)).child(format!(
+                    "Connection lost · rejoining room {} (attempt {} of {MAX_RECONNECT_ATTEMPTS})",
+                    reconnecting.room,
+                    reconnecting.attempts + 1
+                )))
+            })
+            .when_some(self.remote.as_ref(), |el, remote| {
+                el.child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap_2()
+                        .child(
+                            div()
+                                .text_color(if remote.opponent_present {
+                                    rgb(0x4caf50)
+                                } else {
+                                    rgb(0xffd43b)
+                                })
+                                .child(connection_status(remote)),
+                        )
+                        // Spectator count badge
+                        .when(remote.spectators > 0, |el| {
+                            el.child(
+                                div()
+                                    .px_2()
+                                    .rounded_full()
+                                    .bg(rgb(0x404040))
+                                    .text_sm()
+                                    .text_color(rgb(0xcccccc))
+                                    .child(format!("👁 {}", remote.spectators)),
+                            )
+                        }),
+                )
+            })
+            .child(
+                // Status text showing current player or game result, with
+                // the Hint button beside it during play
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_2xl()
+                            .text_color(rgb(0xffffff))
+                            .child(self.get_status_text()),
+                    )
+                    .when(in_progress && self.preview_ply.is_none(), |el| {
+                        el.child(
+                            small_button("hint-button", "Hint", self.hint.is_some()).on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event, _window, _cx| {
+                                    this.hint = this.best_move();
+                                }),
+                            ),
+                        )
+                    }),
+            )
+            .when_some(self.move_time_left(), |el, left| {
+                el.child(self.render_move_timer(left))
+            })
+            .when_some(self.file_error.clone(), |el, error| {
+                el.child(div().text_sm().text_color(rgb(0xff6b6b)).child(error))
+            })
+            .when_some(self.hosted_link.clone(), |el, link| {
+                el.child(
+                    div()
+                        .text_sm()
+                        .text_color(rgb(0xcccccc))
+                        .child(format!("Replay link copied: {link}")),
+                )
+            })
+            .when_some(self.move_error, |el, error| {
+                el.child(
+                    div()
+                        .text_sm()
+                        .text_color(rgb(0xff6b6b))
+                        .child(format!("Can't play there: {error}")),
+                )
+            })
+            .when(self.puzzle_rush.is_none(), |el| {
+                el.child(self.render_player_cards())
+            })
+            .when_some(self.layout_seed, |el, seed| {
+                el.child(self.render_layout_seed(seed, cx))
+            })
+            .when_some(self.coin_flip, |el, flip| el.child(render_coin(flip)))
+            .when(self.puzzle_rush.is_some(), |el| {
+                el.child(self.render_puzzle_rush_info(cx))
+            })
+            .child(
+                // Game board grid, flanked by the game clocks when playing
+                // with them. On a torus a faint frame with arrows at the
+                // edges hints that lines carry on from the opposite side
+                self.board_region(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap_4()
+                        .when(self.clock_budget.is_some(), |el| {
+                            el.child(self.render_game_clock(Player::X))
+                        })
+                        .child(
+                            div()
+                                .flex()
+                                .flex_col()
+                                .gap_2()
+                                .when(torus, |el| {
+                                    el.p_2()
+                                        .rounded_md()
+                                        .border_1()
+                                        .border_color(rgb(0x364fc7))
+                                        .child(wrap_indicator("⇅  ⇄"))
+                                })
+                                .children(rows)
+                                .when(torus, |el| el.child(wrap_indicator("⇄  ⇅"))),
+                        )
+                        .when(self.clock_budget.is_some(), |el| {
+                            el.child(self.render_game_clock(Player::O))
+                        }),
+                    cx,
+                ),
+            )
+            .when(!self.moves.is_empty() && self.puzzle_rush.is_none(), |el| {
+                el.child(self.render_timeline(cx))
+            })
+            .when(self.can_undo() || self.can_redo(), |el| {
+                el.child(self.render_undo_controls(cx))
+            })
+            .when(in_progress, |el| el.child(self.render_resign_controls(cx)))
+            .when(self.remote.is_some(), |el| {
+                el.child(self.render_takeback_controls(cx))
+            })
+            .when(game_over && self.replaying, |el| match &self.analysis {
+                Some(analysis) => el.child(self.render_analysis(analysis, cx)),
+                None => el
+                    .child(self.render_evaluation_graph(cx))
+                    .child(self.render_replay_controls(cx))
+                    .child(self.render_move_list(cx)),
+            })
+            .when(self.reset_button_shown(), |el| {
+                el.child(
+                    div()
+                        .flex()
+                        .gap_2()
+                        .child(reset_button)
+                        .child(rematch_button)
+                        .when(!self.moves.is_empty(), |el| el.child(replay_button)),
+                )
+            })
+            .when_some(self.remote.as_ref(), |el, remote| {
+                el.child(self.render_chat(remote, cx))
+            })
+            .when(self.show_event_log, |el| {
+                el.child(
+                    div()
+                        .id("event-log-splitter")
+                        .w_full()
+                        .h(px(6.0))
+                        .flex_none()
+                        .bg(if self.dragging_split {
+                            rgb(0x4dabf7)
+                        } else {
+                            rgb(0x404040)
+                        })
+                        .cursor_row_resize()
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, _cx| {
+                                this.dragging_split = true;
+                            }),
+                        ),
+                )
+                .child(self.render_event_log(log_height))
+            })
+    }
+
+    /// Wraps `board` in a region that takes the height the rest of the
+    /// screen leaves free, centering the board in it.
+    ///
+    /// The region's size is only known once it has been laid out, so it is
+    /// recorded then, and a change redraws the board to fit.
+    fn board_region(&self, board: impl IntoElement, cx: &mut Context<Self>) -> Div {
+        let view = cx.entity();
+        div()
+            .relative()
+            .flex_1()
+            .min_h_0()
+            .w_full()
+            .flex()
+            .justify_center()
+            .items_center()
+            .child(
+                canvas(
+                    move |bounds, _window, cx| {
+                        view.update(cx, |this, cx| {
+                            if this.board_area != Some(bounds.size) {
+                                this.board_area = Some(bounds.size);
+                                cx.notify();
+                            }
+                        });
+                    },
+                    |_, _, _, _| {},
+                )
+                .absolute()
+                .size_full(),
+            )
+            .child(board)
+    }
+
+    /// Renders the launch dialog offering to resume the autosaved game.
+    fn render_resume_prompt(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let moves = self
+            .resume_prompt
+            .as_ref()
+            .map_or(0, |record| record.moves.len());
+
+        div()
+            .id("resume-prompt")
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .bg(rgba(0x000000cc))
+            .flex()
+            .flex_col()
+            .justify_center()
+            .items_center()
+            .gap_4()
+            .text_color(rgb(0xffffff))
+            .child(div().text_xl().child("Resume last game?"))
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(rgb(0xcccccc))
+                    .child(format!("An unfinished game with {moves} moves was found.")),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(small_button("resume-button", "Resume", true).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, _cx| {
+                            this.answer_resume_prompt(true);
+                        }),
+                    ))
+                    .child(
+                        small_button("discard-autosave-button", "Start New", false).on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, _cx| {
+                                this.answer_resume_prompt(false);
+                            }),
+                        ),
+                    ),
+            )
+    }
+
+    /// Renders the dialog explaining that a network peer speaking protocol
+    /// version `theirs` cannot be played with.
+    fn render_version_dialog(&self, theirs: u32, cx: &mut Context<Self>) -> impl IntoElement {
+        let explanation = match theirs {
+            0 => "The other side runs an older version of the game.".to_string(),
+            theirs if theirs > net::PROTOCOL_VERSION => format!(
+                "The other side runs a newer version of the game (protocol {theirs}, this \
+                 copy speaks {}).",
+                net::PROTOCOL_VERSION
+            ),
+            theirs => format!(
+                "The other side runs an older version of the game (protocol {theirs}, this \
+                 copy speaks {}).",
+                net::PROTOCOL_VERSION
+            ),
+        };
+
+        div()
+            .id("version-dialog")
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .bg(rgba(0x000000cc))
+            .flex()
+            .flex_col()
+            .justify_center()
+            .items_center()
+            .gap_4()
+            .text_color(rgb(0xffffff))
+            .child(div().text_xl().child("Incompatible Version"))
+            .child(div().text_sm().text_color(rgb(0xcccccc)).child(explanation))
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(rgb(0xcccccc))
+                    .child("Both players need the same version to play together."),
+            )
+            .child(
+                small_button("dismiss-version-button", "OK", true).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.incompatible_version = None;
+                    }),
+                ),
+            )
+    }
+
+    /// Renders the dialog asking whether to abandon the game in progress.
+    fn render_abandon_prompt(
+        &self,
+        prompt: AbandonPrompt,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let question = match prompt.action {
+            Abandon::NewGame => "Abandon this game and start a new one?",
+            Abandon::LoadGame => "Abandon this game and load a saved one?",
+            Abandon::ImportGame => "Abandon this game and import one?",
+            Abandon::Quit => "Abandon this game and close the window?",
+        };
+
+        div()
+            .id("abandon-prompt")
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .bg(rgba(0x000000cc))
+            .flex()
+            .flex_col()
+            .justify_center()
+            .items_center()
+            .gap_4()
+            .text_color(rgb(0xffffff))
+            .child(div().text_xl().child(question))
+            .child(
+                small_button(
+                    "dont-ask-again-button",
+                    "Don't ask again",
+                    prompt.dont_ask_again,
+                )
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        if let Some(prompt) = this.abandon_prompt.as_mut() {
+                            prompt.dont_ask_again = !prompt.dont_ask_again;
+                        }
+                    }),
+                ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(
+                        small_button("confirm-abandon-button", "Abandon", true).on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, window, cx| {
+                                this.confirm_abandon(window, cx);
+                            }),
+                        ),
+                    )
+                    .child(
+                        small_button("cancel-abandon-button", "Keep Playing", false).on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, _cx| {
+                                this.abandon_prompt = None;
+                            }),
+                        ),
+                    ),
+            )
+    }
+
+    /// Renders the pre-game players screen, where profiles are added,
+    /// removed, and seated on either side, with each profile's lifetime
+    /// record.
+    fn render_profiles(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut rows: Vec<_> = Vec::new();
+        for (index, profile) in self.profiles.all().iter().enumerate() {
+            let seat_button = |player: Player| {
+                let (id, label) = match player {
+                    Player::X => ("seat-x", "X"),
+                    Player::O => ("seat-o", "O"),
+                };
+                let seated = self.seats[player.index()].as_deref() == Some(&profile.name);
+                let name = profile.name.clone();
+                small_button(
+                    ElementId::Name(format!("{id}-{index}").into()),
+                    label,
+                    seated,
+                )
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event, _window, _cx| {
+                        this.toggle_seat(player, &name);
+                    }),
+                )
+            };
+            let stats = &profile.stats;
+            let mut record = format!(
+                "{} games  ·  {}W {}L {}D  ·  best streak {}",
+                stats.games, stats.wins, stats.losses, stats.draws, stats.best_streak
+            );
+            if let Some((row, col)) = stats.favorite_opening() {
+                record.push_str(&format!("  ·  opens {}", cell_name(row, col)));
+            }
+            let name = profile.name.clone();
+            rows.push(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .w(px(440.0))
+                    .child(
+                        div()
+                            .flex_1()
+                            .flex()
+                            .flex_col()
+                            .child(profile.name.clone())
+                            .child(div().text_xs().text_color(rgb(0x808080)).child(record)),
+                    )
+                    .child(
+                        div()
+                            .text_color(rgb(0xcccccc))
+                            .child(format!("{:.0}", profile.rating)),
+                    )
+                    .child(seat_button(Player::X))
+                    .child(seat_button(Player::O))
+                    .child(
+                        small_button(
+                            ElementId::Name(format!("remove-profile-{index}").into()),
+                            "Remove",
+                            false,
+                        )
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(move |this, _event, _window, _cx| {
+                                this.remove_profile(&name);
+                            }),
+                        ),
+                    ),
+            );
+        }
+
+        div()
+            .id("profiles-panel")
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .bg(rgba(0x000000cc))
+            .flex()
+            .flex_col()
+            .justify_center()
+            .items_center()
+            .gap_3()
+            .text_color(rgb(0xffffff))
+            .child(div().text_2xl().child("Players"))
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(rgb(0xcccccc))
+                    .child("Type a name and press Enter to add a profile"),
+            )
+            .child(
+                div()
+                    .w(px(240.0))
+                    .px_2()
+                    .py_1()
+                    .bg(rgb(0x1e1e1e))
+                    .border_1()
+                    .border_color(rgb(0x4dabf7))
+                    .child(format!("{}|", self.profile_input)),
+            )
+            .children(rows)
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(
+                        action_button("profiles-start-button", "Start Game").on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, cx| {
+                                this.show_profiles = false;
+                                this.reset(cx);
+                            }),
+                        ),
+                    )
+                    .child(
+                        small_button("close-profiles-button", "Done", false).on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, _cx| {
+                                this.show_profiles = false;
+                            }),
+                        ),
+                    ),
+            )
+    }
+
+    /// Renders the History screen: the filters, then one line per stored
+    /// game, newest first.
+    fn render_history(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(view) = &self.history_view else {
+            return div();
+        };
+        let player_filter = view
+            .player
+            .clone()
+            .unwrap_or_else(|| "All players".to_string());
+        let result_filter = match view.result {
+            ResultFilter::All => "All results",
+            ResultFilter::XWins => "X wins",
+            ResultFilter::OWins => "O wins",
+            ResultFilter::Draws => "Draws",
+        };
+
+        let mut rows: Vec<_> = Vec::new();
+        for (index, entry) in view.entries.iter().enumerate() {
+            let result = entry.record.describe_result();
+            let secs = entry.duration_secs;
+            let notation = Notation::from_history(entry);
+            let recording = entry
+                .recording
+                .as_deref()
+                .and_then(online::parse_recording_link);
+            rows.push(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .text_sm()
+                    .child(format!(
+                        "{}  {} (X) vs {} (O)  ·  {}  ·  {}  ·  {} moves  ·  {}:{:02}",
+                        entry.played_at.format("%Y-%m-%d %H:%M"),
+                        entry.x_player,
+                        entry.o_player,
+                        entry.variant,
+                        result,
+                        entry.record.moves.len(),
+                        secs / 60,
+                        secs % 60
+                    ))
+                    .child(
+                        small_button(
+                            ElementId::Name(format!("export-history-{index}").into()),
+                            "Export",
+                            false,
+                        )
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(move |this, _event, _window, cx| {
+                                this.prompt_export(notation.clone(), cx);
+                            }),
+                        ),
+                    )
+                    .when_some(recording, |el, link| {
+                        el.child(
+                            small_button(
+                                ElementId::Name(format!("recording-history-{index}").into()),
+                                "Recording",
+                                false,
+                            )
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(move |this, _event, _window, cx| {
+                                    this.open_recording(link.clone(), cx);
+                                }),
+                            ),
+                        )
+                    }),
+            );
+        }
+        if rows.is_empty() {
+            rows.push(
+                div()
+                    .text_sm()
+                    .text_color(rgb(0x808080))
+                    .child("No games match these filters"),
+            );
+        }
+
+        div()
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .bg(rgba(0x000000cc))
+            .flex()
+            .flex_col()
+            .justify_center()
+            .items_center()
+            .gap_3()
+            .p_4()
+            .text_color(rgb(0xffffff))
+            .child(div().text_2xl().child("History"))
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .id("history-player-filter")
+                            .px_2()
+                            .py_1()
+                            .bg(rgb(0x404040))
+                            .text_sm()
+                            .cursor_pointer()
+                            .hover(|el| el.bg(rgb(0x505050)))
+                            .child(player_filter)
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event, _window, _cx| {
+                                    this.filter_history(HistoryView::next_player);
+                                }),
+                            ),
+                    )
+                    .child(
+                        small_button("history-result-filter", result_filter, false).on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, _cx| {
+                                this.filter_history(|view| view.result = view.result.next());
+                            }),
+                        ),
+                    ),
+            )
+            .child(
+                div()
+                    .id("history-list")
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .max_h(px(360.0))
+                    .overflow_y_scroll()
+                    .children(rows),
+            )
+            .child(render_opening_heatmap(view))
+            .child(self.render_csv_export(view, cx))
+            .child(
+                small_button("close-history-button", "Done", false).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.history_view = None;
+                    }),
+                ),
+            )
+    }
+
+    /// Renders the History screen's CSV export row: a toggle per column,
+    /// the delimiter, and the button that exports.
+    fn render_csv_export(&self, view: &HistoryView, cx: &mut Context<Self>) -> impl IntoElement {
+        let columns = Column::ALL.map(|column| {
+            small_button(
+                ElementId::Name(format!("csv-column-{column:?}").into()),
+                column.header(),
+                view.csv.columns.contains(&column),
+            )
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, _event, _window, _cx| {
+                    if let Some(view) = this.history_view.as_mut() {
+                        view.csv.toggle(column);
+                    }
+                }),
+            )
+        });
+
+        div()
+            .flex()
+            .flex_col()
+            .items_center()
+            .gap_1()
+            .child(div().flex().flex_wrap().gap_1().children(columns))
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(
+                        small_button("csv-delimiter", view.csv.delimiter.label(), false)
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event, _window, _cx| {
+                                    if let Some(view) = this.history_view.as_mut() {
+                                        view.csv.delimiter = view.csv.delimiter.next();
+                                    }
+                                }),
+                            ),
+                    )
+                    .child(
+                        small_button("csv-export", "Export CSV", false).on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, cx| {
+                                this.prompt_export_csv(cx);
+                            }),
+                        ),
+                    ),
+            )
+    }
+
+    /// Renders the campaign's level list, with each level's opponent,
+    /// twist, and best stars, and a button to play each open level.
+    fn render_campaign(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let progress = self.campaign_progress();
+        let unlocked = campaign::unlocked(progress);
+        let mut rows: Vec<_> = Vec::new();
+        for (index, level) in campaign::LEVELS.iter().enumerate() {
+            let difficulty = match level.difficulty {
+                Difficulty::Easy => "Easy",
+                Difficulty::Medium => "Medium",
+                Difficulty::Hard => "Hard",
+            };
+            let stars = progress.get(index).copied().unwrap_or_default();
+            let open = index < unlocked;
+            rows.push(
+                div()
+                    .flex()
+                    .items_center()
+                    .justify_between()
+                    .gap_4()
+                    .w(px(420.0))
+                    .text_sm()
+                    .when(!open, |el| el.text_color(rgb(0x808080)))
+                    .child(format!(
+                        "{}. {}  ·  {difficulty}  ·  {}  ·  {}",
+                        index + 1,
+                        level.opponent,
+                        level.twist,
+                        campaign::star_label(stars)
+                    ))
+                    .when(open, |el| {
+                        el.child(
+                            small_button(
+                                ElementId::Name(format!("campaign-level-{index}").into()),
+                                "Play",
+                                false,
+                            )
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(move |this, _event, _window, _cx| {
+                                    this.start_campaign_level(index);
+                                }),
+                            ),
+                        )
+                    })
+                    .when(!open, |el| el.child("Locked")),
+            );
+        }
+        let saving = match &self.seats[COMPUTER_SIDE.other().index()] {
+            Some(name) => format!("Progress is saved for {name}"),
+            None => "Seat a profile at X to save progress".to_string(),
+        };
+
+        div()
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .bg(rgba(0x000000cc))
+            .flex()
+            .flex_col()
+            .justify_center()
+            .items_center()
+            .gap_3()
+            .p_4()
+            .text_color(rgb(0xffffff))
+            .child(div().text_2xl().child("Campaign"))
+            .child(div().text_sm().text_color(rgb(0xcccccc)).child(saving))
+            .child(div().flex().flex_col().gap_2().children(rows))
+            .child(
+                small_button("close-campaign-button", "Done", false).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.show_campaign = false;
+                    }),
+                ),
+            )
+    }
+
+    /// Renders the campaign level being played, such as "Level 3 ·
+    /// Contrary Mo · Medium · Misère", with the stars earned once the game
+    /// is over and a button on to the next level after clearing it.
+    fn render_campaign_header(
+        &self,
+        campaign: Campaign,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let level = campaign.current();
+        let difficulty = match level.difficulty {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+        };
+        let next = campaign.level + 1;
+        let cleared = campaign.stars.is_some_and(|stars| stars > 0);
+        div()
+            .flex()
+            .items_center()
+            .gap_2()
+            .text_color(rgb(0xcccccc))
+            .child(format!(
+                "Level {} · {} · {difficulty} · {}",
+                campaign.level + 1,
+                level.opponent,
+                level.twist
+            ))
+            .when_some(campaign.stars.filter(|_| self.game_over()), |el, stars| {
+                el.child(
+                    div()
+                        .text_color(rgb(0xffd43b))
+                        .child(campaign::star_label(stars)),
+                )
+            })
+            .when(
+                cleared && self.game_over() && next < campaign::LEVELS.len(),
+                |el| {
+                    el.child(
+                        small_button("next-level-button", "Next Level", false).on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(move |this, _event, _window, _cx| {
+                                this.start_campaign_level(next);
+                            }),
+                        ),
+                    )
+                },
+            )
+    }
+
+    /// Renders the Daily Challenge being played, such as "Daily Challenge ·
+    /// Oct 16 · Misère, Blocked · Hard · streak 4". Once the day has a
+    /// result, it follows with how that result ranks against past dailies,
+    /// and games played after it are marked as practice.
+    fn render_daily_header(&self, challenge: Challenge) -> impl IntoElement {
+        let difficulty = match challenge.difficulty {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+        };
+        let today = chrono::Local::now().date_naive();
+        let streak = self.dailies.streak(today);
+        let summary = format!(
+            "Daily Challenge · {} · {} · {difficulty} · streak {streak} (longest {})",
+            challenge.date.format("%b %-d"),
+            self.variant_name(),
+            self.dailies.longest_streak()
+        );
+        let result = self.dailies.result(challenge.date).map(|result| {
+            let standing = match self.dailies.rank(challenge.date) {
+                Some((_, 0)) | None => "your first daily".to_string(),
+                Some((beaten, past)) => format!("beats {beaten} of your {past} past dailies"),
+            };
+            let best = self
+                .dailies
+                .best_before(challenge.date)
+                .map(|best| format!(" · best {}", best.describe()))
+                .unwrap_or_default();
+            let practice = if self.daily_practice {
+                " · this game is practice"
+            } else {
+                ""
+            };
+            format!("Today: {} · {standing}{best}{practice}", result.describe())
+        });
+        div()
+            .flex()
+            .flex_col()
+            .items_center()
+            .text_color(rgb(0xcccccc))
+            .child(summary)
+            .when_some(result, |el, result| {
+                el.child(div().text_color(rgb(0xffd43b)).child(result))
+            })
+    }
+
+    /// Renders the settings panel. Every change is saved as soon as it is
+    /// made.
+    fn render_settings(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let row = |label: &'static str, control: Stateful<Div>| {
+            div()
+                .flex()
+                .items_center()
+                .justify_between()
+                .w(px(280.0))
+                .child(label)
+                .child(control)
+        };
+        let difficulty = match self.settings.difficulty {
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+        };
+        let clock = match self.settings.clock_minutes {
+            None => "Off",
+            Some(1) => "1 min",
+            Some(3) => "3 min",
+            Some(_) => "5 min",
+        };
+        let increment = match self.settings.clock_increment_secs {
+            None => "Off",
+            Some(1) => "+1 sec",
+            Some(2) => "+2 sec",
+            Some(_) => "+5 sec",
+        };
+        let move_limit = match self.settings.move_limit_secs {
+            None => "Off",
+            Some(5) => "5 sec",
+            Some(10) => "10 sec",
+            Some(_) => "20 sec",
+        };
+        let move_timeout = match self.settings.move_timeout {
+            MoveTimeout::Forfeit => "Lose the game",
+            MoveTimeout::RandomMove => "Random move",
+        };
+
+        div()
+            .id("settings-panel")
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .bg(rgba(0x000000cc))
+            .flex()
+            .flex_col()
+            .justify_center()
+            .items_center()
+            .gap_3()
+            .text_color(rgb(0xffffff))
+            .child(div().text_2xl().child("Settings"))
+            .child(row(
+                "Start new sessions against",
+                small_button(
+                    "opponent-setting",
+                    match self.settings.opponent {
+                        Opponent::Human => "Another player",
+                        Opponent::Computer => "Computer",
+                    },
+                    false,
+                )
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.settings.opponent = match this.settings.opponent {
+                            Opponent::Human => Opponent::Computer,
+                            Opponent::Computer => Opponent::Human,
+                        };
+                        this.settings.save();
+                    }),
+                ),
+            ))
+            .child(row(
+                "Computer difficulty",
+                small_button("difficulty-setting", difficulty, false).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.settings.difficulty = this.settings.difficulty.next();
+                        this.settings.save();
+                    }),
+                ),
+            ))
+            .child(row(
+                "Game clock",
+                small_button(
+                    "clock-setting",
+                    clock,
+                    self.settings.clock_minutes.is_some(),
+                )
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.settings.cycle_clock();
+                        this.settings.save();
+                    }),
+                ),
+            ))
+            .child(row(
+                "Increment per move",
+                small_button(
+                    "clock-increment-setting",
+                    increment,
+                    self.settings.clock_increment_secs.is_some(),
+                )
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.settings.cycle_clock_increment();
+                        this.settings.save();
+                    }),
+                ),
+            ))
+            .child(row(
+                "Time per move",
+                small_button(
+                    "move-limit-setting",
+                    move_limit,
+                    self.settings.move_limit_secs.is_some(),
+                )
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.settings.cycle_move_limit();
+                        this.settings.save();
+                    }),
+                ),
+            ))
+            .child(row(
+                "When a move runs out of time",
+                small_button("move-timeout-setting", move_timeout, false).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.settings.move_timeout = match this.settings.move_timeout {
+                            MoveTimeout::Forfeit => MoveTimeout::RandomMove,
+                            MoveTimeout::RandomMove => MoveTimeout::Forfeit,
+                        };
+                        this.settings.save();
+                    }),
+                ),
+            ))
+            .child(row(
+                "Record online games on the relay",
+                small_button(
+                    "record-online-setting",
+                    if self.settings.record_online_games {
+                        "On"
+                    } else {
+                        "Off"
+                    },
+                    self.settings.record_online_games,
+                )
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.settings.record_online_games = !this.settings.record_online_games;
+                        this.settings.save();
+                    }),
+                ),
+            ))
+            .child(row(
+                "Ask before abandoning a game",
+                small_button(
+                    "confirm-abandon-setting",
+                    if self.settings.confirm_abandon {
+                        "On"
+                    } else {
+                        "Off"
+                    },
+                    self.settings.confirm_abandon,
+                )
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.settings.confirm_abandon = !this.settings.confirm_abandon;
+                        this.settings.save();
+                    }),
+                ),
+            ))
+            .child(row(
+                "Interface font",
+                small_button("font-setting", "Choose...", self.settings.font.is_some())
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| {
+                            this.open_font_picker(cx);
+                        }),
+                    ),
+            ))
+            .child(row(
+                "Your data",
+                div()
+                    .id("data-setting")
+                    .flex()
+                    .gap_2()
+                    .child(
+                        small_button("export-data-button", "Export...", false).on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, cx| {
+                                this.prompt_export_data(cx);
+                            }),
+                        ),
+                    )
+                    .child(
+                        small_button(
+                            "import-data-button",
+                            if self.confirming_import {
+                                "Replace my data?"
+                            } else {
+                                "Import..."
+                            },
+                            self.confirming_import,
+                        )
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, cx| {
+                                if this.confirming_import {
+                                    this.prompt_import_data(cx);
+                                } else {
+                                    this.confirming_import = true;
+                                }
+                            }),
+                        ),
+                    ),
+            ))
+            .child(row(
+                "First-run setup",
+                small_button("setup-setting", "Run Again", false).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.show_settings = false;
+                        this.setup = Some(Setup::new(&this.settings));
+                    }),
+                ),
+            ))
+            .child(
+                small_button("close-settings-button", "Done", false).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.show_settings = false;
+                        this.confirming_import = false;
+                    }),
+                ),
+            )
+    }
+
+    /// Renders the first-run setup wizard's page: choosing the opponent
+    /// new sessions start against, then typing the player's name.
+    fn render_setup(&self, setup: &Setup, cx: &mut Context<Self>) -> impl IntoElement {
+        let page = match setup.step {
+            Step::Opponent => {
+                let choice = |id: &'static str, label: &'static str, opponent: Opponent| {
+                    small_button(id, label, setup.opponent == opponent).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event, _window, _cx| {
+                            if let Some(setup) = this.setup.as_mut() {
+                                setup.opponent = opponent;
+                            }
+                        }),
+                    )
+                };
+                div()
+                    .flex()
+                    .flex_col()
+                    .items_center()
+                    .gap_3()
+                    .child("Who do you usually play against?")
+                    .child(
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(choice("setup-human", "Another Player", Opponent::Human))
+                            .child(choice("setup-computer", "The Computer", Opponent::Computer)),
+                    )
+                    .child(small_button("setup-next", "Next", false).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, _cx| {
+                            if let Some(setup) = this.setup.as_mut() {
+                                setup.step = Step::Name;
+                            }
+                        }),
+                    ))
+            }
+            Step::Name => div()
+                .flex()
+                .flex_col()
+                .items_center()
+                .gap_3()
+                .child("What should we call you?")
+                .child(
+                    div()
+                        .w(px(240.0))
+                        .px_2()
+                        .py_1()
+                        .rounded_md()
+                        .bg(rgb(0x303030))
+                        .child(format!("{}|", setup.name)),
+                )
+                .child(
+                    div()
+                        .text_sm()
+                        .text_color(rgb(0xcccccc))
+                        .child("Your games and rating are kept under this name"),
+                )
+                .child(
+                    div()
+                        .flex()
+                        .gap_2()
+                        .child(small_button("setup-back", "Back", false).on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, _cx| {
+                                if let Some(setup) = this.setup.as_mut() {
+                                    setup.step = Step::Opponent;
+                                }
+                            }),
+                        ))
+                        .child(small_button("setup-finish", "Finish", true).on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, _cx| {
+                                this.finish_setup();
+                            }),
+                        )),
+                ),
+        };
+
+        div()
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .bg(rgba(0x000000cc))
+            .flex()
+            .flex_col()
+            .justify_center()
+            .items_center()
+            .gap_4()
+            .p_4()
+            .text_color(rgb(0xffffff))
+            .child(div().text_2xl().child("Welcome to Tic Tac Toe"))
+            .child(page)
+            .child(small_button("setup-skip", "Skip", false).on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, _cx| {
+                    this.skip_setup();
+                }),
+            ))
+    }
+
+    /// Renders the font picker: the default font, then every installed
+    /// family shown in its own typeface.
+    fn render_font_picker(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let fonts = self.font_picker.clone().unwrap_or_default();
+        let current = self.settings.font.clone();
+        let option = |id: ElementId, label: String, selected: bool| {
+            div()
+                .id(id)
+                .px_2()
+                .py_1()
+                .rounded_md()
+                .cursor_pointer()
+                .when(selected, |el| el.bg(rgb(0x4dabf7)))
+                .hover(|el| el.bg(rgb(0x505050)))
+                .child(label)
+        };
+
+        div()
+            .id("font-picker")
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .bg(rgba(0x000000cc))
+            .flex()
+            .flex_col()
+            .items_center()
+            .gap_2()
+            .p_4()
+            .text_color(rgb(0xffffff))
+            .child(div().text_2xl().child("Font"))
+            .child(
+                div()
+                    .id("font-list")
+                    .flex()
+                    .flex_col()
+                    .flex_1()
+                    .w_full()
+                    .overflow_y_scroll()
+                    .child(
+                        option(
+                            "font-default".into(),
+                            "Default".to_string(),
+                            current.is_none(),
+                        )
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, _cx| {
+                                this.choose_font(None);
+                            }),
+                        ),
+                    )
+                    .children(fonts.into_iter().map(|font| {
+                        let selected = current.as_ref() == Some(&font);
+                        let label = if READABLE_FONTS.contains(&font.as_str()) {
+                            format!("{font} (dyslexia-friendly)")
+                        } else {
+                            font.clone()
+                        };
+                        option(
+                            ElementId::Name(format!("font-{font}").into()),
+                            label,
+                            selected,
+                        )
+                        .font_family(font.clone())
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(move |this, _event, _window, _cx| {
+                                this.choose_font(Some(font.clone()));
+                            }),
+                        )
+                    })),
+            )
+            .child(
+                small_button("close-font-picker-button", "Cancel", false).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.font_picker = None;
+                    }),
+                ),
+            )
+    }
+
+    /// Renders the overlay listing every keyboard shortcut by group.
+    ///
+    /// The list comes from the same table that registers the bindings, so
+    /// it always matches what the keys actually do.
+    fn render_shortcuts(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let shortcuts = keymap::shortcuts();
+        let mut groups: Vec<&str> = Vec::new();
+        for shortcut in &shortcuts {
+            if !groups.contains(&shortcut.group) {
+                groups.push(shortcut.group);
+            }
+        }
+
+        let sections = groups.into_iter().map(|group| {
+            div()
+                .flex()
+                .flex_col()
+                .gap_1()
+                .child(div().text_lg().text_color(rgb(0xffd43b)).child(group))
+                .children(
+                    shortcuts
+                        .iter()
+                        .filter(|shortcut| shortcut.group == group)
+                        .map(|shortcut| {
+                            div()
+                                .flex()
+                                .gap_4()
+                                .child(div().w(px(140.0)).child(shortcut.display_keystroke()))
+                                .child(shortcut.description)
+                        }),
+                )
+        });
+
+        div()
+            .id("shortcut-overlay")
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .bg(rgba(0x000000cc))
+            .flex()
+            .flex_col()
+            .justify_center()
+            .items_center()
+            .gap_4()
+            .text_sm()
+            .text_color(rgb(0xffffff))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, _cx| {
+                    this.show_shortcuts = false;
+                }),
+            )
+            .child(div().text_2xl().child("Keyboard Shortcuts"))
+            .children(sections)
+    }
+
+    /// Returns what a cell of the board shows.
+    ///
+    /// Faded marks in blind mode look empty until the game ends, a
+    /// timeline preview shows the board from earlier in the game, and
+    /// analysis shows the variation being tried.
+    fn shown_cell(&self, row: usize, col: usize) -> Cell {
+        if let Some(analysis) = &self.analysis {
+            analysis.state().board()[row][col]
+        } else if let Some(ply) = self.preview_ply {
+            self.board_at(ply)[row][col]
+        } else if self.blind_mode && self.hidden[row][col] && !self.game_over() {
+            Cell::Empty
+        } else {
+            self.game.board()[row][col]
+        }
+    }
+
+    /// Renders the network lobby: creating an online room and sharing its
+    /// code, joining one by code, or, under the LAN options, hosting and
+    /// joining by address.
+    fn render_lan(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let waiting_room = self
+            .remote
+            .as_ref()
+            .filter(|remote| !remote.opponent_present)
+            .and_then(|remote| remote.room.clone());
+        // The link, and its QR code, that brings the opponent in
+        let invite = match (&waiting_room, &self.lan_host) {
+            (Some(code), _) => Some(Invite::Room(code.clone())),
+            (None, Some(host)) => host
+                .address
+                .map(|ip| Invite::Lan(SocketAddr::new(ip, net::DEFAULT_PORT))),
+            (None, None) => None,
+        }
+        .map(|invite| invite.to_string());
+        let status = if waiting_room.is_some() {
+            "Share this code with your opponent. Waiting for them to join...".to_string()
+        } else if let Some(remote) = &self.remote {
+            connection_status(remote)
+        } else if let Some(host) = &self.lan_host {
+            let address = host
+                .address
+                .map_or("this computer".to_string(), |ip| ip.to_string());
+            format!(
+                "Waiting for a player to join {address} on port {}",
+                net::DEFAULT_PORT
+            )
+        } else if let Some(reconnecting) = &self.reconnecting {
+            format!("Rejoining room {}...", reconnecting.room)
+        } else if self.connecting {
+            "Connecting...".to_string()
+        } else if self.show_lan_options {
+            "Host a game on this network, or pick one nearby or type the host's address".to_string()
+        } else {
+            "Create a room and share its code, or type a friend's code to join or watch".to_string()
+        };
+        let idle = self.remote.is_none()
+            && self.lan_host.is_none()
+            && !self.connecting
+            && self.reconnecting.is_none();
+
+        let buttons = if !idle {
+            div().child(
+                small_button("lan-leave-button", "Leave", false).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.disconnect();
+                    }),
+                ),
+            )
+        } else if self.show_lan_options {
+            div()
+                .child(action_button("lan-host-button", "Host").on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, cx| {
+                        this.host_lan(cx);
+                    }),
+                ))
+                .child(action_button("lan-join-button", "Join").on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, cx| {
+                        this.join_lan(cx);
+                    }),
+                ))
+                .child(
+                    small_button("room-codes-button", "Room Codes", false).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, _cx| {
+                            this.show_lan_options = false;
+                        }),
+                    ),
+                )
+        } else {
+            div()
+                .child(
+                    action_button("create-room-button", "Create Room").on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| {
+                            this.create_room(cx);
+                        }),
+                    ),
+                )
+                .child(
+                    action_button("join-room-button", "Join Room").on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| {
+                            this.join_room(Entry::Join, cx);
+                        }),
+                    ),
+                )
+                .child(
+                    small_button("watch-room-button", "Watch", false).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| {
+                            this.join_room(Entry::Watch, cx);
+                        }),
+                    ),
+                )
+                .child(
+                    small_button("lan-options-button", "LAN", false).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| {
+                            this.show_lan_options(cx);
+                        }),
+                    ),
+                )
+        };
+
+        div()
+            .id("lan-panel")
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .bg(rgba(0x000000cc))
+            .flex()
+            .flex_col()
+            .justify_center()
+            .items_center()
+            .gap_3()
+            .text_color(rgb(0xffffff))
+            .child(div().text_2xl().child("Network Game"))
+            .when_some(waiting_room, |el, code| {
+                el.child(div().text_3xl().text_color(rgb(0xffd43b)).child(code))
+            })
+            .when_some(invite, |el, link| {
+                el.when_some(Qr::new(&link), |el, qr| el.child(qr)).child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap_2()
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(rgb(0xcccccc))
+                                .child(link.clone()),
+                        )
+                        .child(
+                            small_button("copy-invite-button", "Copy", false).on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(move |_this, _event, _window, cx| {
+                                    cx.write_to_clipboard(ClipboardItem::new_string(link.clone()));
+                                }),
+                            ),
+                        ),
+                )
+            })
+            .child(div().text_sm().text_color(rgb(0xcccccc)).child(status))
+            .when(idle, |el| {
+                el.child(
+                    div()
+                        .w(px(240.0))
+                        .px_2()
+                        .py_1()
+                        .bg(rgb(0x1e1e1e))
+                        .border_1()
+                        .border_color(rgb(0x4dabf7))
+                        .child(format!("{}|", self.network_input)),
+                )
+            })
+            .when_some(self.lan_browser.as_ref().filter(|_| idle), |el, browser| {
+                el.child(self.render_found_games(browser, cx))
+            })
+            .when_some(self.file_error.clone(), |el, error| {
+                el.child(div().text_sm().text_color(rgb(0xff6b6b)).child(error))
+            })
+            .child(buttons.flex().gap_2().child(
+                small_button("close-lan-button", "Done", false).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.show_lan = false;
+                    }),
+                ),
+            ))
+    }
+
+    /// Renders the LAN games found on the network, each joined by a
+    /// click.
+    fn render_found_games(&self, browser: &Browser, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .items_center()
+            .gap_1()
+            .text_sm()
+            .when(browser.games().is_empty(), |el| {
+                el.child(
+                    div()
+                        .text_color(rgb(0xcccccc))
+                        .child("Looking for games on this network..."),
+                )
+            })
+            .children(browser.games().iter().enumerate().map(|(index, game)| {
+                let address = game.address;
+                div()
+                    .id(ElementId::Name(format!("found-game-{index}").into()))
+                    .px_2()
+                    .py_1()
+                    .bg(rgb(0x404040))
+                    .cursor_pointer()
+                    .hover(|el| el.bg(rgb(0x505050)))
+                    .child(game.label())
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event, _window, cx| {
+                            this.network_input = address.to_string();
+                            this.join_lan(cx);
+                        }),
+                    )
+            }))
+    }
+
+    /// Renders a single cell of the game board.
+    ///
+    /// Each cell displays X, O, or is empty, with appropriate coloring
+    /// and hover effects for interactive feedback.
+    fn render_cell(
+        &self,
+        position: Position,
+        cell_size: Pixels,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let (row, col) = (position.row(), position.col());
+        let cell = self.shown_cell(row, col);
+
+        let cell_content = match cell {
+            Cell::Empty | Cell::Blocked => "",
+            Cell::Player(Player::X) => "X",
+            Cell::Player(Player::O) => "O",
+        };
+
+        // Color scheme: gray for empty, red for X, blue for O, and a dark
+        // hatch for blocked cells
+        let cell_color = match cell {
+            Cell::Empty => rgb(0x404040),
+            Cell::Blocked => rgb(0x2a2a2a),
+            Cell::Player(Player::X) => rgb(0xff6b6b),
+            Cell::Player(Player::O) => rgb(0x4dabf7),
+        };
+
+        // Whether the board shows the game being played, rather than an
+        // earlier position or a variation
+        let live = self.preview_ply.is_none() && self.analysis.is_none();
+        let is_empty = cell == Cell::Empty
+            && match &self.analysis {
+                Some(analysis) => analysis.is_playable(),
+                None => live && !self.game_over(),
+            };
+
+        // Under gravity the latest mark falls into place from the top row
+        let dropping = self.rules.gravity
+            && live
+            && self.moves.last().is_some_and(|&(last, _)| last == position);
+        // With expiring marks, the mark the player to move will lose next
+        // pulses as a warning
+        let expiring = live
+            && !self.game_over()
+            && self.game.expiring_mark(self.game.to_move()) == Some(position);
+        let ghost = if cell == Cell::Empty && live {
+            self.ghost_at(position)
+        } else {
+            None
+        };
+        // The cells of the line that decided the game are outlined
+        let in_line = live
+            && self
+                .outcome()
+                .line()
+                .is_some_and(|line| line.contains(&position));
+
+        let element = div()
+            .id(ElementId::Name(format!("cell-{}-{}", row, col).into()))
+            .w(cell_size)
+            .h(cell_size)
+            .bg(cell_color)
+            .when(cell == Cell::Blocked, |el| {
+                el.bg(pattern_slash(Hsla::from(rgb(0x555555)), 2.0, 8.0))
+            })
+            .border_1()
+            .border_color(rgb(0x000000))
+            .when(in_line, |el| el.border_4().border_color(rgb(0xffd43b)))
+            .when(self.key_focus == Some(KeyFocus::Cell(position)), |el| {
+                el.border_2().border_color(rgb(0xffffff))
+            })
+            .flex()
+            .justify_center()
+            .items_center()
+            .text_size(cell_size * 0.4)
+            .text_color(rgb(0xffffff))
+            .cursor_pointer()
+            .when(is_empty, |el| el.hover(|el| el.bg(rgb(0x505050))))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, _event, _window, cx| {
+                    this.key_focus = None;
+                    this.click_cell(position, cx);
+                }),
+            )
+            .child(if dropping {
+                let fall = (cell_size + px(8.0)) * row as f32;
+                div()
+                    .relative()
+                    .child(cell_content)
+                    .with_animation(
+                        ElementId::Name(format!("drop-{}", self.moves.len()).into()),
+                        Animation::new(DROP_DURATION).with_easing(ease_in_out),
+                        move |el, delta| el.top(-fall * (1.0 - delta)),
+                    )
+                    .into_any_element()
+            } else if expiring {
+                div()
+                    .child(cell_content)
+                    .with_animation(
+                        "expiring-mark",
+                        Animation::new(TURN_PULSE_DURATION)
+                            .repeat()
+                            .with_easing(pulsating_between(0.2, 1.0)),
+                        |el, delta| el.opacity(delta),
+                    )
+                    .into_any_element()
+            } else {
+                cell_content.into_any_element()
+            })
+            .when_some(ghost, |el, player| {
+                let (label, color) = match player {
+                    Player::X => ("X", rgba(0xff6b6b66)),
+                    Player::O => ("O", rgba(0x4dabf766)),
+                };
+                el.text_color(color).child(label)
+            });
+
+        // The hinted cell gets a thick border that pulses in and out
+        if self.hint != Position::new(row, col) || !live {
+            return element.into_any_element();
+        }
+        let hint_color = Hsla::from(rgb(0xffd43b));
+        element
+            .border_4()
+            .with_animation(
+                "hint-pulse",
+                Animation::new(HINT_PULSE_DURATION)
+                    .repeat()
+                    .with_easing(pulsating_between(0.2, 1.0)),
+                move |el, delta| el.border_color(hint_color.opacity(delta)),
+            )
+            .into_any_element()
+    }
+
+    /// Returns the ghost mark to show at a cell, if any.
+    ///
+    /// Ghosts replay the previous game: its move for each turn appears once
+    /// the current game reaches that turn.
+    fn ghost_at(&self, position: Position) -> Option<Player> {
+        if !self.show_ghost || self.puzzle_rush.is_some() {
+            return None;
+        }
+        self.previous_moves
+            .iter()
+            .take(self.moves.len() + 1)
+            .find(|&&(ghost, _)| ghost == position)
+            .map(|&(_, player)| player)
+    }
+
+    /// Renders the replay view's step controls: first position, back one
+    /// move, forward one move, and final position, and the button taking
+    /// over the position shown for analysis.
+    fn render_replay_controls(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let last = self.moves.len();
+        div()
+            .flex()
+            .gap_2()
+            .child(
+                small_button("replay-first-button", "⏮", false).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.scrub_to(0);
+                    }),
+                ),
+            )
+            .child(
+                small_button("replay-back-button", "◀", false).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.step_timeline_back();
+                    }),
+                ),
+            )
+            .child(
+                small_button("replay-forward-button", "▶", false).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.step_timeline_forward();
+                    }),
+                ),
+            )
+            .child(
+                small_button("replay-last-button", "⏭", false).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event, _window, _cx| {
+                        this.scrub_to(last);
+                    }),
+                ),
+            )
+            .child(
+                small_button("analyze-button", "Analyze", false).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.start_analysis();
+                    }),
+                ),
+            )
+            .child(
+                small_button("exit-replay-button", "Done", false).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.exit_replay();
+                    }),
+                ),
+            )
+    }
+
+    /// Renders the tree of variations tried in analysis, a move per row
+    /// indented by how deep it is, with the position shown highlighted.
+    /// Pressing a move shows the position after it.
+    fn render_analysis(&self, analysis: &Analysis, cx: &mut Context<Self>) -> impl IntoElement {
+        let row = |id: ElementId, depth: usize, selected: bool| {
+            div()
+                .id(id)
+                .pl(px(4.0 + 12.0 * depth as f32))
+                .pr_1()
+                .when(selected, |el| el.bg(rgb(0x505050)))
+                .cursor_pointer()
+        };
+        let start = row("analysis-start".into(), 0, analysis.current.is_none())
+            .text_color(rgb(0x808080))
+            .child(format!("After move {}", analysis.from_ply))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, _cx| {
+                    if let Some(analysis) = this.analysis.as_mut() {
+                        analysis.select(None);
+                    }
+                }),
+            );
+        let moves: Vec<_> = analysis
+            .lines()
+            .into_iter()
+            .map(|(index, depth)| {
+                let node = analysis.node(index);
+                let color = match node.player {
+                    Player::X => rgb(0xff6b6b),
+                    Player::O => rgb(0x4dabf7),
+                };
+                row(
+                    ElementId::Name(format!("variation-{index}").into()),
+                    depth + 1,
+                    analysis.current == Some(index),
+                )
+                .text_color(color)
+                .child(format!(
+                    "{}. {:?} {}",
+                    analysis.from_ply + depth + 1,
+                    node.player,
+                    cell_name(node.position.row(), node.position.col())
+                ))
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event, _window, _cx| {
+                        if let Some(analysis) = this.analysis.as_mut() {
+                            analysis.select(Some(index));
+                        }
+                    }),
+                )
+            })
+            .collect();
+        let status = match analysis.state().status() {
+            Status::InProgress => "Play a move to try it against the engine",
+            Status::Won(_) | Status::Drawn => "This line is over",
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .text_sm()
+            .child(div().text_color(rgb(0xcccccc)).child(status))
+            .child(div().flex().flex_col().child(start).children(moves))
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(
+                        small_button("analysis-back-button", "◀", false).on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, _cx| {
+                                if let Some(analysis) = this.analysis.as_mut() {
+                                    analysis.back();
+                                }
+                            }),
+                        ),
+                    )
+                    .child(
+                        small_button("main-line-button", "Main Line", false).on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, _cx| {
+                                this.return_to_main_line();
+                            }),
+                        ),
+                    ),
+            )
+    }
+
+    /// Renders the replay's move list, each move with its glyph and
+    /// comment. Pressing a move steps to the position after it, and the
+    /// move shown can be marked with a glyph and commented on.
+    fn render_move_list(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let selected = self.annotated_move();
+        let moves: Vec<_> = self
+            .moves
+            .iter()
+            .enumerate()
+            .map(|(index, &(position, player))| {
+                let annotation = self.annotations.get(&index);
+                let glyph = annotation
+                    .and_then(|annotation| annotation.glyph)
+                    .map_or("", Glyph::symbol);
+                let color = match player {
+                    Player::X => rgb(0xff6b6b),
+                    Player::O => rgb(0x4dabf7),
+                };
+                div()
+                    .id(ElementId::Name(format!("move-list-{index}").into()))
+                    .flex()
+                    .gap_2()
+                    .px_1()
+                    .when(selected == Some(index), |el| el.bg(rgb(0x505050)))
+                    .cursor_pointer()
+                    .child(
+                        div()
+                            .text_color(rgb(0x808080))
+                            .child(format!("{}.", index + 1)),
+                    )
+                    .child(div().text_color(color).child(format!(
+                        "{player:?} {}{glyph}",
+                        cell_name(position.row(), position.col())
+                    )))
+                    .when_some(
+                        annotation.filter(|annotation| !annotation.comment.is_empty()),
+                        |el, annotation| {
+                            el.child(
+                                div()
+                                    .italic()
+                                    .text_color(rgb(0xcccccc))
+                                    .child(annotation.comment.clone()),
+                            )
+                        },
+                    )
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event, _window, _cx| {
+                            this.scrub_to(index + 1);
+                        }),
+                    )
+            })
+            .collect();
+
+        let current = selected.and_then(|index| self.annotations.get(&index));
+        let glyphs = Glyph::ALL.map(|glyph| {
+            small_button(
+                ElementId::Name(format!("glyph-{}", glyph.symbol()).into()),
+                glyph.symbol(),
+                current.is_some_and(|annotation| annotation.glyph == Some(glyph)),
+            )
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, _event, _window, _cx| {
+                    this.toggle_glyph(glyph);
+                }),
+            )
+        });
+        let comment = current.map_or("", |annotation| annotation.comment.as_str());
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .text_sm()
+            .child(div().flex().flex_col().children(moves))
+            .when(selected.is_some(), |el| {
+                el.child(
+                    div().flex().items_center().gap_2().children(glyphs).child(
+                        div()
+                            .id("comment-input")
+                            .min_w(px(200.0))
+                            .px_2()
+                            .py_1()
+                            .bg(rgb(0x1e1e1e))
+                            .border_1()
+                            .border_color(if self.editing_comment {
+                                rgb(0x4dabf7)
+                            } else {
+                                rgb(0x505050)
+                            })
+                            .text_color(rgb(0xffffff))
+                            .cursor_text()
+                            .child(if self.editing_comment {
+                                format!("{comment}|")
+                            } else if comment.is_empty() {
+                                "Add a comment…".to_string()
+                            } else {
+                                comment.to_string()
+                            })
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event, _window, _cx| {
+                                    this.editing_comment = true;
+                                }),
+                            ),
+                    ),
+                )
+            })
+    }
+
+    /// Renders the replay's evaluation graph: a bar per position, rising
+    /// in X's color while X is winning and falling in O's while O is, so
+    /// the move where the game swung stands out. Pressing a bar steps to
+    /// that position.
+    fn render_evaluation_graph(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        const HALF_HEIGHT: f32 = 24.0;
+        let Some(evaluations) = &self.evaluations else {
+            return div()
+                .text_sm()
+                .text_color(rgb(0x808080))
+                .child("Evaluating…");
+        };
+        let selected = self.preview_ply.unwrap_or(self.moves.len());
+        let bars = evaluations.iter().enumerate().map(|(ply, &evaluation)| {
+            let height = px(HALF_HEIGHT * evaluation.abs());
+            let bar = |shown: bool, color| {
+                div()
+                    .h(px(HALF_HEIGHT))
+                    .flex()
+                    .flex_col()
+                    .when(shown, |el| el.child(div().h(height).bg(color)))
+            };
+            div()
+                .id(ElementId::Name(format!("evaluation-{ply}").into()))
+                .w(px(10.0))
+                .flex()
+                .flex_col()
+                .justify_center()
+                .when(ply == selected, |el| el.bg(rgb(0x505050)))
+                .cursor_pointer()
+                .child(bar(evaluation > 0.0, rgb(0xff6b6b)).justify_end())
+                .child(div().h(px(1.0)).bg(rgb(0x808080)))
+                .child(bar(evaluation < 0.0, rgb(0x4dabf7)))
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event, _window, _cx| {
+                        this.scrub_to(ply);
+                    }),
+                )
+        });
+        div().flex().items_center().gap_1().children(bars)
+    }
+
+    /// Renders the timeline slider with one notch per position.
+    ///
+    /// Pressing or dragging across a notch previews that position, and the
+    /// last notch returns to live play.
+    fn render_timeline(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let live = self.moves.len();
+        let selected = self.preview_ply.unwrap_or(live);
+        let notches = (0..=live).map(|ply| {
+            div()
+                .id(ElementId::Name(format!("timeline-{ply}").into()))
+                .size(px(14.0))
+                .rounded_full()
+                .bg(if ply == selected {
+                    rgb(0xffd43b)
+                } else if ply < selected {
+                    rgb(0x4dabf7)
+                } else {
+                    rgb(0x606060)
+                })
+                .cursor_pointer()
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event, _window, _cx| {
+                        this.scrub_to(ply);
+                    }),
+                )
+                .on_mouse_move(
+                    cx.listener(move |this, event: &MouseMoveEvent, _window, cx| {
+                        if event.pressed_button == Some(MouseButton::Left)
+                            && this.preview_ply.unwrap_or(this.moves.len()) != ply
+                        {
+                            this.scrub_to(ply);
+                            cx.notify();
+                        }
+                    }),
+                )
+        });
+
+        div()
+            .flex()
+            .items_center()
+            .gap_2()
+            .text_sm()
+            .text_color(rgb(0xcccccc))
+            .child(div().flex().items_center().gap_1().children(notches))
+            .child(match self.preview_ply {
+                Some(ply) => format!("Move {ply}/{live}"),
+                None => "Live".to_string(),
+            })
+    }
+
+    /// Renders the blocked-cell layout's seed, with buttons to copy it or
+    /// start a game on a pasted one.
+    fn render_layout_seed(&self, seed: u64, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .gap_2()
+            .items_center()
+            .text_sm()
+            .text_color(rgb(0xcccccc))
+            .child(format!("Layout {}", blocked::format_seed(seed)))
+            .child(
+                small_button("copy-layout-button", "Copy", false).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, cx| {
+                        this.copy_layout_seed(cx);
+                    }),
+                ),
+            )
+            .child(
+                small_button("paste-layout-button", "Paste", false).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, cx| {
+                        this.paste_layout_seed(cx);
+                    }),
+                ),
+            )
+    }
+
+    /// Returns who is playing `player`'s side and their rating, such as
+    /// "Alice (1234)", when it is a profile or the computer.
+    fn seat_label(&self, player: Player) -> Option<String> {
+        let rating = self.seat_rating(player)?;
+        let name = if self.opponent == Opponent::Computer && player == COMPUTER_SIDE {
+            "Computer"
+        } else {
+            self.seats[player.index()].as_deref()?
+        };
+        Some(format!("{name} ({rating:.0})"))
+    }
+
+    /// Renders a card for each player with their thinking time.
+    ///
+    /// The card of the player to move is highlighted in their color and
+    /// carries a gently pulsing marker, so whose turn it is can be seen at
+    /// a glance.
+    fn render_player_cards(&self) -> impl IntoElement {
+        let card = |player: Player| {
+            let (label, color) = match player {
+                Player::X => ("X", rgb(0xff6b6b)),
+                Player::O => ("O", rgb(0x4dabf7)),
+            };
+            let to_move =
+                player == self.game.to_move() && !self.game_over() && self.coin_flip.is_none();
+            let seconds = self.thinking_time(player).as_secs();
+            div()
+                .flex()
+                .items_center()
+                .gap_2()
+                .px_3()
+                .py_1()
+                .rounded_md()
+                .border_2()
+                .border_color(if to_move { color } else { rgb(0x404040) })
+                .bg(if to_move {
+                    rgb(0x3a3a3a)
+                } else {
+                    rgb(0x2d2d2d)
+                })
+                .child(div().text_color(color).child(label))
+                .when_some(self.seat_label(player), |el, seat| {
+                    el.child(div().text_color(rgb(0xcccccc)).child(seat))
+                })
+                .child(
+                    div()
+                        .text_color(if to_move {
+                            rgb(0xffffff)
+                        } else {
+                            rgb(0x808080)
+                        })
+                        .child(format!("{}:{:02}", seconds / 60, seconds % 60)),
+                )
+                .when(to_move, |el| {
+                    el.child(
+                        div().text_color(color).child("◀").with_animation(
+                            "turn-marker",
+                            Animation::new(TURN_PULSE_DURATION)
+                                .repeat()
+                                .with_easing(pulsating_between(0.3, 1.0)),
+                            |marker, delta| marker.opacity(delta),
+                        ),
+                    )
+                })
+        };
+
+        div()
+            .flex()
+            .gap_4()
+            .text_sm()
+            .child(card(Player::X))
+            .child(card(Player::O))
+    }
+
+    /// Renders the blitz progress bar, shrinking as the time left for the
+    /// current move runs down.
+    fn render_move_timer(&self, left: Duration) -> impl IntoElement {
+        let limit = self.settings.move_limit_secs.unwrap_or(1) as f32;
+        let fraction = (left.as_secs_f32() / limit).clamp(0.0, 1.0);
+        let color = match self.game.to_move() {
+            Player::X => rgb(0xff6b6b),
+            Player::O => rgb(0x4dabf7),
+        };
+        div()
+            .w(px(240.0))
+            .h(px(6.0))
+            .rounded_full()
+            .bg(rgb(0x404040))
+            .child(
+                div()
+                    .h_full()
+                    .w(relative(fraction))
+                    .rounded_full()
+                    .bg(color),
+            )
+    }
+
+    /// Renders `player`'s game clock, lit while it is running and red in
+    /// the last ten seconds, with the increment under its label.
+    fn render_game_clock(&self, player: Player) -> impl IntoElement {
+        let left = self.time_left(player).unwrap_or_default();
+        let seconds = left.as_secs_f32().ceil() as u64;
+        let running =
+            player == self.game.to_move() && !self.game_over() && self.coin_flip.is_none();
+        let color = if seconds <= 10 {
+            rgb(0xff6b6b)
+        } else if running {
+            rgb(0xffffff)
+        } else {
+            rgb(0x808080)
+        };
+        div()
+            .flex()
+            .flex_col()
+            .items_center()
+            .px_3()
+            .py_2()
+            .rounded_md()
+            .border_2()
+            .border_color(if running { color } else { rgb(0x404040) })
+            .bg(rgb(0x2d2d2d))
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(rgb(0xcccccc))
+                    .child(match player {
+                        Player::X => "X",
+                        Player::O => "O",
+                    }),
+            )
+            .when(!self.clock_increment.is_zero(), |el| {
+                el.child(
+                    div()
+                        .text_xs()
+                        .text_color(rgb(0x808080))
+                        .child(format!("+{}s", self.clock_increment.as_secs())),
+                )
+            })
+            .child(div().text_2xl().text_color(color).child(format!(
+                "{}:{:02}",
+                seconds / 60,
+                seconds % 60
+            )))
+    }
+
+    /// Renders the Undo and Redo buttons that are currently usable.
+    fn render_undo_controls(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .gap_2()
+            .when(self.can_undo(), |el| {
+                el.child(small_button("undo-button", "Undo", false).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, cx| {
+                        this.undo(cx);
+                    }),
+                ))
+            })
+            .when(self.can_redo(), |el| {
+                el.child(small_button("redo-button", "Redo", false).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, cx| {
+                        this.redo(cx);
+                    }),
+                ))
+            })
+    }
+
+    /// Renders the Request Takeback button of a network game, or where
+    /// this player's request stands.
+    fn render_takeback_controls(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let note = match self.takeback() {
+            Some(Takeback::Requested) => Some("Takeback requested..."),
+            Some(Takeback::Declined) => Some("Takeback declined"),
+            Some(Takeback::Offered(_)) | None => None,
+        };
+        div()
+            .text_sm()
+            .text_color(rgb(0xcccccc))
+            .when(self.can_request_takeback(), |el| {
+                el.child(
+                    small_button("takeback-button", "Request Takeback", false).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, _cx| {
+                            this.request_takeback();
+                        }),
+                    ),
+                )
+            })
+            .when_some(note, |el, note| el.child(note))
+    }
+
+    /// Renders the dialog asking whether to accept a draw offered by
+    /// `offerer`.
+    fn render_draw_prompt(&self, offerer: Player, cx: &mut Context<Self>) -> impl IntoElement {
+        let (offerer, answerer) = match offerer {
+            Player::X => ("X", "O"),
+            Player::O => ("O", "X"),
+        };
+        let detail = if self.remote.is_some() {
+            "Your opponent offers a draw.".to_string()
+        } else {
+            format!("Player {offerer} offers a draw. Player {answerer}, do you accept?")
+        };
+        div()
+            .id("draw-prompt")
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .bg(rgba(0x000000cc))
+            .flex()
+            .flex_col()
+            .justify_center()
+            .items_center()
+            .gap_4()
+            .text_color(rgb(0xffffff))
+            .child(div().text_xl().child("Draw offered"))
+            .child(div().text_sm().text_color(rgb(0xcccccc)).child(detail))
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(
+                        small_button("accept-draw-button", "Accept", true).on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, cx| {
+                                this.answer_draw(true);
+                                this.continue_series(cx);
+                            }),
+                        ),
+                    )
+                    .child(
+                        small_button("decline-draw-button", "Decline", false).on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, _cx| {
+                                this.answer_draw(false);
+                            }),
+                        ),
+                    ),
+            )
+    }
+
+    /// Renders the dialog asking whether to grant the opponent's takeback
+    /// request.
+    fn render_takeback_prompt(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .id("takeback-prompt")
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .bg(rgba(0x000000cc))
+            .flex()
+            .flex_col()
+            .justify_center()
+            .items_center()
+            .gap_4()
+            .text_color(rgb(0xffffff))
+            .child(div().text_xl().child("Takeback requested"))
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(rgb(0xcccccc))
+                    .child("Your opponent asks to take back the last move."),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(
+                        small_button("accept-takeback-button", "Accept", true).on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, _cx| {
+                                this.answer_takeback(true);
+                            }),
+                        ),
+                    )
+                    .child(
+                        small_button("decline-takeback-button", "Decline", false).on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, _cx| {
+                                this.answer_takeback(false);
+                            }),
+                        ),
+                    ),
+            )
     }
 
-    /// Attempts to make a move at the specified position.
-    ///
-    /// The move is only made if the game is not over and the cell is empty.
-    /// After a valid move, checks for a winner or draw and switches players.
-    fn make_move(&mut self, row: usize, col: usize) {
-        if self.game_over || self.board[row][col] != Cell::Empty {
-            return;
+    /// Renders the Offer Draw and Resign buttons, or the resign
+    /// confirmation prompt once clicked. Spectators get neither.
+    fn render_resign_controls(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(side) = self.own_side() else {
+            return div();
+        };
+        if !self.confirming_resign {
+            // Over the network our own offer waits on the opponent, while
+            // locally the prompt covers the board until it is answered. The
+            // computer plays on rather than weigh offers
+            let offered = self.draw_offer == Some(side) && self.remote.is_some();
+            let can_offer = self.draw_offer.is_none()
+                && self.opponent != Opponent::Computer
+                && self
+                    .remote
+                    .as_ref()
+                    .is_none_or(|remote| remote.opponent_present);
+            return div()
+                .flex()
+                .gap_2()
+                .items_center()
+                .text_sm()
+                .text_color(rgb(0xcccccc))
+                .when(can_offer, |el| {
+                    el.child(
+                        small_button("offer-draw-button", "Offer Draw", false).on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, _cx| {
+                                this.offer_draw();
+                            }),
+                        ),
+                    )
+                })
+                .when(offered, |el| el.child("Draw offered..."))
+                .child(
+                    small_button("resign-button", "Resign", false).on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, _cx| {
+                            this.confirming_resign = true;
+                        }),
+                    ),
+                );
         }
 
-        self.board[row][col] = Cell::Player(self.current_player);
+        div()
+            .flex()
+            .gap_2()
+            .items_center()
+            .text_color(rgb(0xffffff))
+            .child(format!(
+                "Resign as {}?",
+                match side {
+                    Player::X => "X",
+                    Player::O => "O",
+                }
+            ))
+            .child(
+                small_button("confirm-resign-button", "Yes", true).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, cx| {
+                        this.resign();
+                        this.continue_series(cx);
+                    }),
+                ),
+            )
+            .child(
+                small_button("cancel-resign-button", "No", false).on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.confirming_resign = false;
+                    }),
+                ),
+            )
+    }
+
+    /// Renders the Puzzle Rush score, strikes, and clock, with the
+    /// high-score table and a retry button once the run is over.
+    fn render_puzzle_rush_info(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut info = div()
+            .flex()
+            .flex_col()
+            .items_center()
+            .gap_1()
+            .text_color(rgb(0xcccccc));
+        let Some(rush) = &self.puzzle_rush else {
+            return info;
+        };
 
-        if self.check_winner(self.current_player) {
-            self.game_over = true;
-            self.winner = Some(self.current_player);
-        } else if self.check_draw() {
-            self.game_over = true;
-        } else {
-            self.current_player = match self.current_player {
-                Player::X => Player::O,
-                Player::O => Player::X,
-            };
+        info = info.child(format!(
+            "Score {}  ·  Strikes {}/{}  ·  {}:{:02}",
+            rush.score,
+            rush.strikes,
+            MAX_STRIKES,
+            rush.seconds_left / 60,
+            rush.seconds_left % 60
+        ));
+        if rush.is_finished() {
+            let scores = rush
+                .high_scores
+                .scores()
+                .iter()
+                .map(|score| score.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            info = info.child(format!("High scores: {scores}")).child(
+                action_button("retry-puzzle-rush-button", "Try Again").on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, cx| {
+                        this.start_puzzle_rush(cx);
+                    }),
+                ),
+            );
         }
+        info
     }
 
-    /// Checks if the specified player has won the game.
-    ///
-    /// Checks all rows, columns, and both diagonals for three in a row.
-    fn check_winner(&self, player: Player) -> bool {
-        // Check rows
+    /// Renders the position editor: a board whose cells cycle through
+    /// empty, X, and O, with the side to move and a validity check.
+    fn render_editor(&self, cell_size: Pixels, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut screen = div()
+            .flex()
+            .flex_col()
+            .items_center()
+            .gap_2()
+            .text_color(rgb(0xffffff));
+        let Some(editor) = &self.editor else {
+            return screen;
+        };
+
+        let mut rows: Vec<_> = Vec::new();
         for row in 0..3 {
-            if self.board[row][0] == Cell::Player(player)
-                && self.board[row][1] == Cell::Player(player)
-                && self.board[row][2] == Cell::Player(player)
-            {
-                return true;
+            let mut cells: Vec<_> = Vec::new();
+            for col in 0..3 {
+                let (label, color) = match editor.board[row][col] {
+                    Cell::Empty | Cell::Blocked => ("", rgb(0x404040)),
+                    Cell::Player(Player::X) => ("X", rgb(0xff6b6b)),
+                    Cell::Player(Player::O) => ("O", rgb(0x4dabf7)),
+                };
+                cells.push(
+                    div()
+                        .id(ElementId::Name(
+                            format!("editor-cell-{}-{}", row, col).into(),
+                        ))
+                        .w(cell_size)
+                        .h(cell_size)
+                        .bg(color)
+                        .border_1()
+                        .border_color(rgb(0x000000))
+                        .flex()
+                        .justify_center()
+                        .items_center()
+                        .text_size(cell_size * 0.4)
+                        .cursor_pointer()
+                        .hover(|el| el.border_color(rgb(0xffffff)))
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(move |this, _event, _window, _cx| {
+                                if let Some(editor) = this.editor.as_mut() {
+                                    editor.cycle_cell(row, col);
+                                }
+                            }),
+                        )
+                        .child(label),
+                );
             }
+            rows.push(div().flex().gap_2().children(cells));
         }
 
-        // Check columns
-        for col in 0..3 {
-            if self.board[0][col] == Cell::Player(player)
-                && self.board[1][col] == Cell::Player(player)
-                && self.board[2][col] == Cell::Player(player)
-            {
-                return true;
-            }
-        }
+        let to_move_label = match editor.to_move {
+            Player::X => "X to Move",
+            Player::O => "O to Move",
+        };
+        let validation = match editor.validate() {
+            Ok(_) => div().text_color(rgb(0x4caf50)).child("Position is valid"),
+            Err(error) => div().text_color(rgb(0xff6b6b)).child(error.to_string()),
+        };
 
-        // Check main diagonal (top-left to bottom-right)
-        if self.board[0][0] == Cell::Player(player)
-            && self.board[1][1] == Cell::Player(player)
-            && self.board[2][2] == Cell::Player(player)
-        {
-            return true;
-        }
+        screen = screen
+            .child(div().text_2xl().child("Position Editor"))
+            .child(div().flex().flex_col().gap_2().children(rows))
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(
+                        small_button("editor-to-move-button", to_move_label, true).on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, _cx| {
+                                if let Some(editor) = this.editor.as_mut() {
+                                    editor.to_move = editor.to_move.other();
+                                }
+                            }),
+                        ),
+                    )
+                    .child(
+                        small_button("editor-clear-button", "Clear", false).on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, _cx| {
+                                if let Some(editor) = this.editor.as_mut() {
+                                    editor.clear();
+                                }
+                            }),
+                        ),
+                    ),
+            )
+            .child(validation);
 
-        // Check anti-diagonal (top-right to bottom-left)
-        if self.board[0][2] == Cell::Player(player)
-            && self.board[1][1] == Cell::Player(player)
-            && self.board[2][0] == Cell::Player(player)
-        {
-            return true;
+        screen.when(editor.validate().is_ok(), |el| {
+            el.child(
+                action_button("editor-play-button", "Start Play").on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, cx| {
+                        this.play_from_editor(cx);
+                    }),
+                ),
+            )
+        })
+    }
+
+    /// Renders the tournament name-entry screen.
+    fn render_tournament_setup(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut screen = div()
+            .flex()
+            .flex_col()
+            .items_center()
+            .gap_2()
+            .text_color(rgb(0xffffff));
+        let Some(setup) = &self.tournament_setup else {
+            return screen;
+        };
+
+        let format_button = |id: &'static str, label: &'static str, format: Format| {
+            small_button(id, label, setup.format == format).on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, _event, _window, _cx| {
+                    if let Some(setup) = this.tournament_setup.as_mut() {
+                        setup.format = format;
+                    }
+                }),
+            )
+        };
+        let player_count = match setup.format {
+            Format::RoundRobin => format!("{MIN_PLAYERS}-{MAX_PLAYERS} players"),
+            Format::Bracket => format!("{} or {} players", BRACKET_SIZES[0], BRACKET_SIZES[1]),
+        };
+
+        screen = screen
+            .child(div().text_2xl().child("Tournament Players"))
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(format_button(
+                        "round-robin-format-button",
+                        "Round Robin",
+                        Format::RoundRobin,
+                    ))
+                    .child(format_button(
+                        "bracket-format-button",
+                        "Bracket",
+                        Format::Bracket,
+                    )),
+            )
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(rgb(0xcccccc))
+                    .child(format!("Type a name and press Enter ({player_count})")),
+            )
+            .child(
+                div()
+                    .w(px(240.0))
+                    .px_2()
+                    .py_1()
+                    .bg(rgb(0x1e1e1e))
+                    .border_1()
+                    .border_color(rgb(0x4dabf7))
+                    .child(format!("{}|", setup.input)),
+            );
+
+        for (index, name) in setup.names.iter().enumerate() {
+            screen = screen.child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .items_center()
+                    .child(format!("{}. {name}", index + 1))
+                    .child(
+                        small_button(
+                            ElementId::Name(format!("remove-player-{index}").into()),
+                            "Remove",
+                            false,
+                        )
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(move |this, _event, _window, _cx| {
+                                if let Some(setup) = this.tournament_setup.as_mut() {
+                                    setup.remove_name(index);
+                                }
+                            }),
+                        ),
+                    ),
+            );
         }
 
-        false
+        screen.when(setup.can_start(), |el| {
+            el.child(
+                action_button("start-tournament-button", "Start Tournament").on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.start_tournament();
+                    }),
+                ),
+            )
+        })
     }
 
-    /// Checks if the game is a draw (all cells filled with no winner).
-    fn check_draw(&self) -> bool {
-        for row in 0..3 {
-            for col in 0..3 {
-                if self.board[row][col] == Cell::Empty {
-                    return false;
-                }
+    /// Renders the current pairing, plus the standings and the button to
+    /// continue once a game is over.
+    fn render_tournament_info(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut info = div()
+            .flex()
+            .flex_col()
+            .items_center()
+            .gap_1()
+            .text_color(rgb(0xcccccc));
+        let Some(tournament) = &self.tournament else {
+            return info;
+        };
+
+        if let Some(champion) = tournament.champion() {
+            info = info.child(
+                div()
+                    .text_xl()
+                    .text_color(rgb(0xffd43b))
+                    .child(format!("Champion: {champion}")),
+            );
+        } else if let Some((x, o)) = tournament.current_pairing() {
+            let (game, total) = tournament.progress();
+            info = info.child(format!("Game {game} of {total}: {x} (X) vs {o} (O)"));
+        }
+
+        if self.game_over() || tournament.is_complete() {
+            for (rank, standing) in tournament.table().into_iter().enumerate() {
+                info = info.child(div().text_sm().child(format!(
+                    "{}. {}  W{} D{} L{}  {} pts",
+                    rank + 1,
+                    standing.name,
+                    standing.wins,
+                    standing.draws,
+                    standing.losses,
+                    standing.points()
+                )));
             }
         }
-        true
-    }
 
-    /// Resets the game to its initial state.
-    fn reset(&mut self) {
-        self.board = [[Cell::Empty; 3]; 3];
-        self.current_player = Player::X;
-        self.game_over = false;
-        self.winner = None;
+        if self.game_over() && !tournament.is_complete() {
+            info = info.child(
+                action_button("next-tournament-game-button", "Next Game").on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.next_tournament_game();
+                    }),
+                ),
+            );
+        }
+        info
     }
-}
 
-impl Render for TicTacToe {
-    /// Renders the game UI including the status, board, and reset button.
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        // Build the game board rows
-        let mut rows: Vec<_> = Vec::new();
-        for row in 0..3 {
-            let mut cells: Vec<_> = Vec::new();
-            for col in 0..3 {
-                cells.push(self.render_cell(row, col, cx));
-            }
-            rows.push(div().flex().gap_2().children(cells));
+    /// Renders the bracket as a tree, one column per round, with the
+    /// current match and the button to continue once a game is over.
+    fn render_bracket(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut info = div()
+            .flex()
+            .flex_col()
+            .items_center()
+            .gap_2()
+            .text_color(rgb(0xcccccc));
+        let Some(bracket) = &self.bracket else {
+            return info;
+        };
+
+        if let Some((x, o)) = bracket.current_pairing() {
+            let round = bracket.round_name(bracket.rounds().len() - 1);
+            info = info.child(format!("{round}: {x} (X) vs {o} (O)"));
         }
 
-        // Create the reset button (shown only when game is over)
-        let reset_button = div()
-            .id("reset-button")
-            .mt_4()
-            .px_4()
-            .py_2()
-            .bg(rgb(0x4caf50))
-            .text_color(rgb(0xffffff))
-            .text_lg()
-            .cursor_pointer()
-            .hover(|el| el.bg(rgb(0x45a049)))
-            .on_mouse_down(
-                MouseButton::Left,
-                cx.listener(|this, _event, _window, _cx| {
-                    this.reset();
-                }),
-            )
-            .child("Play Again");
+        // Rounds not reached yet are drawn as empty slots so the whole
+        // tree is visible from the start
+        let mut columns: Vec<_> = Vec::new();
+        for round in 0..bracket.round_count() {
+            let slots = (1 << (bracket.round_count() - round)) / 2;
+            let mut matches: Vec<_> = Vec::new();
+            for slot in 0..slots {
+                let game = bracket
+                    .rounds()
+                    .get(round)
+                    .and_then(|games| games.get(slot));
+                let line = |player: Option<usize>| {
+                    let won =
+                        game.is_some_and(|game| game.winner.is_some() && game.winner == player);
+                    div()
+                        .px_2()
+                        .text_color(if won { rgb(0xffd43b) } else { rgb(0xcccccc) })
+                        .child(
+                            player
+                                .map_or("TBD", |player| bracket.name(player))
+                                .to_string(),
+                        )
+                };
+                matches.push(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .w(px(120.0))
+                        .py_1()
+                        .rounded_md()
+                        .border_1()
+                        .border_color(rgb(0x404040))
+                        .bg(rgb(0x1e1e1e))
+                        .child(line(game.map(|game| game.x)))
+                        .child(line(game.map(|game| game.o))),
+                );
+            }
+            columns.push(
+                div()
+                    .flex()
+                    .flex_col()
+                    .justify_around()
+                    .gap_2()
+                    .child(div().text_xs().child(bracket.round_name(round)))
+                    .children(matches),
+            );
+        }
+        info = info.child(div().flex().gap_4().text_sm().children(columns));
 
-        let game_over = self.game_over;
+        if self.game_over() {
+            info = info.child(
+                action_button("next-bracket-game-button", "Next Match").on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, _window, _cx| {
+                        this.next_tournament_game();
+                    }),
+                ),
+            );
+        }
+        info
+    }
 
-        // Main container
+    /// Renders the screen crowning the bracket's champion.
+    fn render_bracket_champion(&self, champion: &str, cx: &mut Context<Self>) -> impl IntoElement {
         div()
             .flex()
             .flex_col()
-            .gap_4()
-            .bg(rgb(0x2d2d2d))
-            .size_full()
-            .justify_center()
             .items_center()
-            .p_4()
+            .gap_4()
             .child(
-                // Status text showing current player or game result
                 div()
-                    .text_2xl()
-                    .text_color(rgb(0xffffff))
-                    .child(self.get_status_text()),
+                    .text_xl()
+                    .text_color(rgb(0xcccccc))
+                    .child("Bracket Champion"),
             )
             .child(
-                // Game board grid
-                div().flex().flex_col().gap_2().children(rows),
+                div()
+                    .text_3xl()
+                    .text_color(rgb(0xffd43b))
+                    .child(format!("🏆 {champion}")),
             )
-            .when(game_over, |el| el.child(reset_button))
+            .child(action_button("leave-bracket-button", "Done").on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, _event, _window, _cx| {
+                    this.toggle_tournament();
+                }),
+            ))
     }
-}
 
-impl TicTacToe {
-    /// Renders a single cell of the game board.
-    ///
-    /// Each cell displays X, O, or is empty, with appropriate coloring
-    /// and hover effects for interactive feedback.
-    fn render_cell(&self, row: usize, col: usize, cx: &mut Context<Self>) -> impl IntoElement {
-        let cell_content = match self.board[row][col] {
-            Cell::Empty => "",
-            Cell::Player(Player::X) => "X",
-            Cell::Player(Player::O) => "O",
-        };
+    /// Resizes the event log panel while its splitter is dragged.
+    fn drag_split(&mut self, event: &MouseMoveEvent, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.dragging_split {
+            return;
+        }
+        let height = f32::from(window.viewport_size().height);
+        let ratio = 1.0 - f32::from(event.position.y) / height;
+        self.window_state.set_split_ratio(ratio);
+        cx.notify();
+    }
 
-        // Color scheme: gray for empty, red for X, blue for O
-        let cell_color = match self.board[row][col] {
-            Cell::Empty => rgb(0x404040),
-            Cell::Player(Player::X) => rgb(0xff6b6b),
-            Cell::Player(Player::O) => rgb(0x4dabf7),
-        };
+    /// Finishes dragging the splitter and remembers the new size.
+    fn end_split_drag(&mut self, _: &MouseUpEvent, _: &mut Window, _: &mut Context<Self>) {
+        if self.dragging_split {
+            self.dragging_split = false;
+            self.window_state.save();
+        }
+    }
 
-        let is_empty = self.board[row][col] == Cell::Empty && !self.game_over;
+    /// Renders the chat panel of a network game: a header that expands or
+    /// collapses it, showing how many lines are unread, and when expanded
+    /// the lines so far, the line being typed, and the emote buttons.
+    fn render_chat(&self, remote: &RemoteGame, cx: &mut Context<Self>) -> impl IntoElement {
+        let header = if self.show_chat {
+            "▾ Chat".to_string()
+        } else if remote.chat.unread > 0 {
+            format!("▸ Chat ({} new)", remote.chat.unread)
+        } else {
+            "▸ Chat".to_string()
+        };
+        let can_send = remote.side.is_some();
 
         div()
-            .id(ElementId::Name(format!("cell-{}-{}", row, col).into()))
-            .w(px(100.0))
-            .h(px(100.0))
-            .bg(cell_color)
-            .border_1()
-            .border_color(rgb(0x000000))
+            .w(px(320.0))
             .flex()
-            .justify_center()
-            .items_center()
-            .text_2xl()
-            .text_color(rgb(0xffffff))
-            .cursor_pointer()
-            .when(is_empty, |el| el.hover(|el| el.bg(rgb(0x505050))))
-            .on_mouse_down(
-                MouseButton::Left,
-                cx.listener(move |this, _event, _window, _cx| {
-                    this.make_move(row, col);
-                }),
+            .flex_col()
+            .gap_1()
+            .text_sm()
+            .child(
+                div()
+                    .id("chat-header")
+                    .cursor_pointer()
+                    .text_color(if remote.chat.unread > 0 {
+                        rgb(0xffd43b)
+                    } else {
+                        rgb(0xcccccc)
+                    })
+                    .child(header)
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, window, cx| {
+                            this.toggle_chat(&ToggleChat, window, cx);
+                        }),
+                    ),
+            )
+            .when(self.show_chat, |el| {
+                el.child(
+                    div()
+                        .id("chat-lines")
+                        .h(px(120.0))
+                        .overflow_y_scroll()
+                        .p_2()
+                        .bg(rgb(0x1e1e1e))
+                        .text_color(rgb(0xcccccc))
+                        .children(
+                            remote
+                                .chat
+                                .lines()
+                                .iter()
+                                .rev()
+                                .map(|line| div().child(line.to_string())),
+                        ),
+                )
+                .when(can_send, |el| {
+                    el.child(
+                        div()
+                            .px_2()
+                            .py_1()
+                            .bg(rgb(0x1e1e1e))
+                            .border_1()
+                            .border_color(rgb(0x4dabf7))
+                            .text_color(rgb(0xffffff))
+                            .child(format!("{}|", self.chat_input)),
+                    )
+                    .child(div().flex().gap_2().children(
+                        chat::EMOTES.iter().enumerate().map(|(index, &emote)| {
+                            small_button(
+                                ElementId::Name(format!("chat-emote-{index}").into()),
+                                emote,
+                                false,
+                            )
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(move |this, _event, _window, _cx| {
+                                    this.send_chat(emote);
+                                }),
+                            )
+                        }),
+                    ))
+                })
+            })
+    }
+
+    /// Renders the event log panel, newest entries first.
+    fn render_event_log(&self, height: Pixels) -> impl IntoElement {
+        div()
+            .id("event-log")
+            .w_full()
+            .h(height)
+            .flex_none()
+            .overflow_y_scroll()
+            .p_2()
+            .bg(rgb(0x1e1e1e))
+            .text_sm()
+            .text_color(rgb(0xcccccc))
+            .children(
+                self.event_log
+                    .entries()
+                    .iter()
+                    .rev()
+                    .map(|entry| div().child(entry.to_string())),
             )
-            .child(cell_content)
     }
 
     /// Returns the status text to display above the board.
     ///
     /// Shows the winner, draw message, or current player's turn.
     fn get_status_text(&self) -> String {
-        if self.game_over {
-            match self.winner {
-                Some(Player::X) => "Player X Wins!".to_string(),
-                Some(Player::O) => "Player O Wins!".to_string(),
+        if let Some(rush) = &self.puzzle_rush {
+            let player = match rush.puzzle.to_move {
+                Player::X => "X",
+                Player::O => "O",
+            };
+            if rush.strikes >= MAX_STRIKES {
+                "Out of Strikes!".to_string()
+            } else if rush.is_finished() {
+                "Time's Up!".to_string()
+            } else if rush.puzzle.kind == PuzzleKind::Win {
+                format!("{player} to Win")
+            } else {
+                format!("{player} to Block")
+            }
+        } else if self.coin_flip.is_some() {
+            "Flipping for first move...".to_string()
+        } else if self.computer_task.is_some() {
+            "Computer is thinking...".to_string()
+        } else if let Some(GameResult::Resignation { winner }) = self.result {
+            match winner {
+                Player::X => "Player O Resigns - X Wins!".to_string(),
+                Player::O => "Player X Resigns - O Wins!".to_string(),
+            }
+        } else if let Some(GameResult::Timeout { winner }) = self.result {
+            match winner {
+                Player::X => "O Ran Out of Time - X Wins!".to_string(),
+                Player::O => "X Ran Out of Time - O Wins!".to_string(),
+            }
+        } else if self.result == Some(GameResult::AgreedDraw) {
+            "Draw Agreed!".to_string()
+        } else if let Some(teams) = self.team_match.as_ref().filter(|_| !self.game_over()) {
+            let team = teams.team(self.game.to_move());
+            format!("{}: {} to Move", team.name, team.seat_to_move())
+        } else if self.opponent == Opponent::Computer && self.game_over() {
+            match self.winner() {
+                Some(winner) if winner == COMPUTER_SIDE => "Computer Wins!".to_string(),
+                Some(_) => "You Win!".to_string(),
                 None => "It's a Draw!".to_string(),
             }
+        } else if let Outcome::Won(winner, _) = self.outcome() {
+            match (self.rules.misere, winner) {
+                (true, Player::X) => "O Completed a Line - X Wins!".to_string(),
+                (true, Player::O) => "X Completed a Line - O Wins!".to_string(),
+                (false, Player::X) => "Player X Wins!".to_string(),
+                (false, Player::O) => "Player O Wins!".to_string(),
+            }
+        } else if self.game_over() {
+            "It's a Draw!".to_string()
         } else {
             format!(
                 "Current Player: {}",
-                match self.current_player {
+                match self.game.to_move() {
                     Player::X => "X",
                     Player::O => "O",
                 }
@@ -267,19 +7774,389 @@ impl TicTacToe {
     }
 }
 
+/// Renders the 2v2 scoreboard with each team's name and results.
+fn render_team_scoreboard(teams: &TeamMatch) -> impl IntoElement {
+    let (x, o) = (teams.team(Player::X), teams.team(Player::O));
+    div().text_color(rgb(0xcccccc)).child(format!(
+        "{} {} - {} {}  ·  {} drawn",
+        x.name, x.wins, o.wins, o.name, x.draws
+    ))
+}
+
+/// Renders the series score, such as "X 2 – 1 O", and the series winner
+/// once there is one.
+fn render_series_header(series: &Series) -> impl IntoElement {
+    let (x, o) = (series.score(Player::X), series.score(Player::O));
+    let text = match series.champion() {
+        Some(Player::X) => format!("X wins the series {x} – {o}"),
+        Some(Player::O) => format!("O wins the series {o} – {x}"),
+        None => format!("Best of {}  ·  X {x} – {o} O", series.best_of),
+    };
+    div().text_color(rgb(0xcccccc)).child(text)
+}
+
+/// Renders the speedrun's clock and split, such as "Medium · 0:42.1 ·
+/// split 0:12.3 (-1.4)", with the delta against the split's best, and the
+/// run's time against the best run once it is over.
+fn render_speedrun_header(run: &Speedrun) -> impl IntoElement {
+    let clock = |time: Duration| {
+        let tenths = time.as_millis() / 100;
+        format!("{}:{:02}.{}", tenths / 600, tenths / 10 % 60, tenths % 10)
+    };
+    if run.is_finished() {
+        let total = run.elapsed();
+        let best = match run.bests.run() {
+            Some(best) if total < best => "new best!".to_string(),
+            Some(best) => format!("best {}", clock(best)),
+            None => "first run".to_string(),
+        };
+        return div()
+            .text_color(rgb(0xcccccc))
+            .child(format!("Speedrun done in {} · {best}", clock(total)));
+    }
+
+    let difficulty = match run.difficulty() {
+        Difficulty::Easy => "Easy",
+        Difficulty::Medium => "Medium",
+        Difficulty::Hard => "Hard",
+    };
+    let (delta, color) = match run.delta() {
+        Some(delta) if delta > 0.0 => (format!(" (+{delta:.1})"), rgb(0xff6b6b)),
+        Some(delta) => (format!(" ({delta:.1})"), rgb(0x4caf50)),
+        None => (String::new(), rgb(0xcccccc)),
+    };
+    div()
+        .flex()
+        .gap_1()
+        .text_color(rgb(0xcccccc))
+        .child(format!(
+            "Speedrun {}/{} · {difficulty} · {} · split {}",
+            run.split() + 1,
+            speedrun::SPLITS.len(),
+            clock(run.elapsed()),
+            clock(run.split_elapsed())
+        ))
+        .child(div().text_color(color).child(delta))
+}
+
+/// Describes a LAN or online game's connection and whether the other
+/// player is there, such as "Online · room 42 · playing X · opponent
+/// connected", followed by the session's score once a game has finished.
+fn connection_status(remote: &RemoteGame) -> String {
+    let place = match &remote.room {
+        Some(room) => format!("Online · room {room}"),
+        None => "LAN".to_string(),
+    };
+    let Some(side) = remote.side else {
+        let players = if remote.opponent_present {
+            "both players here"
+        } else {
+            "waiting for players"
+        };
+        return format!("{place} · watching · {players}");
+    };
+    let opponent = if remote.opponent_present {
+        "opponent connected"
+    } else {
+        "waiting for an opponent"
+    };
+    let status = format!("{place} · playing {side:?} · {opponent}");
+    match remote.score {
+        score if score.played() == 0 => status,
+        Score {
+            wins: [x, o],
+            draws,
+        } => format!("{status} · X {x} – {o} O, {draws} drawn"),
+    }
+}
+
+/// Returns the letter labelling a board column, starting from `a`.
+fn column_label(col: usize) -> String {
+    char::from(b'a' + col as u8).to_string()
+}
+
+/// Returns the number labelling a board row, starting from 1 at the top.
+fn row_label(row: usize) -> String {
+    (row + 1).to_string()
+}
+
+/// Reads moves saved or sent as rows and columns, leaving out any off the
+/// board.
+fn positions(moves: &[(usize, usize, Player)]) -> Vec<(Position, Player)> {
+    moves
+        .iter()
+        .filter_map(|&(row, col, player)| Some((Position::new(row, col)?, player)))
+        .collect()
+}
+
+/// Writes moves as rows and columns for saving or sending.
+fn coordinates(moves: &[(Position, Player)]) -> Vec<(usize, usize, Player)> {
+    moves
+        .iter()
+        .map(|&(position, player)| (position.row(), position.col(), player))
+        .collect()
+}
+
+/// Returns the coordinate name of a cell, such as `b2` for the center.
+fn cell_name(row: usize, col: usize) -> String {
+    format!("{}{}", column_label(col), row_label(row))
+}
+
+/// Checks if `player` has a line on `board`, whose edges do not wrap.
+fn has_line(board: &Board, player: Player) -> bool {
+    find_line(board, player, Topology::Flat).is_some()
+}
+
+/// Renders the History screen's heatmap of the win rate from each first
+/// move, running from red for none won to green for all won, with cells
+/// never opened on left grey.
+fn render_opening_heatmap(view: &HistoryView) -> impl IntoElement {
+    let title = match &view.player {
+        Some(player) => format!("{player}'s win rate by first move"),
+        None => "Opener's win rate by first move".to_string(),
+    };
+    let rows = view.openings.iter().map(|cells| {
+        div().flex().gap_1().children(cells.iter().map(|stats| {
+            let (background, label) = match stats.win_rate() {
+                Some(rate) => (
+                    Hsla {
+                        h: rate / 3.0,
+                        s: 0.6,
+                        l: 0.35,
+                        a: 1.0,
+                    },
+                    format!("{:.0}%", rate * 100.0),
+                ),
+                None => (Hsla::from(rgb(0x404040)), String::new()),
+            };
+            div()
+                .size(px(64.0))
+                .flex()
+                .flex_col()
+                .justify_center()
+                .items_center()
+                .bg(background)
+                .child(div().text_sm().child(label))
+                .when(stats.games > 0, |el| {
+                    el.child(
+                        div()
+                            .text_xs()
+                            .text_color(rgb(0xcccccc))
+                            .child(format!("{} games", stats.games)),
+                    )
+                })
+        }))
+    });
+    div()
+        .flex()
+        .flex_col()
+        .items_center()
+        .gap_1()
+        .child(div().text_sm().text_color(rgb(0xcccccc)).child(title))
+        .children(rows)
+}
+
+/// Renders the coin shown while flipping for the first move.
+fn render_coin(flip: CoinFlip) -> impl IntoElement {
+    let (label, color) = match flip.face {
+        Player::X => ("X", rgb(0xff6b6b)),
+        Player::O => ("O", rgb(0x4dabf7)),
+    };
+    div()
+        .size(px(56.0))
+        .rounded_full()
+        .bg(color)
+        .border_2()
+        .border_color(rgb(0xffd43b))
+        .flex()
+        .justify_center()
+        .items_center()
+        .text_2xl()
+        .text_color(rgb(0xffffff))
+        .child(label)
+}
+
+/// Renders the faint arrows shown above and below a toroidal board.
+fn wrap_indicator(arrows: &'static str) -> impl IntoElement {
+    div()
+        .flex()
+        .justify_center()
+        .text_xs()
+        .text_color(rgb(0x5c7cfa))
+        .child(arrows)
+}
+
+/// Plays `mv` in an Ultimate, Qubic, Notakto, or Connect-K game and,
+/// against the computer, its reply, found by searching `depth` moves
+/// ahead.
+///
+/// An illegal move is ignored, as a click on a taken cell is.
+fn play_variant<G: Game>(game: &mut G, mv: G::Move, opponent: Opponent, depth: u32) {
+    if game.apply_move(mv).is_err() || opponent != Opponent::Computer {
+        return;
+    }
+    if game.status() == Status::InProgress && game.current_player() == COMPUTER_SIDE {
+        if let Some(reply) = ai::search(game, depth) {
+            // The search only returns legal moves
+            let _ = game.apply_move(reply);
+        }
+    }
+}
+
+/// Creates a green text button used for the game-over actions.
+fn action_button(id: &'static str, label: &'static str) -> Stateful<Div> {
+    div()
+        .id(id)
+        .mt_4()
+        .px_4()
+        .py_2()
+        .bg(rgb(0x4caf50))
+        .text_color(rgb(0xffffff))
+        .text_lg()
+        .cursor_pointer()
+        .hover(|el| el.bg(rgb(0x45a049)))
+        .child(label)
+}
+
+/// Creates a small secondary button, highlighted when `active`.
+fn small_button(id: impl Into<ElementId>, label: &'static str, active: bool) -> Stateful<Div> {
+    div()
+        .id(id)
+        .px_2()
+        .py_1()
+        .bg(if active { rgb(0x4dabf7) } else { rgb(0x404040) })
+        .text_color(rgb(0xffffff))
+        .text_sm()
+        .cursor_pointer()
+        .hover(|el| el.bg(rgb(0x505050)))
+        .child(label)
+}
+
+/// Computes the side length of a board cell to fill `area`, the region
+/// the board was laid out in, or the smallest size before it has been.
+///
+/// The size is snapped to whole device pixels so cell edges and marks
+/// stay crisp at any scale factor.
+fn cell_size(window: &Window, area: Option<Size<Pixels>>) -> Pixels {
+    let Some(area) = area else {
+        return MIN_CELL_SIZE;
+    };
+    let available = f32::from(area.width.min(area.height) - BOARD_MARGIN);
+    let logical = (available / 3.0).max(f32::from(MIN_CELL_SIZE));
+    let scale = window.scale_factor();
+    px((logical * scale).floor() / scale)
+}
+
+/// Whether the window can be pinned above other windows.
+///
+/// A pinned window is opened as a pop-up, which GPUI keeps above other
+/// windows on macOS and Windows. Elsewhere a pop-up is not a normal
+/// top-level window, so pinning is not offered.
+const PIN_SUPPORTED: bool = cfg!(any(target_os = "macos", target_os = "windows"));
+
+/// Builds the options for the game window.
+///
+/// A pinned window is opened as a pop-up so it stays above other windows.
+fn window_options(
+    display_id: Option<DisplayId>,
+    bounds: Bounds<Pixels>,
+    pinned: bool,
+) -> WindowOptions {
+    WindowOptions {
+        window_bounds: Some(WindowBounds::Windowed(bounds)),
+        display_id,
+        kind: if pinned {
+            WindowKind::PopUp
+        } else {
+            WindowKind::Normal
+        },
+        titlebar: Some(TitlebarOptions {
+            title: Some(APP_TITLE.into()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
 /// Application entry point.
 ///
-/// Creates a 400x500 window centered on the screen and initializes the game.
+/// Reopens the window where it was last placed, or creates a 400x500
+/// window centered on the primary display, and initializes the game.
 fn main() {
-    Application::new().run(|cx: &mut App| {
-        let bounds = Bounds::centered(None, size(px(400.0), px(500.0)), cx);
-        cx.open_window(
-            WindowOptions {
-                window_bounds: Some(WindowBounds::Windowed(bounds)),
-                ..Default::default()
-            },
-            |_, cx| cx.new(|_| TicTacToe::new()),
-        )
+    let args = Args::parse();
+    // Held until exit so buffered log records are flushed
+    let _log_guard = logging::init(args.log_level);
+    if let Some(Command::Simulate(options)) = args.command {
+        if let Err(err) = simulate::run(options) {
+            eprintln!("tic-tac-toe: {err}");
+            std::process::exit(1);
+        }
+        return;
+    }
+    if args.cli {
+        let opponent = if args.two_player {
+            Opponent::Human
+        } else {
+            Opponent::Computer
+        };
+        if let Err(err) = cli::run(opponent, args.difficulty) {
+            eprintln!("tic-tac-toe: {err}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(theme) = args.theme.as_deref().filter(|&theme| theme != args::THEME) {
+        eprintln!(
+            "tic-tac-toe: there is no {theme:?} theme; only {:?} is available",
+            args::THEME
+        );
+        std::process::exit(1);
+    }
+    let bot = args.bot.as_deref().map(Bot::start).transpose();
+    let game = args
+        .load
+        .as_deref()
+        .map(|path| {
+            Notation::load(path).map_err(|err| {
+                io::Error::new(
+                    err.kind(),
+                    format!("could not load {}: {err}", path.display()),
+                )
+            })
+        })
+        .transpose();
+    let launch = match (bot, game) {
+        (Ok(bot), Ok(game)) => Launch {
+            vs_ai: args.vs_ai,
+            bot,
+            game: game.map(|notation| notation.record),
+            size: args.size,
+        },
+        (Err(err), _) | (_, Err(err)) => {
+            eprintln!("tic-tac-toe: {err}");
+            std::process::exit(1);
+        }
+    };
+    let fullscreen = args.fullscreen;
+
+    Application::new().run(move |cx: &mut App| {
+        keymap::bind_keys(cx);
+
+        let window_state = WindowState::load();
+        let (display_id, bounds) = match window_state.restore(cx) {
+            Some((display_id, bounds)) => (Some(display_id), bounds),
+            None => (None, Bounds::centered(None, size(px(400.0), px(500.0)), cx)),
+        };
+        let mut options = window_options(display_id, bounds, false);
+        if fullscreen {
+            options.window_bounds = Some(WindowBounds::Fullscreen(bounds));
+        }
+        cx.open_window(options, |window, cx| {
+            let view = cx.new(|cx| TicTacToe::new(window_state, launch, window, cx));
+            window.focus(&view.focus_handle(cx));
+            view
+        })
         .unwrap();
     });
 }
@@ -0,0 +1,383 @@
+//! LAN play over TCP.
+//!
+//! One player hosts by listening on [`DEFAULT_PORT`] and the other joins
+//! by connecting to the host's address. The host plays X and decides when
+//! a game starts and under which rules; after that each side sends the
+//! moves it makes.
+//!
+//! Messages are JSON, each preceded by its length as a four-byte
+//! big-endian number. Each side's first message is a [`Message::Hello`]
+//! giving its [`PROTOCOL_VERSION`], and a connection to a copy of the game
+//! speaking another version fails with [`VersionMismatch`] rather than
+//! letting the two boards drift apart. Sockets are non-blocking and polled from the UI's
+//! own timer, so no thread ever waits on the network.
+//!
+//! The same [`Message`]s also travel through an online relay; see
+//! [`crate::online`]. A [`Connection`] hides which of the two carries
+//! them.
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{IpAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+use crate::chat::Chat;
+use crate::discovery::Advertisement;
+use crate::online::Relay;
+use crate::rules::RuleSet;
+use crate::{Board, Player};
+
+pub use tic_tac_toe::protocol::{Message, PROTOCOL_VERSION};
+
+/// Port a host listens on.
+pub const DEFAULT_PORT: u16 = 7878;
+
+/// How long joining waits for the host to answer.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Largest message accepted, well above any real one, so a bad length
+/// cannot make us allocate without bound.
+const MAX_MESSAGE_LEN: usize = 64 * 1024;
+
+/// The other side speaks a protocol version other than
+/// [`PROTOCOL_VERSION`].
+#[derive(Clone, Copy, Debug)]
+pub struct VersionMismatch {
+    /// The version the other side speaks, or 0 for copies of the game from
+    /// before versions were exchanged.
+    pub theirs: u32,
+}
+
+impl fmt::Display for VersionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.theirs {
+            0 => write!(f, "the other side runs an older version of the game"),
+            theirs => write!(
+                f,
+                "the other side speaks protocol version {theirs}, and this copy speaks \
+                 version {PROTOCOL_VERSION}"
+            ),
+        }
+    }
+}
+
+impl Error for VersionMismatch {}
+
+impl From<VersionMismatch> for io::Error {
+    fn from(mismatch: VersionMismatch) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, mismatch)
+    }
+}
+
+/// Returns the other side's version if `err` says it speaks a different
+/// protocol.
+pub fn version_mismatch(err: &io::Error) -> Option<u32> {
+    err.get_ref()?
+        .downcast_ref::<VersionMismatch>()
+        .map(|mismatch| mismatch.theirs)
+}
+
+/// Something that happened on a connection.
+#[derive(Clone, Debug)]
+pub enum Event {
+    /// The other player sent a message.
+    Message(Message),
+    /// The other player joined the relay room.
+    OpponentJoined,
+    /// The other player took their seat in the relay room back after
+    /// their connection dropped.
+    OpponentReturned,
+    /// The other player left the relay room, which stays open for them to
+    /// come back.
+    OpponentLeft,
+    /// This many spectators are now watching the relay room.
+    Spectators(usize),
+    /// The relay refused this player's last message, for the given reason.
+    Rejected(String),
+    /// The relay sent the game so far after this player rejoined, with the
+    /// resignation or accepted draw that ended it, if one did.
+    Resync {
+        first: Player,
+        board: Board,
+        rules: RuleSet,
+        moves: Vec<(usize, usize, Player)>,
+        ending: Option<Message>,
+    },
+    /// The relay recorded the game that just finished under this id.
+    Recorded(String),
+}
+
+/// A host waiting for someone to join.
+pub struct Host {
+    listener: TcpListener,
+    /// This machine's address on the local network, for showing to the
+    /// player who joins.
+    pub address: Option<IpAddr>,
+    /// The game's mDNS advertisement, if it could be made. It ends when
+    /// the host is dropped.
+    _advertisement: Option<Advertisement>,
+}
+
+impl Host {
+    /// Starts listening for a player to join on `port`, advertising the
+    /// game on the network as hosted by `name`.
+    pub fn listen(port: u16, name: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        listener.set_nonblocking(true)?;
+        let address = local_ip();
+        // Without an advertisement the address can still be typed in
+        let advertisement = address.and_then(|ip| Advertisement::start(name, ip, port).ok());
+        Ok(Self {
+            listener,
+            address,
+            _advertisement: advertisement,
+        })
+    }
+
+    /// Returns the connection to a player who has joined, if one has.
+    pub fn accept(&self) -> io::Result<Option<Connection>> {
+        match self.listener.accept() {
+            Ok((stream, _)) => Connection::new(stream).map(Some),
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Connects to a host at `address`, which may leave out the port to use
+/// [`DEFAULT_PORT`].
+///
+/// This blocks until the host answers or [`CONNECT_TIMEOUT`] passes, so
+/// it should be run off the UI thread.
+pub fn join(address: &str) -> io::Result<Connection> {
+    let address = address.trim();
+    // A colon separates the port, except inside a bare IPv6 address
+    let mut targets = if address.contains(':') && address.parse::<IpAddr>().is_err() {
+        address.to_socket_addrs()?
+    } else {
+        (address, DEFAULT_PORT).to_socket_addrs()?
+    };
+    let target = targets
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no such address"))?;
+    Connection::new(TcpStream::connect_timeout(&target, CONNECT_TIMEOUT)?)
+}
+
+/// Returns this machine's address on the local network.
+///
+/// Connecting a UDP socket sends nothing; it only makes the system pick
+/// the interface it would route through.
+fn local_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind(("0.0.0.0", 0)).ok()?;
+    socket.connect(("192.0.2.1", 9)).ok()?;
+    Some(socket.local_addr().ok()?.ip())
+}
+
+/// An open connection to the other player.
+pub enum Connection {
+    /// A direct connection on the local network.
+    Lan {
+        stream: TcpStream,
+        /// Bytes read that do not yet make up a whole message.
+        buffer: Vec<u8>,
+        /// Bytes sent that the socket has not taken yet.
+        outgoing: Vec<u8>,
+        /// Whether the other side's [`Message::Hello`] has arrived.
+        greeted: bool,
+    },
+    /// A connection through an online relay.
+    Online(Box<Relay>),
+}
+
+impl Connection {
+    fn new(stream: TcpStream) -> io::Result<Self> {
+        stream.set_nonblocking(true)?;
+        stream.set_nodelay(true)?;
+        let mut connection = Self::Lan {
+            stream,
+            buffer: Vec::new(),
+            outgoing: Vec::new(),
+            greeted: false,
+        };
+        connection.send(&Message::Hello {
+            version: PROTOCOL_VERSION,
+        })?;
+        Ok(connection)
+    }
+
+    /// Sends `message` to the other player.
+    ///
+    /// Whatever a full socket does not take is queued and goes out on a
+    /// later call, or on the next [`Self::receive`].
+    pub fn send(&mut self, message: &Message) -> io::Result<()> {
+        let (stream, outgoing) = match self {
+            Connection::Lan {
+                stream, outgoing, ..
+            } => (stream, outgoing),
+            Connection::Online(relay) => return relay.send(message),
+        };
+        let json = serde_json::to_vec(message)?;
+        outgoing.extend((json.len() as u32).to_be_bytes());
+        outgoing.extend(json);
+        flush(stream, outgoing)
+    }
+
+    /// Returns everything that has arrived since the last call.
+    ///
+    /// Fails once the other player has disconnected or sent something
+    /// that is not a message, or if their first message shows they speak
+    /// another protocol version.
+    pub fn receive(&mut self) -> io::Result<Vec<Event>> {
+        let (stream, buffer, outgoing, greeted) = match self {
+            Connection::Lan {
+                stream,
+                buffer,
+                outgoing,
+                greeted,
+            } => (stream, buffer, outgoing, greeted),
+            Connection::Online(relay) => return relay.receive(),
+        };
+        // Finish sending anything a full socket held back last time
+        flush(stream, outgoing)?;
+        let mut chunk = [0; 1024];
+        loop {
+            match stream.read(&mut chunk) {
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(read) => buffer.extend_from_slice(&chunk[..read]),
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                Err(err) => return Err(err),
+            }
+        }
+
+        let mut events = Vec::new();
+        while let Some(header) = buffer.first_chunk::<4>() {
+            let len = u32::from_be_bytes(*header) as usize;
+            if len > MAX_MESSAGE_LEN {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "message too long",
+                ));
+            }
+            if buffer.len() < 4 + len {
+                break;
+            }
+            let frame: Vec<_> = buffer.drain(..4 + len).collect();
+            let message = serde_json::from_slice(&frame[4..])?;
+            if !*greeted {
+                check_hello(&message)?;
+                *greeted = true;
+                continue;
+            }
+            events.push(Event::Message(message));
+        }
+        Ok(events)
+    }
+}
+
+/// Writes as much of `outgoing` as the non-blocking `stream` takes,
+/// leaving the rest queued.
+fn flush(stream: &mut TcpStream, outgoing: &mut Vec<u8>) -> io::Result<()> {
+    while !outgoing.is_empty() {
+        match stream.write(outgoing) {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(written) => {
+                outgoing.drain(..written);
+            }
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the first message from the other side is a
+/// [`Message::Hello`] for this protocol version.
+fn check_hello(message: &Message) -> io::Result<()> {
+    match *message {
+        Message::Hello { version } if version == PROTOCOL_VERSION => Ok(()),
+        Message::Hello { version } => Err(VersionMismatch { theirs: version }.into()),
+        // Copies from before the handshake start straight in with the game
+        _ => Err(VersionMismatch { theirs: 0 }.into()),
+    }
+}
+
+/// A game in progress against a player on another machine.
+pub struct RemoteGame {
+    /// The side this instance plays: X when hosting or first into a relay
+    /// room, O when joining, and `None` when watching.
+    pub side: Option<Player>,
+    pub connection: Connection,
+    /// The relay room code, for online games.
+    pub room: Option<String>,
+    /// Whether the other player is there. Over a LAN they always are while
+    /// connected; in a relay room they may not have arrived yet. For a
+    /// spectator, whether both players are.
+    pub opponent_present: bool,
+    /// How many spectators are watching the relay room.
+    pub spectators: usize,
+    pub chat: Chat,
+    /// A takeback request waiting on an answer, or just declined.
+    pub takeback: Option<Takeback>,
+    /// A rematch one side has asked for, until the other agrees.
+    pub rematch: Option<Rematch>,
+    /// Games won and drawn since the session began.
+    pub score: Score,
+}
+
+/// Where a takeback request stands.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Takeback {
+    /// This player asked and is waiting for the answer.
+    Requested,
+    /// The other player asked, with this many moves on the board.
+    Offered(usize),
+    /// The other player said no, shown until the next move.
+    Declined,
+}
+
+/// Where a rematch request stands.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rematch {
+    /// This player asked and is waiting for the other to agree.
+    Requested,
+    /// The other player asked.
+    Offered,
+}
+
+/// The running score of a network session, kept across rematches.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Score {
+    /// Games won by each side, indexed by [`Player::index`].
+    pub wins: [u32; 2],
+    pub draws: u32,
+}
+
+impl Score {
+    /// Records a finished game, a draw when `winner` is `None`.
+    pub fn record(&mut self, winner: Option<Player>) {
+        match winner {
+            Some(winner) => self.wins[winner.index()] += 1,
+            None => self.draws += 1,
+        }
+    }
+
+    /// Returns the number of games finished.
+    pub fn played(&self) -> u32 {
+        self.wins.iter().sum::<u32>() + self.draws
+    }
+}
+
+/// An online game waiting to rejoin its relay room after the connection
+/// dropped.
+pub struct Reconnecting {
+    pub room: String,
+    /// The side played, or `None` when watching.
+    pub side: Option<Player>,
+    /// The chat so far, carried over to the rejoined game.
+    pub chat: Chat,
+    /// The session's score so far, carried over likewise.
+    pub score: Score,
+    /// Attempts made so far.
+    pub attempts: u32,
+}
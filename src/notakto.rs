@@ -0,0 +1,153 @@
+//! Notakto: impartial tic-tac-toe across several boards.
+//!
+//! Both players place X. Completing a line kills that board, and no more
+//! marks may be placed on it. The player who kills the last live board
+//! loses.
+
+use tic_tac_toe::engine::MoveError;
+use tic_tac_toe::game::{Game, Status};
+
+use crate::{has_line, Board, Cell, Player};
+
+/// Most boards a Notakto game can be played on.
+pub const MAX_BOARDS: usize = 3;
+
+/// How many moves ahead the computer looks before scoring a position as
+/// undecided.
+pub const SEARCH_DEPTH: u32 = 3;
+
+/// The state of a Notakto game.
+#[derive(Clone, Debug)]
+pub struct Notakto {
+    /// The boards in play, left to right.
+    pub boards: Vec<Board>,
+    /// Whether each board has been killed by a completed line.
+    pub dead: Vec<bool>,
+    /// The player whose turn it is. Both players place X; this only
+    /// tracks who is moving.
+    pub to_move: Player,
+    /// The player who killed the last board, once the game is over.
+    pub loser: Option<Player>,
+}
+
+impl Notakto {
+    /// Creates a game on `count` empty boards with `first` to move.
+    pub fn new(count: usize, first: Player) -> Self {
+        let count = count.clamp(1, MAX_BOARDS);
+        Self {
+            boards: vec![[[Cell::Empty; 3]; 3]; count],
+            dead: vec![false; count],
+            to_move: first,
+            loser: None,
+        }
+    }
+
+    /// Whether the game is over.
+    pub fn is_finished(&self) -> bool {
+        self.loser.is_some()
+    }
+
+    /// Places an X for the current player, returning whether the move was
+    /// legal. A cell off the boards is refused.
+    pub fn play(&mut self, board: usize, row: usize, col: usize) -> bool {
+        if self.is_finished() || board >= self.boards.len() || row >= 3 || col >= 3 {
+            return false;
+        }
+        if self.dead[board] || self.boards[board][row][col] != Cell::Empty {
+            return false;
+        }
+
+        self.boards[board][row][col] = Cell::Player(Player::X);
+        if has_line(&self.boards[board], Player::X) {
+            self.dead[board] = true;
+        }
+        if self.dead.iter().all(|&dead| dead) {
+            self.loser = Some(self.to_move);
+        } else {
+            self.to_move = self.to_move.other();
+        }
+        true
+    }
+}
+
+impl Game for Notakto {
+    /// A cell as (board, row, column).
+    type Move = (usize, usize, usize);
+
+    fn current_player(&self) -> Player {
+        self.to_move
+    }
+
+    fn legal_moves(&self) -> Vec<(usize, usize, usize)> {
+        if self.is_finished() {
+            return Vec::new();
+        }
+        (0..self.boards.len())
+            .filter(|&board| !self.dead[board])
+            .flat_map(|board| (0..9).map(move |cell| (board, cell / 3, cell % 3)))
+            .filter(|&(board, row, col)| self.boards[board][row][col] == Cell::Empty)
+            .collect()
+    }
+
+    fn apply_move(&mut self, (board, row, col): (usize, usize, usize)) -> Result<(), MoveError> {
+        if self.is_finished() {
+            return Err(MoveError::GameOver);
+        }
+        if board >= self.boards.len() || row >= 3 || col >= 3 {
+            return Err(MoveError::OutOfBounds);
+        }
+        if self.dead[board] {
+            return Err(MoveError::BoardClosed);
+        }
+        if self.play(board, row, col) {
+            Ok(())
+        } else {
+            Err(MoveError::CellOccupied)
+        }
+    }
+
+    /// Notakto cannot be drawn: the player who kills the last board
+    /// loses.
+    fn status(&self) -> Status {
+        self.loser
+            .map_or(Status::InProgress, |loser| Status::Won(loser.other()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_off_the_boards_are_refused() {
+        let mut game = Notakto::new(2, Player::X);
+        assert_eq!(game.apply_move((2, 0, 0)), Err(MoveError::OutOfBounds));
+        assert_eq!(game.apply_move((0, 3, 0)), Err(MoveError::OutOfBounds));
+        assert_eq!(game.apply_move((1, 0, 3)), Err(MoveError::OutOfBounds));
+        assert!(!game.play(2, 0, 0));
+        assert_eq!(game.to_move, Player::X);
+        assert!(game.legal_moves().iter().all(|&(board, _, _)| board < 2));
+    }
+
+    #[test]
+    fn a_killed_board_is_closed() {
+        let mut game = Notakto::new(2, Player::X);
+        for col in 0..3 {
+            game.apply_move((0, 0, col)).unwrap();
+        }
+        assert!(game.dead[0]);
+        assert_eq!(game.apply_move((0, 2, 2)), Err(MoveError::BoardClosed));
+        assert_eq!(game.apply_move((1, 1, 1)), Ok(()));
+        assert_eq!(game.apply_move((1, 1, 1)), Err(MoveError::CellOccupied));
+    }
+
+    #[test]
+    fn killing_the_last_board_loses() {
+        let mut game = Notakto::new(1, Player::X);
+        for col in 0..3 {
+            game.apply_move((0, 0, col)).unwrap();
+        }
+        assert_eq!(game.status(), Status::Won(Player::O));
+        assert_eq!(game.apply_move((0, 2, 2)), Err(MoveError::GameOver));
+    }
+}
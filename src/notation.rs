@@ -0,0 +1,610 @@
+//! Portable text notation for games.
+//!
+//! A game is written as a few PGN-style header lines followed by the
+//! moves, each tagged with the player who made it:
+//!
+//! ```text
+//! [X "Alice"]
+//! [O "Bob"]
+//! [Date "2026-10-16"]
+//! [Variant "Standard"]
+//! [Result "Draw"]
+//!
+//! X:b2 O:a1 X:c3 O:a3 X:a2 O:c2 X:b1 O:b3 X:c1
+//! ```
+//!
+//! The result is `X` or `O` for a win, `Draw`, `X by resignation` or
+//! `O by resignation`, `X on time` or `O on time`, `Draw by agreement`,
+//! or `*` for a game still in progress. A game that started from a custom position adds a `Setup`
+//! header listing the nine cells in reading order, with `.` for empty and
+//! `#` for blocked.
+//!
+//! Moves may be annotated: a glyph judging the move follows it directly,
+//! `!!` for brilliant, `!` for good, or `?` for bad, and a comment in
+//! braces after it, such as `X:b2! {takes the centre}`. A comment ends on
+//! the line it starts on.
+//!
+//! The `Variant` header names the rules the game was played under, and
+//! parsing replays the moves under them, so moves out of turn, onto a
+//! taken cell or after the game ended are refused, as is a result the
+//! moves contradict: a win or draw must be on the board, and a result
+//! decided away from it must leave the board undecided. Files written
+//! before those results had their own tags give a plain win or `Draw` for
+//! them, which on an undecided board is read as a resignation or an agreed
+//! draw. Errors report the line of the first
+//! problem. Unknown headers are ignored.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+
+use crate::annotation::{Annotation, Glyph};
+use crate::game_record::GameRecord;
+use crate::game_result::GameResult;
+use crate::history::HistoryEntry;
+use crate::rules::RuleSet;
+use crate::{cell_name, Board, Cell, GameState, Outcome, Player, Position, Topology};
+
+/// File name suggested when exporting a game.
+pub const DEFAULT_FILE_NAME: &str = "tic-tac-toe.ttt";
+
+/// A game with the details written in its headers.
+#[derive(Clone, Debug)]
+pub struct Notation {
+    /// Name of the player who had X.
+    pub x_player: String,
+    /// Name of the player who had O.
+    pub o_player: String,
+    /// The day the game was played.
+    pub date: NaiveDate,
+    /// The rules the game was played under, such as "Standard".
+    pub variant: String,
+    /// The game itself.
+    pub record: GameRecord,
+}
+
+impl Notation {
+    /// Returns the notation for a game from the history.
+    pub fn from_history(entry: &HistoryEntry) -> Self {
+        Self {
+            x_player: entry.x_player.clone(),
+            o_player: entry.o_player.clone(),
+            date: entry.played_at.date_naive(),
+            variant: entry.variant.clone(),
+            record: entry.record.clone(),
+        }
+    }
+
+    /// Writes the game to `path`.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    /// Reads a game from `path`.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        text.parse()
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+}
+
+/// A problem found while parsing notation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    /// The line the problem is on, counting from 1.
+    pub line: usize,
+    /// What is wrong with it.
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+impl FromStr for Notation {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        let mut notation = Notation {
+            x_player: "Player X".to_string(),
+            o_player: "Player O".to_string(),
+            date: NaiveDate::default(),
+            variant: "Standard".to_string(),
+            record: GameRecord {
+                rules: RuleSet::default(),
+                initial_board: [[Cell::Empty; 3]; 3],
+                starting_player: Player::X,
+                coin_seed: None,
+                moves: Vec::new(),
+                current_player: Player::X,
+                result: None,
+                annotations: Default::default(),
+            },
+        };
+        let mut tokens = Vec::new();
+        // The claimed result and the line it is on, checked against the
+        // moves once they have been replayed
+        let mut claimed = None;
+
+        for (index, line) in text.lines().enumerate() {
+            let number = index + 1;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('[') {
+                let (key, value) = parse_header(header).ok_or_else(|| {
+                    ParseError::new(number, "expected a header like [Key \"Value\"]")
+                })?;
+                match key {
+                    "X" => notation.x_player = value.to_string(),
+                    "O" => notation.o_player = value.to_string(),
+                    "Variant" => {
+                        notation.record.rules = parse_variant(value).ok_or_else(|| {
+                            ParseError::new(number, format!("unknown variant \"{value}\""))
+                        })?;
+                        notation.variant = value.to_string();
+                    }
+                    "Date" => {
+                        notation.date =
+                            NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+                                ParseError::new(number, format!("bad date \"{value}\""))
+                            })?;
+                    }
+                    "Result" => {
+                        let result = parse_result(value).ok_or_else(|| {
+                            ParseError::new(number, format!("unknown result \"{value}\""))
+                        })?;
+                        claimed = Some((number, value, result));
+                    }
+                    "Setup" => {
+                        notation.record.initial_board = parse_board(value).ok_or_else(|| {
+                            ParseError::new(number, "setup must be nine of X, O, . and #")
+                        })?;
+                    }
+                    _ => {}
+                }
+            } else {
+                let line_tokens = split_moves(line)
+                    .ok_or_else(|| ParseError::new(number, "comment is missing its \"}\""))?;
+                tokens.extend(line_tokens.into_iter().map(|token| (number, token)));
+            }
+        }
+
+        // The moves are replayed under the game's rules, so a move out of
+        // turn, onto a taken cell, or after the game ended is caught. In
+        // blind play a forfeit passes the turn without a move.
+        let blind = notation.variant.split(", ").any(|name| name == "Blind");
+        let record = &mut notation.record;
+        let first = tokens.iter().find_map(|&(_, token)| match token {
+            Token::Move(token) => parse_move(Glyph::split(token).0),
+            Token::Comment(_) => None,
+        });
+        if let Some((_, first)) = first {
+            record.starting_player = first;
+        }
+        let mut game = GameState::new(record.starting_player, record.initial_board, record.rules);
+        for (number, token) in tokens {
+            let token = match token {
+                Token::Move(token) => token,
+                Token::Comment(comment) => {
+                    let index = record.moves.len().checked_sub(1).ok_or_else(|| {
+                        ParseError::new(number, "comment comes before the first move")
+                    })?;
+                    let annotation = record.annotations.entry(index).or_default();
+                    if !annotation.comment.is_empty() {
+                        annotation.comment.push(' ');
+                    }
+                    annotation.comment.push_str(comment);
+                    continue;
+                }
+            };
+            let (token, glyph) = Glyph::split(token);
+            let (position, player) = parse_move(token)
+                .ok_or_else(|| ParseError::new(number, format!("bad move \"{token}\"")))?;
+            if player != game.to_move() && blind {
+                game.pass();
+            }
+            let problem = if game.outcome().is_over() {
+                Some("is played after the game ended")
+            } else if player != game.to_move() {
+                Some("is played out of turn")
+            } else {
+                match game.play(position) {
+                    Ok(landed) if landed == position => None,
+                    Ok(_) => Some("would fall further under gravity"),
+                    Err(_) => Some("is played on a cell that is not free"),
+                }
+            };
+            if let Some(problem) = problem {
+                return Err(ParseError::new(number, format!("{token} {problem}")));
+            }
+            if glyph.is_some() {
+                record.annotations.insert(
+                    record.moves.len(),
+                    Annotation {
+                        glyph,
+                        comment: String::new(),
+                    },
+                );
+            }
+            record.moves.push((position.row(), position.col(), player));
+        }
+        record.current_player = game.to_move();
+
+        // A result decided away from the board needs the board undecided;
+        // older files wrote such results as a plain win or draw
+        record.result = match (game.outcome(), claimed) {
+            (Outcome::Won(winner, _), None) => Some(GameResult::Win(winner)),
+            (Outcome::Draw, None) => Some(GameResult::Draw),
+            (Outcome::InProgress, None) => None,
+            (outcome, Some((number, value, result))) => match (outcome, result) {
+                (Outcome::Won(winner, _), Some(GameResult::Win(claimed))) if winner == claimed => {
+                    result
+                }
+                (Outcome::Draw, Some(GameResult::Draw)) => result,
+                (Outcome::InProgress, None) => None,
+                (Outcome::InProgress, Some(result)) if result.is_decided_off_board() => {
+                    Some(result)
+                }
+                (Outcome::InProgress, Some(GameResult::Win(winner))) => {
+                    Some(GameResult::Resignation { winner })
+                }
+                (Outcome::InProgress, Some(GameResult::Draw)) => Some(GameResult::AgreedDraw),
+                _ => {
+                    return Err(ParseError::new(
+                        number,
+                        format!("result \"{value}\" does not match the moves"),
+                    ))
+                }
+            },
+        };
+        Ok(notation)
+    }
+}
+
+/// A piece of the move text.
+#[derive(Clone, Copy, Debug)]
+enum Token<'a> {
+    /// A move, with any glyph still attached.
+    Move(&'a str),
+    /// The text of a comment, without its braces.
+    Comment(&'a str),
+}
+
+/// Splits a line of move text into moves and comments, or returns `None`
+/// if a comment is not closed on the line.
+fn split_moves(line: &str) -> Option<Vec<Token<'_>>> {
+    let mut tokens = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find('{') {
+        tokens.extend(rest[..start].split_whitespace().map(Token::Move));
+        let (comment, after) = rest[start + 1..].split_once('}')?;
+        tokens.push(Token::Comment(comment.trim()));
+        rest = after;
+    }
+    tokens.extend(rest.split_whitespace().map(Token::Move));
+    Some(tokens)
+}
+
+/// Reads a `Result` header written by [`result_tag`], or returns `None`
+/// if it is not one.
+fn parse_result(value: &str) -> Option<Option<GameResult>> {
+    Some(Some(match value {
+        "*" => return Some(None),
+        "X" => GameResult::Win(Player::X),
+        "O" => GameResult::Win(Player::O),
+        "Draw" => GameResult::Draw,
+        "X by resignation" => GameResult::Resignation { winner: Player::X },
+        "O by resignation" => GameResult::Resignation { winner: Player::O },
+        "Draw by agreement" => GameResult::AgreedDraw,
+        "X on time" => GameResult::Timeout { winner: Player::X },
+        "O on time" => GameResult::Timeout { winner: Player::O },
+        _ => return None,
+    }))
+}
+
+/// Returns the `Result` header for how a game ended, or for a game still
+/// in progress.
+fn result_tag(result: Option<GameResult>) -> String {
+    match result {
+        None => "*".to_string(),
+        Some(GameResult::Win(winner)) => format!("{winner:?}"),
+        Some(GameResult::Draw) => "Draw".to_string(),
+        Some(GameResult::Resignation { winner }) => format!("{winner:?} by resignation"),
+        Some(GameResult::AgreedDraw) => "Draw by agreement".to_string(),
+        Some(GameResult::Timeout { winner }) => format!("{winner:?} on time"),
+    }
+}
+
+/// Reads the rules out of a `Variant` header, the names joined by `, `
+/// as the app writes them. Blocked and Blind change the setup and the
+/// display rather than the rules.
+fn parse_variant(value: &str) -> Option<RuleSet> {
+    let mut rules = RuleSet::default();
+    for name in value.split(", ") {
+        match name {
+            "Standard" | "Blocked" | "Blind" => {}
+            "Misère" => rules.misere = true,
+            "Gravity" => rules.gravity = true,
+            "Torus" => rules.topology = Topology::Torus,
+            "Expiring" => rules.expiring = true,
+            _ => return None,
+        }
+    }
+    Some(rules)
+}
+
+/// Splits the inside of a header line, after its `[`, into its key and
+/// quoted value.
+fn parse_header(header: &str) -> Option<(&str, &str)> {
+    let (key, rest) = header.strip_suffix(']')?.split_once(' ')?;
+    let value = rest.trim().strip_prefix('"')?.strip_suffix('"')?;
+    Some((key, value))
+}
+
+/// Reads a board written by [`format_board`].
+pub fn parse_board(text: &str) -> Option<Board> {
+    let cells: Vec<_> = text
+        .chars()
+        .map(|symbol| match symbol {
+            '.' => Some(Cell::Empty),
+            '#' => Some(Cell::Blocked),
+            'X' => Some(Cell::Player(Player::X)),
+            'O' => Some(Cell::Player(Player::O)),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    if cells.len() != 9 {
+        return None;
+    }
+    let mut board = [[Cell::Empty; 3]; 3];
+    for (index, cell) in cells.into_iter().enumerate() {
+        board[index / 3][index % 3] = cell;
+    }
+    Some(board)
+}
+
+/// Reads a move such as `X:b2` as its cell and player.
+fn parse_move(token: &str) -> Option<(Position, Player)> {
+    let (player, cell) = token.split_once(':')?;
+    let player = match player {
+        "X" => Player::X,
+        "O" => Player::O,
+        _ => return None,
+    };
+    Some((parse_cell(cell)?, player))
+}
+
+/// Reads a cell name such as `b2`.
+pub fn parse_cell(cell: &str) -> Option<Position> {
+    let &[column, row] = cell.as_bytes() else {
+        return None;
+    };
+    let col = column.checked_sub(b'a')?;
+    let row = row.checked_sub(b'1')?;
+    Position::new(row.into(), col.into())
+}
+
+impl fmt::Display for Notation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let record = &self.record;
+        let result = result_tag(record.result);
+        writeln!(f, "[X \"{}\"]", self.x_player)?;
+        writeln!(f, "[O \"{}\"]", self.o_player)?;
+        writeln!(f, "[Date \"{}\"]", self.date.format("%Y-%m-%d"))?;
+        writeln!(f, "[Variant \"{}\"]", self.variant)?;
+        writeln!(f, "[Result \"{result}\"]")?;
+        if record.initial_board != [[Cell::Empty; 3]; 3] {
+            writeln!(f, "[Setup \"{}\"]", format_board(&record.initial_board))?;
+        }
+        writeln!(f)?;
+
+        let moves: Vec<_> = record
+            .moves
+            .iter()
+            .enumerate()
+            .map(|(index, &(row, col, player))| {
+                let mut text = format!("{player:?}:{}", cell_name(row, col));
+                if let Some(annotation) = record.annotations.get(&index) {
+                    if let Some(glyph) = annotation.glyph {
+                        text += glyph.symbol();
+                    }
+                    if !annotation.comment.is_empty() {
+                        text += &format!(" {{{}}}", annotation.comment.replace(['{', '}'], ""));
+                    }
+                }
+                text
+            })
+            .collect();
+        writeln!(f, "{}", moves.join(" "))
+    }
+}
+
+/// Writes a board as nine characters in reading order, as used by the
+/// setup header and copied positions.
+pub fn format_board(board: &Board) -> String {
+    board
+        .iter()
+        .flatten()
+        .map(|cell| match cell {
+            Cell::Empty => '.',
+            Cell::Blocked => '#',
+            Cell::Player(Player::X) => 'X',
+            Cell::Player(Player::O) => 'O',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// X takes the a column.
+    const X_WINS: &str = "X:a1 O:b1 X:a2 O:b2 X:a3";
+    /// The board fills up without a line.
+    const DRAWN: &str = "X:b2 O:a1 X:c3 O:a3 X:a2 O:c2 X:b1 O:b3 X:c1";
+    /// Two moves each, with the game still open.
+    const OPEN: &str = "X:b2 O:a1 X:c3 O:a3";
+
+    /// Returns notation text with a `Result` header of `result`.
+    fn text(result: &str, moves: &str) -> String {
+        format!("[X \"Alice\"]\n[O \"Bob\"]\n[Result \"{result}\"]\n\n{moves}\n")
+    }
+
+    fn parse(text: &str) -> Result<Notation, ParseError> {
+        text.parse()
+    }
+
+    /// Reads a game played as `moves` that ended in `result`, writes it
+    /// out again, and checks that nothing was lost.
+    fn round_trip(moves: &str, result: Option<GameResult>) {
+        let notation = parse(&text(&result_tag(result), moves)).unwrap();
+        assert_eq!(notation.record.result, result);
+        let written = notation.to_string();
+        let read = parse(&written).unwrap();
+        assert_eq!(read.record.result, result, "{written}");
+        assert_eq!(read.record.moves, notation.record.moves);
+        assert_eq!(read.x_player, "Alice");
+        assert_eq!(read.o_player, "Bob");
+        assert_eq!(read.to_string(), written);
+    }
+
+    #[test]
+    fn every_result_round_trips() {
+        round_trip(X_WINS, Some(GameResult::Win(Player::X)));
+        round_trip(DRAWN, Some(GameResult::Draw));
+        round_trip(OPEN, None);
+        for winner in [Player::X, Player::O] {
+            round_trip(OPEN, Some(GameResult::Resignation { winner }));
+            round_trip(OPEN, Some(GameResult::Timeout { winner }));
+        }
+        round_trip(OPEN, Some(GameResult::AgreedDraw));
+    }
+
+    #[test]
+    fn each_result_has_its_own_tag() {
+        let results = [
+            Some(GameResult::Win(Player::X)),
+            Some(GameResult::Draw),
+            Some(GameResult::Resignation { winner: Player::X }),
+            Some(GameResult::AgreedDraw),
+            Some(GameResult::Timeout { winner: Player::X }),
+            None,
+        ];
+        for (index, &result) in results.iter().enumerate() {
+            assert_eq!(parse_result(&result_tag(result)), Some(result));
+            for &other in &results[index + 1..] {
+                assert_ne!(result_tag(result), result_tag(other));
+            }
+        }
+    }
+
+    #[test]
+    fn annotations_round_trip() {
+        let notation = parse(&text("*", "X:b2!! {takes the centre} O:a1? X:c3")).unwrap();
+        let read = parse(&notation.to_string()).unwrap();
+        assert_eq!(read.record.annotations.len(), 2);
+        assert_eq!(read.record.annotations[&0].glyph, Some(Glyph::Brilliant));
+        assert_eq!(read.record.annotations[&0].comment, "takes the centre");
+        assert_eq!(read.record.annotations[&1].glyph, Some(Glyph::Bad));
+    }
+
+    #[test]
+    fn an_old_plain_result_on_an_open_board_was_decided_off_it() {
+        let notation = parse(&text("O", OPEN)).unwrap();
+        assert_eq!(
+            notation.record.result,
+            Some(GameResult::Resignation { winner: Player::O })
+        );
+        let notation = parse(&text("Draw", OPEN)).unwrap();
+        assert_eq!(notation.record.result, Some(GameResult::AgreedDraw));
+    }
+
+    #[test]
+    fn an_unknown_result_is_refused() {
+        let error = parse(&text("X wins", X_WINS)).unwrap_err();
+        assert_eq!(error.line, 3);
+        assert_eq!(error.message, "unknown result \"X wins\"");
+    }
+
+    #[test]
+    fn a_result_the_moves_contradict_is_refused() {
+        for result in [
+            "O",
+            "Draw",
+            "X by resignation",
+            "X on time",
+            "Draw by agreement",
+        ] {
+            let error = parse(&text(result, X_WINS)).unwrap_err();
+            assert_eq!(error.line, 3, "{result}");
+            assert!(error.message.contains("does not match the moves"));
+        }
+        assert!(parse(&text("O by resignation", DRAWN)).is_err());
+    }
+
+    #[test]
+    fn a_move_out_of_turn_is_refused() {
+        let error = parse(&text("*", "X:b2\nX:a1")).unwrap_err();
+        assert_eq!(error.line, 6);
+        assert_eq!(error.message, "X:a1 is played out of turn");
+    }
+
+    #[test]
+    fn a_move_on_a_taken_cell_is_refused() {
+        let error = parse(&text("*", "X:b2 O:b2")).unwrap_err();
+        assert_eq!(error.message, "O:b2 is played on a cell that is not free");
+    }
+
+    #[test]
+    fn a_move_after_the_game_ended_is_refused() {
+        let error = parse(&text("*", &format!("{X_WINS} O:c3"))).unwrap_err();
+        assert_eq!(error.message, "O:c3 is played after the game ended");
+    }
+
+    #[test]
+    fn a_malformed_move_is_refused() {
+        for moves in ["X:d1", "X:b4", "Z:b2", "b2"] {
+            let error = parse(&text("*", moves)).unwrap_err();
+            assert_eq!(error.line, 5, "{moves}");
+            assert!(error.message.starts_with("bad move"), "{moves}");
+        }
+    }
+
+    #[test]
+    fn a_malformed_header_is_refused() {
+        let error = parse("[X Alice]\n\nX:b2\n").unwrap_err();
+        assert_eq!(error.line, 1);
+        let error = parse("[Variant \"Chess\"]\n").unwrap_err();
+        assert_eq!(error.message, "unknown variant \"Chess\"");
+        let error = parse("[Date \"yesterday\"]\n").unwrap_err();
+        assert_eq!(error.message, "bad date \"yesterday\"");
+    }
+
+    #[test]
+    fn a_misplaced_or_unclosed_comment_is_refused() {
+        let error = parse(&text("*", "{opening} X:b2")).unwrap_err();
+        assert_eq!(error.message, "comment comes before the first move");
+        let error = parse(&text("*", "X:b2 {takes the centre")).unwrap_err();
+        assert_eq!(error.message, "comment is missing its \"}\"");
+    }
+}
@@ -0,0 +1,342 @@
+//! Online play through a relay server over WebSockets.
+//!
+//! One player creates a room on the relay and gets a short code to pass
+//! on; the other joins by typing it in. The creator is seated as X and
+//! starts the games, as the host does on a LAN; the joiner is seated as O.
+//! The relay passes game messages between them and says when the other
+//! player comes or goes.
+//!
+//! A player whose connection drops can rejoin the room as the same side,
+//! and the relay sends back the game so far so both boards agree again.
+//!
+//! Others may watch a room by its code. The relay brings a spectator up to
+//! date with the game so far and then passes on every move, and tells the
+//! players how many are watching.
+//!
+//! The relay also hosts replays: a finished game uploaded to it in `.ttt`
+//! notation is stored under an id, and a link naming the relay and the id
+//! lets anyone fetch the game into their replay view. A player may also
+//! opt in to the relay recording each online game they finish, which it
+//! keeps the same way.
+//!
+//! Every message is a JSON text frame, starting with a hello that gives
+//! the [`PROTOCOL_VERSION`]; a relay speaking another version answers
+//! with its own and closes the connection. As with LAN play, the socket is
+//! non-blocking once the room has been joined and is polled from the UI's
+//! timer.
+
+use std::io;
+use std::net::TcpStream;
+use std::time::Duration;
+
+use rand::Rng;
+use tic_tac_toe::protocol::{ClientMessage, RecordedGame, ServerMessage};
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::WebSocket;
+
+use crate::net::{Event, Message, VersionMismatch, PROTOCOL_VERSION};
+use crate::Player;
+
+/// Relay used until another is set in the settings file.
+pub const DEFAULT_RELAY_URL: &str = "ws://localhost:9001";
+
+/// How long joining waits for each answer from the relay.
+const RELAY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Number of characters in a room code.
+pub const ROOM_CODE_LEN: usize = 6;
+
+/// Characters room codes are made of, leaving out ones easily mistaken
+/// for each other such as 0 and O.
+const ROOM_CODE_CHARS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+/// Text every hosted replay link starts with. The replay's id follows,
+/// then the relay holding it, as in
+/// `tictactoe://replay/K7QM2XPA?relay=ws://example.com:9001`.
+pub const REPLAY_LINK_PREFIX: &str = "tictactoe://replay/";
+
+/// Text every link to a game the relay recorded starts with, followed by
+/// the id and relay as for [`REPLAY_LINK_PREFIX`].
+pub const RECORDING_LINK_PREFIX: &str = "tictactoe://recording/";
+
+/// An open connection to the relay, seated in a room.
+pub struct Relay {
+    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+}
+
+/// How to enter a room.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Entry {
+    /// Create the room and play X.
+    Create,
+    /// Join the room and play O.
+    Join,
+    /// Watch the room as a spectator.
+    Watch,
+    /// Take back the seat of the given side after a dropped connection.
+    Rejoin(Player),
+}
+
+/// Where a player was seated on entering a room.
+pub struct Seat {
+    pub relay: Box<Relay>,
+    /// The side played, or `None` for a spectator.
+    pub side: Option<Player>,
+    pub opponent_present: bool,
+}
+
+/// Returns a new random room code.
+pub fn new_room_code() -> String {
+    let mut rng = rand::thread_rng();
+    (0..ROOM_CODE_LEN)
+        .map(|_| char::from(ROOM_CODE_CHARS[rng.gen_range(0..ROOM_CODE_CHARS.len())]))
+        .collect()
+}
+
+/// Reads a typed room code, ignoring case and spaces, or `None` if it is
+/// not one.
+pub fn parse_room_code(text: &str) -> Option<String> {
+    let code: String = text
+        .chars()
+        .filter(|symbol| !symbol.is_whitespace())
+        .map(|symbol| symbol.to_ascii_uppercase())
+        .collect();
+    let valid =
+        code.len() == ROOM_CODE_LEN && code.bytes().all(|symbol| ROOM_CODE_CHARS.contains(&symbol));
+    valid.then_some(code)
+}
+
+/// Connects to the relay at `url` and enters the room `room` as `entry`
+/// says, asking the relay to record the room's finished games if `record`
+/// is set and the player takes a seat.
+///
+/// This blocks until the relay has seated the player, so it should be run
+/// off the UI thread. A relay that goes quiet for [`RELAY_TIMEOUT`] fails
+/// the join rather than leaving it waiting.
+pub fn join(url: &str, room: &str, entry: Entry, record: bool) -> io::Result<Seat> {
+    let mut relay = Relay::connect(url)?;
+    let room = room.to_string();
+    relay.send_raw(&match entry {
+        Entry::Create => ClientMessage::Create { room },
+        Entry::Join => ClientMessage::Join { room },
+        Entry::Watch => ClientMessage::Watch { room },
+        Entry::Rejoin(side) => ClientMessage::Rejoin { room, side },
+    })?;
+    let (side, opponent_present) = loop {
+        match relay.read()? {
+            Some(ServerMessage::Seated {
+                side,
+                opponent_present,
+            }) => break (Some(side), opponent_present),
+            Some(ServerMessage::Watching { players_present }) => break (None, players_present),
+            Some(ServerMessage::Incompatible { version }) => {
+                return Err(VersionMismatch { theirs: version }.into());
+            }
+            Some(ServerMessage::Rejected { reason }) => return Err(io::Error::other(reason)),
+            Some(_) => {}
+            // A read that times out looks like one that would block
+            None => return Err(io::ErrorKind::TimedOut.into()),
+        }
+    };
+    if record && side.is_some() {
+        relay.send_raw(&ClientMessage::Record)?;
+    }
+    if let MaybeTlsStream::Plain(stream) = relay.socket.get_mut() {
+        stream.set_nonblocking(true)?;
+    }
+    Ok(Seat {
+        relay: Box::new(relay),
+        side,
+        opponent_present,
+    })
+}
+
+/// Uploads a game written in `.ttt` notation to the relay at `url` and
+/// returns the link it can be replayed from.
+///
+/// Like [`join`], this blocks until the relay answers.
+pub fn upload_replay(url: &str, notation: &str) -> io::Result<String> {
+    let notation = notation.to_string();
+    match Relay::request(url, &ClientMessage::UploadReplay { notation })? {
+        ServerMessage::ReplayStored { id } => Ok(link(REPLAY_LINK_PREFIX, url, &id)),
+        _ => Err(io::ErrorKind::InvalidData.into()),
+    }
+}
+
+/// Fetches the game a hosted replay link points to, in `.ttt` notation.
+///
+/// Like [`join`], this blocks until the relay answers.
+pub fn fetch_replay(link: &ReplayLink) -> io::Result<String> {
+    let id = link.id.clone();
+    match Relay::request(&link.relay_url, &ClientMessage::FetchReplay { id })? {
+        ServerMessage::Replay { notation } => Ok(notation),
+        _ => Err(io::ErrorKind::InvalidData.into()),
+    }
+}
+
+/// Fetches the game a recording link points to.
+///
+/// Like [`join`], this blocks until the relay answers.
+pub fn fetch_recording(link: &ReplayLink) -> io::Result<RecordedGame> {
+    let id = link.id.clone();
+    match Relay::request(&link.relay_url, &ClientMessage::FetchRecording { id })? {
+        ServerMessage::Recording(game) => Ok(game),
+        _ => Err(io::ErrorKind::InvalidData.into()),
+    }
+}
+
+/// Returns the link to the game the relay at `url` recorded under `id`.
+pub fn recording_link(url: &str, id: &str) -> String {
+    link(RECORDING_LINK_PREFIX, url, id)
+}
+
+/// A hosted replay or recording link, read by [`parse_replay_link`] or
+/// [`parse_recording_link`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReplayLink {
+    /// The relay holding the replay.
+    pub relay_url: String,
+    /// The replay's id on the relay.
+    pub id: String,
+}
+
+/// Reads a hosted replay link, or `None` if `text` is not one.
+pub fn parse_replay_link(text: &str) -> Option<ReplayLink> {
+    parse_link(REPLAY_LINK_PREFIX, text)
+}
+
+/// Reads a recording link, or `None` if `text` is not one.
+pub fn parse_recording_link(text: &str) -> Option<ReplayLink> {
+    parse_link(RECORDING_LINK_PREFIX, text)
+}
+
+/// Returns the link starting with `prefix` to what the relay at `url`
+/// keeps under `id`.
+fn link(prefix: &str, url: &str, id: &str) -> String {
+    format!("{prefix}{id}?relay={}", url.trim())
+}
+
+/// Reads a link written by [`link`] with `prefix`.
+fn parse_link(prefix: &str, text: &str) -> Option<ReplayLink> {
+    let (id, relay_url) = text.trim().strip_prefix(prefix)?.split_once("?relay=")?;
+    let valid = !id.is_empty() && id.bytes().all(|symbol| symbol.is_ascii_alphanumeric());
+    (valid && !relay_url.is_empty()).then(|| ReplayLink {
+        relay_url: relay_url.to_string(),
+        id: id.to_string(),
+    })
+}
+
+impl Relay {
+    /// Connects to the relay at `url` and says which protocol version the
+    /// game speaks.
+    fn connect(url: &str) -> io::Result<Self> {
+        let (socket, _) = tungstenite::connect(url.trim()).map_err(io::Error::other)?;
+        let mut relay = Relay { socket };
+        if let MaybeTlsStream::Plain(stream) = relay.socket.get_mut() {
+            stream.set_read_timeout(Some(RELAY_TIMEOUT))?;
+        }
+        relay.send_raw(&ClientMessage::Hello {
+            version: PROTOCOL_VERSION,
+        })?;
+        Ok(relay)
+    }
+
+    /// Sends `message` to the relay at `url` on a connection of its own,
+    /// and returns the relay's answer.
+    fn request(url: &str, message: &ClientMessage) -> io::Result<ServerMessage> {
+        let mut relay = Relay::connect(url)?;
+        relay.send_raw(message)?;
+        match relay.read()? {
+            Some(ServerMessage::Incompatible { version }) => {
+                Err(VersionMismatch { theirs: version }.into())
+            }
+            Some(ServerMessage::Rejected { reason }) => Err(io::Error::other(reason)),
+            Some(answer) => Ok(answer),
+            // A read that times out looks like one that would block
+            None => Err(io::ErrorKind::TimedOut.into()),
+        }
+    }
+
+    /// Sends a game message to the other player in the room.
+    pub fn send(&mut self, message: &Message) -> io::Result<()> {
+        self.send_raw(&ClientMessage::Game(message.clone()))
+    }
+
+    /// Returns everything that has arrived since the last call.
+    ///
+    /// Fails once the relay has closed the connection. A message the
+    /// relay refused comes back as [`Event::Rejected`] with the relay's
+    /// reason.
+    pub fn receive(&mut self) -> io::Result<Vec<Event>> {
+        // Finish sending anything a full socket held back last time
+        match self.socket.flush() {
+            Err(tungstenite::Error::Io(err)) if err.kind() == io::ErrorKind::WouldBlock => {}
+            result => result.map_err(io::Error::other)?,
+        }
+        let mut events = Vec::new();
+        while let Some(message) = self.read()? {
+            events.push(match message {
+                ServerMessage::Game(message) => Event::Message(message),
+                ServerMessage::OpponentJoined => Event::OpponentJoined,
+                ServerMessage::OpponentReturned => Event::OpponentReturned,
+                ServerMessage::OpponentLeft => Event::OpponentLeft,
+                ServerMessage::Resync {
+                    first,
+                    board,
+                    rules,
+                    moves,
+                    ending,
+                } => Event::Resync {
+                    first,
+                    board,
+                    rules,
+                    moves,
+                    ending,
+                },
+                ServerMessage::Spectators { count } => Event::Spectators(count),
+                ServerMessage::Recorded { id } => Event::Recorded(id),
+                ServerMessage::Rejected { reason } => Event::Rejected(reason),
+                ServerMessage::Incompatible { version } => {
+                    return Err(VersionMismatch { theirs: version }.into());
+                }
+                ServerMessage::Seated { .. }
+                | ServerMessage::Watching { .. }
+                | ServerMessage::ReplayStored { .. }
+                | ServerMessage::Replay { .. }
+                | ServerMessage::Recording(_) => continue,
+            });
+        }
+        Ok(events)
+    }
+
+    fn send_raw(&mut self, message: &ClientMessage) -> io::Result<()> {
+        let json = serde_json::to_string(message)?;
+        match self.socket.send(tungstenite::Message::text(json)) {
+            // The frame is queued and goes out on a later flush
+            Err(tungstenite::Error::Io(err)) if err.kind() == io::ErrorKind::WouldBlock => Ok(()),
+            result => result.map_err(io::Error::other),
+        }
+    }
+
+    /// Reads the next message from the relay, or `None` when nothing more
+    /// has arrived yet.
+    fn read(&mut self) -> io::Result<Option<ServerMessage>> {
+        loop {
+            match self.socket.read() {
+                Ok(tungstenite::Message::Text(text)) => {
+                    return Ok(Some(serde_json::from_str(&text)?));
+                }
+                Ok(tungstenite::Message::Close(_)) => {
+                    return Err(io::ErrorKind::UnexpectedEof.into());
+                }
+                // Pings are answered by the library, and nothing else is
+                // part of the protocol
+                Ok(_) => {}
+                Err(tungstenite::Error::Io(err)) if err.kind() == io::ErrorKind::WouldBlock => {
+                    return Ok(None);
+                }
+                Err(err) => return Err(io::Error::other(err)),
+            }
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Pentago: placing a mark, then twisting a quadrant.
+//!
+//! The 6x6 board is made of four 3x3 quadrants. Each turn a player places
+//! a mark and then rotates any quadrant a quarter turn either way. Five in
+//! a row wins, checked once the rotation is done; if the rotation makes
+//! lines for both players, the game is drawn.
+
+use crate::rules::Topology;
+use crate::{find_run, Cell, Player};
+
+/// Cells along each edge of the board.
+pub const SIZE: usize = 6;
+
+/// Marks in a row needed to win.
+pub const WIN_LENGTH: usize = 5;
+
+/// The part of a turn still to be played.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Phase {
+    /// The player to move places a mark.
+    Place,
+    /// The player to move rotates a quadrant.
+    Rotate,
+}
+
+/// The state of a Pentago game.
+#[derive(Clone, Debug)]
+pub struct Pentago {
+    /// The board, indexed by row then column.
+    pub cells: [[Cell; SIZE]; SIZE],
+    /// The player whose turn it is.
+    pub to_move: Player,
+    /// Whether the player to move is placing or rotating.
+    pub phase: Phase,
+    /// Whether the game has ended.
+    pub finished: bool,
+    /// The winner of the game, if any.
+    pub winner: Option<Player>,
+}
+
+impl Pentago {
+    /// Creates an empty board with `first` to move.
+    pub fn new(first: Player) -> Self {
+        Self {
+            cells: [[Cell::Empty; SIZE]; SIZE],
+            to_move: first,
+            phase: Phase::Place,
+            finished: false,
+            winner: None,
+        }
+    }
+
+    /// Places the current player's mark, returning whether the move was
+    /// legal. A cell off the board is refused.
+    pub fn place(&mut self, row: usize, col: usize) -> bool {
+        if self.finished || self.phase != Phase::Place || row >= SIZE || col >= SIZE {
+            return false;
+        }
+        if self.cells[row][col] != Cell::Empty {
+            return false;
+        }
+        self.cells[row][col] = Cell::Player(self.to_move);
+        self.phase = Phase::Rotate;
+        true
+    }
+
+    /// Rotates `quadrant` (0-3 in reading order) a quarter turn and ends
+    /// the turn, returning whether the rotation was legal.
+    pub fn rotate(&mut self, quadrant: usize, clockwise: bool) -> bool {
+        if self.finished || self.phase != Phase::Rotate || quadrant >= 4 {
+            return false;
+        }
+
+        let (top, left) = ((quadrant / 2) * 3, (quadrant % 2) * 3);
+        let before = self.cells;
+        for row in 0..3 {
+            for col in 0..3 {
+                let (from_row, from_col) = if clockwise {
+                    (2 - col, row)
+                } else {
+                    (col, 2 - row)
+                };
+                self.cells[top + row][left + col] = before[top + from_row][left + from_col];
+            }
+        }
+
+        let x_wins = find_run(&self.cells, Player::X, WIN_LENGTH, Topology::Flat).is_some();
+        let o_wins = find_run(&self.cells, Player::O, WIN_LENGTH, Topology::Flat).is_some();
+        let full = self.cells.iter().flatten().all(|&cell| cell != Cell::Empty);
+        match (x_wins, o_wins) {
+            (true, false) => self.winner = Some(Player::X),
+            (false, true) => self.winner = Some(Player::O),
+            _ => {}
+        }
+        self.finished = x_wins || o_wins || full;
+        if !self.finished {
+            self.to_move = self.to_move.other();
+            self.phase = Phase::Place;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const X: Cell = Cell::Player(Player::X);
+    const O: Cell = Cell::Player(Player::O);
+
+    /// Returns the cells of X's marks, in reading order.
+    fn marks(game: &Pentago) -> Vec<(usize, usize)> {
+        (0..SIZE)
+            .flat_map(|row| (0..SIZE).map(move |col| (row, col)))
+            .filter(|&(row, col)| game.cells[row][col] == X)
+            .collect()
+    }
+
+    /// Returns a game with X to rotate and X's marks on `cells`.
+    fn rotating(cells: &[(usize, usize)]) -> Pentago {
+        let mut game = Pentago::new(Player::X);
+        for &(row, col) in cells {
+            game.cells[row][col] = X;
+        }
+        game.phase = Phase::Rotate;
+        game
+    }
+
+    #[test]
+    fn clockwise_turns_the_corner_to_the_right() {
+        let mut game = Pentago::new(Player::X);
+        assert!(game.place(0, 0));
+        assert!(game.rotate(0, true));
+        assert_eq!(marks(&game), vec![(0, 2)]);
+    }
+
+    #[test]
+    fn anticlockwise_turns_the_corner_down() {
+        let mut game = Pentago::new(Player::X);
+        assert!(game.place(0, 0));
+        assert!(game.rotate(0, false));
+        assert_eq!(marks(&game), vec![(2, 0)]);
+    }
+
+    #[test]
+    fn only_the_chosen_quadrant_turns() {
+        let mut game = rotating(&[(0, 0), (3, 3), (4, 4)]);
+        assert!(game.rotate(3, true));
+        assert_eq!(marks(&game), vec![(0, 0), (3, 5), (4, 4)]);
+    }
+
+    #[test]
+    fn four_quarter_turns_restore_the_quadrant() {
+        let cells = [(3, 0), (3, 1), (5, 2)];
+        let mut game = rotating(&cells);
+        for _ in 0..4 {
+            game.phase = Phase::Rotate;
+            assert!(game.rotate(2, true));
+        }
+        assert_eq!(marks(&game), cells);
+
+        game.phase = Phase::Rotate;
+        assert!(game.rotate(2, true));
+        game.phase = Phase::Rotate;
+        assert!(game.rotate(2, false));
+        assert_eq!(marks(&game), cells);
+    }
+
+    #[test]
+    fn a_turn_is_a_placement_then_a_rotation() {
+        let mut game = Pentago::new(Player::X);
+        assert!(!game.rotate(0, true));
+        assert!(game.place(1, 1));
+        assert!(!game.place(2, 2));
+        assert!(game.rotate(1, false));
+        assert_eq!(game.to_move, Player::O);
+        assert_eq!(game.phase, Phase::Place);
+        assert!(!game.place(1, 1));
+    }
+
+    #[test]
+    fn moves_off_the_board_are_refused() {
+        let mut game = Pentago::new(Player::X);
+        assert!(!game.place(SIZE, 0));
+        assert!(!game.place(0, SIZE));
+        assert!(game.place(0, 0));
+        assert!(!game.rotate(4, true));
+        assert_eq!(game.phase, Phase::Rotate);
+    }
+
+    #[test]
+    fn a_rotation_completing_a_line_wins() {
+        let mut game = rotating(&[(0, 0), (1, 0), (2, 0), (0, 3), (0, 4)]);
+        assert!(game.rotate(0, true));
+        assert!(game.finished);
+        assert_eq!(game.winner, Some(Player::X));
+        assert!(!game.place(5, 5));
+    }
+
+    #[test]
+    fn a_rotation_completing_both_lines_draws() {
+        let mut game = rotating(&[(0, 0), (1, 0), (2, 0), (0, 3), (0, 4)]);
+        for col in 0..WIN_LENGTH {
+            game.cells[5][col] = O;
+        }
+        assert!(game.rotate(0, true));
+        assert!(game.finished);
+        assert_eq!(game.winner, None);
+    }
+}
@@ -0,0 +1,178 @@
+//! Player profiles with Elo ratings and lifetime statistics.
+//!
+//! Profiles are kept as JSON in the data directory so ratings and records
+//! carry over between sessions. After every rated game both sides' ratings
+//! move by the standard Elo formula. The computer plays at a fixed rating
+//! for each difficulty, which never changes.
+
+use std::fs;
+use std::path::PathBuf;
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+
+use crate::ai::Difficulty;
+
+/// Rating a new profile starts with.
+pub const INITIAL_RATING: f64 = 1200.0;
+
+/// Most rating points a single game can move.
+const K_FACTOR: f64 = 32.0;
+
+/// A named player with their rating and record.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub rating: f64,
+    /// Profiles saved before statistics were tracked start from zero.
+    #[serde(default)]
+    pub stats: Stats,
+    /// Best stars earned on each campaign level, in level order.
+    #[serde(default)]
+    pub campaign: Vec<u8>,
+}
+
+/// A profile's lifetime record.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Stats {
+    pub games: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    /// How often each cell was the profile's first mark of a game.
+    pub openings: [[u32; 3]; 3],
+    /// Games won in a row up to the latest.
+    pub win_streak: u32,
+    /// Longest run of wins ever.
+    pub best_streak: u32,
+}
+
+impl Stats {
+    /// Records a finished game, where `won` is `None` for a draw and
+    /// `opening` is the cell of the profile's first mark, if they made one.
+    pub fn record(&mut self, won: Option<bool>, opening: Option<(usize, usize)>) {
+        self.games += 1;
+        match won {
+            Some(true) => {
+                self.wins += 1;
+                self.win_streak += 1;
+                self.best_streak = self.best_streak.max(self.win_streak);
+            }
+            Some(false) => {
+                self.losses += 1;
+                self.win_streak = 0;
+            }
+            None => {
+                self.draws += 1;
+                self.win_streak = 0;
+            }
+        }
+        if let Some((row, col)) = opening {
+            self.openings[row][col] += 1;
+        }
+    }
+
+    /// Returns the cell most often played as the profile's first mark,
+    /// preferring the first in reading order on a tie.
+    pub fn favorite_opening(&self) -> Option<(usize, usize)> {
+        let mut favorite = None;
+        let mut most = 0;
+        for (row, counts) in self.openings.iter().enumerate() {
+            for (col, &count) in counts.iter().enumerate() {
+                if count > most {
+                    most = count;
+                    favorite = Some((row, col));
+                }
+            }
+        }
+        favorite
+    }
+}
+
+/// Every saved profile.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Profiles {
+    profiles: Vec<Profile>,
+}
+
+impl Profiles {
+    /// Loads the saved profiles, starting empty if there are none.
+    pub fn load() -> Self {
+        profiles_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Writes the profiles to disk, ignoring failures.
+    pub fn save(&self) {
+        let Some(path) = profiles_path() else {
+            return;
+        };
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        if let Ok(json) = serde_json::to_string_pretty(self) {
+            let _ = fs::write(path, json);
+        }
+    }
+
+    /// Returns the profiles in creation order.
+    pub fn all(&self) -> &[Profile] {
+        &self.profiles
+    }
+
+    /// Returns the profile called `name`.
+    pub fn get(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    /// Returns the profile called `name` for updating.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Profile> {
+        self.profiles
+            .iter_mut()
+            .find(|profile| profile.name == name)
+    }
+
+    /// Adds a profile at the initial rating, ignoring blanks and names
+    /// already taken.
+    pub fn add(&mut self, name: &str) {
+        let name = name.trim();
+        if name.is_empty() || self.get(name).is_some() {
+            return;
+        }
+        self.profiles.push(Profile {
+            name: name.to_string(),
+            rating: INITIAL_RATING,
+            stats: Stats::default(),
+            campaign: Vec::new(),
+        });
+    }
+
+    /// Deletes the profile called `name`.
+    pub fn remove(&mut self, name: &str) {
+        self.profiles.retain(|profile| profile.name != name);
+    }
+}
+
+/// Returns the fixed rating the computer plays at on `difficulty`.
+pub fn computer_rating(difficulty: Difficulty) -> f64 {
+    match difficulty {
+        Difficulty::Easy => 800.0,
+        Difficulty::Medium => 1200.0,
+        Difficulty::Hard => 1600.0,
+    }
+}
+
+/// Returns how much a rating moves after a game against an opponent rated
+/// `opponent`, where `score` is 1 for a win, 0.5 for a draw, and 0 for a
+/// loss.
+pub fn rating_change(rating: f64, opponent: f64, score: f64) -> f64 {
+    let expected = 1.0 / (1.0 + 10f64.powf((opponent - rating) / 400.0));
+    K_FACTOR * (score - expected)
+}
+
+/// Location of the profiles file in the platform data directory.
+pub fn profiles_path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "tic-tac-toe").map(|dirs| dirs.data_dir().join("profiles.json"))
+}
@@ -0,0 +1,263 @@
+//! The messages players and the relay server exchange.
+//!
+//! A [`Message`] passes between the two players, directly over a LAN or
+//! through the relay. [`ClientMessage`] and [`ServerMessage`] wrap it on
+//! the way to and from the relay. The game and the relay both use these
+//! types, so they always speak the same JSON.
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{Board, Player};
+use crate::rules::RuleSet;
+
+/// Version of [`Message`] and the relay messages, raised whenever a change
+/// would be misread by an older copy of the game.
+pub const PROTOCOL_VERSION: u32 = 7;
+
+/// A message between the two players.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Message {
+    /// The first message on a connection, saying which protocol version
+    /// the sender speaks.
+    Hello { version: u32 },
+    /// The host starts a game from `board` with `first` to move.
+    Start {
+        first: Player,
+        board: Board,
+        rules: RuleSet,
+    },
+    /// The sender placed a mark.
+    Move { row: usize, col: usize },
+    /// The player who joined asks the host to start a new game.
+    NewGame,
+    /// A line of chat from `from`.
+    Chat { from: Player, text: String },
+    /// The sender asks to take back the last move, made when the board
+    /// held `moves` moves.
+    TakebackRequest { moves: usize },
+    /// The answer to a takeback request. When accepted, both sides take
+    /// back the last move if the board still holds `moves` moves.
+    TakebackAnswer { moves: usize, accepted: bool },
+    /// `player` resigned the game.
+    Resign { player: Player },
+    /// The sender offers a draw.
+    DrawOffer,
+    /// The answer to a draw offer, ending the game when accepted.
+    DrawAnswer { accepted: bool },
+    /// The sender wants a rematch, or agrees to the one the other side
+    /// asked for.
+    Rematch,
+}
+
+/// What a player sends the relay.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ClientMessage {
+    /// Say which protocol version the player speaks. This must come
+    /// first.
+    Hello { version: u32 },
+    /// Create a room under this code.
+    Create { room: String },
+    /// Join the room with this code.
+    Join { room: String },
+    /// Watch the room with this code without playing.
+    Watch { room: String },
+    /// Take back the seat of `side` after the connection dropped.
+    Rejoin { room: String, side: Player },
+    /// Pass a game message on to the other player in the room.
+    Game(Message),
+    /// Record each game in the room that finishes from now on, and send
+    /// back the id it is kept under. Only seated players may ask.
+    Record,
+    /// Store a game, written in the game's `.ttt` notation, for anyone
+    /// given its id to replay. This comes instead of entering a room.
+    UploadReplay { notation: String },
+    /// Send back the game stored under this id. This comes instead of
+    /// entering a room.
+    FetchReplay { id: String },
+    /// Send back the game recorded under this id. This comes instead of
+    /// entering a room.
+    FetchRecording { id: String },
+}
+
+/// What the relay sends a player.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ServerMessage {
+    /// The player has joined the room and plays `side`.
+    Seated {
+        side: Player,
+        opponent_present: bool,
+    },
+    /// The other player has joined the room.
+    OpponentJoined,
+    /// The other player has taken their seat back after their connection
+    /// dropped, and the game carries on.
+    OpponentReturned,
+    /// The other player has left the room.
+    OpponentLeft,
+    /// The game so far, sent to a player who rejoins, with the resignation
+    /// or accepted draw that ended it, if one did.
+    Resync {
+        first: Player,
+        board: Board,
+        rules: RuleSet,
+        moves: Vec<(usize, usize, Player)>,
+        ending: Option<Message>,
+    },
+    /// The spectator is watching the room. For spectators the other
+    /// player's comings and goings mean the room filling or losing a
+    /// player.
+    Watching { players_present: bool },
+    /// This many spectators are now watching the room.
+    Spectators { count: usize },
+    /// A game message from the other player.
+    Game(Message),
+    /// The relay speaks another protocol version, given here, and has
+    /// closed the connection.
+    Incompatible { version: u32 },
+    /// The last message was refused, such as a move out of turn or an
+    /// unknown room code.
+    Rejected { reason: String },
+    /// The uploaded game was stored under this id.
+    ReplayStored { id: String },
+    /// The game stored under the id asked for, in `.ttt` notation.
+    Replay { notation: String },
+    /// The game that just finished in the room was recorded under this id.
+    Recorded { id: String },
+    /// The game recorded under the id asked for.
+    Recording(RecordedGame),
+}
+
+/// A finished game as the relay records it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedGame {
+    pub first: Player,
+    pub board: Board,
+    pub rules: RuleSet,
+    pub moves: Vec<(usize, usize, Player)>,
+    /// The resignation or accepted draw that ended the game away from the
+    /// board, if one did.
+    pub ending: Option<Message>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::Cell;
+    use crate::rules::Topology;
+
+    fn decode<T: for<'de> Deserialize<'de>>(json: &str) -> serde_json::Result<T> {
+        serde_json::from_str(json)
+    }
+
+    /// Encodes `value` and decodes it again.
+    fn round_trip<T: Serialize + for<'de> Deserialize<'de>>(value: &T) -> T {
+        decode(&serde_json::to_string(value).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn decodes_each_message_shape() {
+        assert!(matches!(
+            decode(r#"{"Hello":{"version":7}}"#),
+            Ok(Message::Hello { version: 7 })
+        ));
+        assert!(matches!(
+            decode(r#"{"Move":{"row":1,"col":2}}"#),
+            Ok(Message::Move { row: 1, col: 2 })
+        ));
+        assert!(matches!(decode(r#""NewGame""#), Ok(Message::NewGame)));
+        assert!(matches!(decode(r#""DrawOffer""#), Ok(Message::DrawOffer)));
+        assert!(matches!(
+            decode(r#"{"Resign":{"player":"O"}}"#),
+            Ok(Message::Resign { player: Player::O })
+        ));
+        assert!(matches!(
+            decode(r#"{"TakebackAnswer":{"moves":4,"accepted":true}}"#),
+            Ok(Message::TakebackAnswer {
+                moves: 4,
+                accepted: true
+            })
+        ));
+    }
+
+    #[test]
+    fn a_start_keeps_its_board_and_rules() {
+        let mut board = [[Cell::Empty; 3]; 3];
+        board[1][1] = Cell::Blocked;
+        board[0][2] = Cell::Player(Player::X);
+        let rules = RuleSet {
+            gravity: true,
+            topology: Topology::Torus,
+            ..RuleSet::default()
+        };
+        let start = Message::Start {
+            first: Player::O,
+            board,
+            rules,
+        };
+        let Message::Start {
+            first,
+            board: decoded_board,
+            rules: decoded_rules,
+        } = round_trip(&start)
+        else {
+            panic!("expected a start");
+        };
+        assert_eq!(first, Player::O);
+        assert_eq!(decoded_board, board);
+        assert_eq!(decoded_rules, rules);
+    }
+
+    #[test]
+    fn relay_messages_wrap_game_messages() {
+        let client = decode(r#"{"Game":{"Chat":{"from":"X","text":"good luck"}}}"#);
+        let Ok(ClientMessage::Game(Message::Chat { from, text })) = client else {
+            panic!("expected a chat");
+        };
+        assert_eq!(from, Player::X);
+        assert_eq!(text, "good luck");
+
+        assert!(matches!(
+            round_trip(&ServerMessage::Game(Message::Move { row: 2, col: 0 })),
+            ServerMessage::Game(Message::Move { row: 2, col: 0 })
+        ));
+        assert!(matches!(
+            decode(r#"{"Join":{"room":"ABCD"}}"#),
+            Ok(ClientMessage::Join { room }) if room == "ABCD"
+        ));
+    }
+
+    #[test]
+    fn a_resync_keeps_the_moves_and_ending() {
+        let resync = ServerMessage::Resync {
+            first: Player::X,
+            board: [[Cell::Empty; 3]; 3],
+            rules: RuleSet::default(),
+            moves: vec![(1, 1, Player::X), (0, 0, Player::O)],
+            ending: Some(Message::Resign { player: Player::O }),
+        };
+        let ServerMessage::Resync { moves, ending, .. } = round_trip(&resync) else {
+            panic!("expected a resync");
+        };
+        assert_eq!(moves, vec![(1, 1, Player::X), (0, 0, Player::O)]);
+        assert!(matches!(
+            ending,
+            Some(Message::Resign { player: Player::O })
+        ));
+    }
+
+    #[test]
+    fn malformed_messages_are_refused() {
+        for json in [
+            r#""Castle""#,
+            r#"{"Move":{"row":1}}"#,
+            r#"{"Move":{"row":-1,"col":0}}"#,
+            r#"{"Resign":{"player":"Z"}}"#,
+            r#"{"Hello":{"version":"7"}}"#,
+            r#"{"Move":{"row":1,"col":2}"#,
+        ] {
+            assert!(decode::<Message>(json).is_err(), "{json}");
+        }
+        assert!(decode::<ClientMessage>(r#"{"Create":{}}"#).is_err());
+        assert!(decode::<ServerMessage>(r#"{"Spectators":{"count":"many"}}"#).is_err());
+    }
+}
@@ -0,0 +1,229 @@
+//! Puzzle Rush: a timed stream of generated tactics puzzles.
+//!
+//! Each puzzle is a position reached by random play where the side to move
+//! either has a winning move or must find the only square that blocks the
+//! opponent's threat. Solving scores a point; a wrong answer is a strike,
+//! and the run ends after three strikes or when time runs out.
+
+use std::fs;
+use std::path::PathBuf;
+
+use directories::ProjectDirs;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::{has_line, Board, Cell, Player};
+
+/// Length of a Puzzle Rush run.
+pub const RUSH_SECONDS: u32 = 90;
+
+/// Wrong answers allowed before the run ends.
+pub const MAX_STRIKES: u32 = 3;
+
+/// Number of scores kept in the high-score table.
+const HIGH_SCORE_COUNT: usize = 5;
+
+/// What the player must find in a puzzle.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PuzzleKind {
+    /// Complete a line to win immediately.
+    Win,
+    /// Block the opponent's only winning threat.
+    Block,
+}
+
+/// A position with the moves that solve it.
+#[derive(Clone, Debug)]
+pub struct Puzzle {
+    pub board: Board,
+    pub to_move: Player,
+    pub kind: PuzzleKind,
+    solutions: Vec<(usize, usize)>,
+}
+
+impl Puzzle {
+    /// Generates a random puzzle.
+    ///
+    /// Positions are built from random play and kept when the side to move
+    /// can win at once, or when the opponent has exactly one winning threat
+    /// to block.
+    pub fn generate(rng: &mut impl Rng) -> Self {
+        loop {
+            let mut board = [[Cell::Empty; 3]; 3];
+            let mut to_move = Player::X;
+            let mut decided = false;
+            for _ in 0..rng.gen_range(3..=6) {
+                let Some(&(row, col)) = empty_cells(&board).choose(rng) else {
+                    break;
+                };
+                board[row][col] = Cell::Player(to_move);
+                if has_line(&board, to_move) {
+                    decided = true;
+                    break;
+                }
+                to_move = to_move.other();
+            }
+            if decided {
+                continue;
+            }
+
+            let wins = finishing_moves(&board, to_move);
+            if !wins.is_empty() {
+                return Self {
+                    board,
+                    to_move,
+                    kind: PuzzleKind::Win,
+                    solutions: wins,
+                };
+            }
+            let threats = finishing_moves(&board, to_move.other());
+            if threats.len() == 1 {
+                return Self {
+                    board,
+                    to_move,
+                    kind: PuzzleKind::Block,
+                    solutions: threats,
+                };
+            }
+        }
+    }
+
+    /// Whether playing at the given cell solves the puzzle.
+    pub fn is_solution(&self, row: usize, col: usize) -> bool {
+        self.solutions.contains(&(row, col))
+    }
+}
+
+/// Returns the coordinates of every empty cell.
+fn empty_cells(board: &Board) -> Vec<(usize, usize)> {
+    (0..3)
+        .flat_map(|row| (0..3).map(move |col| (row, col)))
+        .filter(|&(row, col)| board[row][col] == Cell::Empty)
+        .collect()
+}
+
+/// Returns the empty cells where `player` would complete a line.
+fn finishing_moves(board: &Board, player: Player) -> Vec<(usize, usize)> {
+    empty_cells(board)
+        .into_iter()
+        .filter(|&(row, col)| {
+            let mut board = *board;
+            board[row][col] = Cell::Player(player);
+            has_line(&board, player)
+        })
+        .collect()
+}
+
+/// A Puzzle Rush run in progress or just finished.
+#[derive(Debug)]
+pub struct PuzzleRush {
+    rng: StdRng,
+    /// The puzzle currently being solved.
+    pub puzzle: Puzzle,
+    /// Puzzles solved so far.
+    pub score: u32,
+    /// Wrong answers so far.
+    pub strikes: u32,
+    /// Time remaining in the run.
+    pub seconds_left: u32,
+    /// Best scores from previous runs, highest first.
+    pub high_scores: HighScores,
+}
+
+impl PuzzleRush {
+    /// Starts a new run with a fresh puzzle and a full clock.
+    pub fn start() -> Self {
+        let mut rng = StdRng::from_entropy();
+        let puzzle = Puzzle::generate(&mut rng);
+        Self {
+            rng,
+            puzzle,
+            score: 0,
+            strikes: 0,
+            seconds_left: RUSH_SECONDS,
+            high_scores: HighScores::load(),
+        }
+    }
+
+    /// Whether the run has ended by strikes or time.
+    pub fn is_finished(&self) -> bool {
+        self.strikes >= MAX_STRIKES || self.seconds_left == 0
+    }
+
+    /// Answers the current puzzle and moves on to the next one.
+    pub fn answer(&mut self, row: usize, col: usize) {
+        if self.is_finished() {
+            return;
+        }
+        if self.puzzle.is_solution(row, col) {
+            self.score += 1;
+        } else {
+            self.strikes += 1;
+        }
+        if self.is_finished() {
+            self.high_scores.record(self.score);
+        } else {
+            self.puzzle = Puzzle::generate(&mut self.rng);
+        }
+    }
+
+    /// Counts down one second of the run.
+    pub fn tick(&mut self) {
+        if self.is_finished() {
+            return;
+        }
+        self.seconds_left -= 1;
+        if self.is_finished() {
+            self.high_scores.record(self.score);
+        }
+    }
+}
+
+/// The best Puzzle Rush scores, persisted between sessions.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    scores: Vec<u32>,
+}
+
+impl HighScores {
+    /// Loads the saved scores, falling back to an empty table.
+    pub fn load() -> Self {
+        scores_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Adds a score, keeping only the best few, and saves the table.
+    pub fn record(&mut self, score: u32) {
+        self.scores.push(score);
+        self.scores.sort_unstable_by(|a, b| b.cmp(a));
+        self.scores.truncate(HIGH_SCORE_COUNT);
+        self.save();
+    }
+
+    /// Returns the scores, highest first.
+    pub fn scores(&self) -> &[u32] {
+        &self.scores
+    }
+
+    /// Writes the table to disk, ignoring failures.
+    fn save(&self) {
+        let Some(path) = scores_path() else {
+            return;
+        };
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        if let Ok(json) = serde_json::to_string_pretty(self) {
+            let _ = fs::write(path, json);
+        }
+    }
+}
+
+/// Location of the high-score file in the platform data directory.
+pub fn scores_path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "tic-tac-toe").map(|dirs| dirs.data_dir().join("puzzle_rush.json"))
+}
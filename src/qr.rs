@@ -0,0 +1,67 @@
+//! QR codes drawn as an element.
+//!
+//! Each row of the code becomes a strip of alternating dark and light
+//! runs, which keeps the element count far below one per module.
+
+use gpui::{div, prelude::*, px, rgb, App, Window};
+use qrcode::{Color, QrCode};
+
+/// Side of one module of the code, in logical pixels.
+const MODULE_SIZE: f32 = 4.0;
+
+/// Width of the light border scanners need around the code, in modules.
+const QUIET_ZONE: f32 = 4.0;
+
+/// A QR code encoding some text.
+#[derive(IntoElement)]
+pub struct Qr {
+    /// Whether each module is dark, row by row.
+    rows: Vec<Vec<bool>>,
+}
+
+impl Qr {
+    /// Encodes `text`, or returns `None` if it is too long for a QR code.
+    pub fn new(text: &str) -> Option<Self> {
+        let code = QrCode::new(text).ok()?;
+        let width = code.width();
+        let modules = code.to_colors();
+        let rows = modules
+            .chunks(width)
+            .map(|row| row.iter().map(|&color| color == Color::Dark).collect())
+            .collect();
+        Some(Self { rows })
+    }
+}
+
+impl RenderOnce for Qr {
+    fn render(self, _window: &mut Window, _cx: &mut App) -> impl IntoElement {
+        div()
+            .p(px(QUIET_ZONE * MODULE_SIZE))
+            .bg(rgb(0xffffff))
+            .flex()
+            .flex_col()
+            .children(self.rows.iter().map(|row| {
+                div()
+                    .flex()
+                    .h(px(MODULE_SIZE))
+                    .children(runs(row).into_iter().map(|(dark, len)| {
+                        div()
+                            .w(px(len as f32 * MODULE_SIZE))
+                            .h_full()
+                            .when(dark, |el| el.bg(rgb(0x000000)))
+                    }))
+            }))
+    }
+}
+
+/// Splits a row into runs of equal modules, as (dark, length) pairs.
+fn runs(row: &[bool]) -> Vec<(bool, usize)> {
+    let mut runs: Vec<(bool, usize)> = Vec::new();
+    for &dark in row {
+        match runs.last_mut() {
+            Some((last, len)) if *last == dark => *len += 1,
+            _ => runs.push((dark, 1)),
+        }
+    }
+    runs
+}
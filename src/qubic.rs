@@ -0,0 +1,175 @@
+//! Qubic: tic-tac-toe on a 4x4x4 cube.
+//!
+//! Four marks in a straight line anywhere in the cube win: along a row,
+//! column, or pillar through the layers, along a diagonal of any flat
+//! slice, or along one of the four space diagonals corner to corner.
+
+use tic_tac_toe::engine::MoveError;
+use tic_tac_toe::game::{Game, Status};
+
+use crate::{Cell, Player};
+
+/// Cells along each edge of the cube.
+pub const SIZE: usize = 4;
+
+/// Coordinates of a cell as (layer, row, column).
+pub type Point = (usize, usize, usize);
+
+/// How many moves ahead the computer looks before scoring a position as
+/// undecided.
+pub const SEARCH_DEPTH: u32 = 2;
+
+/// One direction for each of the thirteen lines through a cell, as
+/// (layer, row, column) steps. The opposite directions cover the same
+/// lines and are left out.
+const DIRECTIONS: [(isize, isize, isize); 13] = [
+    (0, 0, 1),
+    (0, 1, 0),
+    (1, 0, 0),
+    (0, 1, 1),
+    (0, 1, -1),
+    (1, 0, 1),
+    (1, 0, -1),
+    (1, 1, 0),
+    (1, -1, 0),
+    (1, 1, 1),
+    (1, 1, -1),
+    (1, -1, 1),
+    (1, -1, -1),
+];
+
+/// The state of a Qubic game.
+#[derive(Clone, Debug)]
+pub struct QubicBoard {
+    /// The cube, indexed by layer, then row, then column.
+    pub cells: [[[Cell; SIZE]; SIZE]; SIZE],
+    /// The player whose turn it is.
+    pub to_move: Player,
+    /// Whether the game has ended.
+    pub finished: bool,
+    /// The winner of the game, if any.
+    pub winner: Option<Player>,
+    /// The cells of the winning line, once there is one.
+    pub winning_line: Vec<Point>,
+}
+
+impl QubicBoard {
+    /// Creates an empty cube with `first` to move.
+    pub fn new(first: Player) -> Self {
+        Self {
+            cells: [[[Cell::Empty; SIZE]; SIZE]; SIZE],
+            to_move: first,
+            finished: false,
+            winner: None,
+            winning_line: Vec::new(),
+        }
+    }
+
+    /// Plays the current player's mark, returning whether the move was
+    /// legal.
+    pub fn play(&mut self, (layer, row, col): Point) -> bool {
+        if self.finished || layer >= SIZE || row >= SIZE || col >= SIZE {
+            return false;
+        }
+        if self.cells[layer][row][col] != Cell::Empty {
+            return false;
+        }
+
+        let player = self.to_move;
+        self.cells[layer][row][col] = Cell::Player(player);
+        if let Some(line) = self.find_line(player) {
+            self.finished = true;
+            self.winner = Some(player);
+            self.winning_line = line;
+        } else if self
+            .cells
+            .iter()
+            .flatten()
+            .flatten()
+            .all(|&cell| cell != Cell::Empty)
+        {
+            self.finished = true;
+        } else {
+            self.to_move = player.other();
+        }
+        true
+    }
+
+    /// Returns the cells of a line of four held by `player`, if any.
+    fn find_line(&self, player: Player) -> Option<Vec<Point>> {
+        let size = SIZE as isize;
+        let at = |layer: isize, row: isize, col: isize| {
+            [layer, row, col]
+                .iter()
+                .all(|index| (0..size).contains(index))
+                .then(|| (layer as usize, row as usize, col as usize))
+        };
+
+        for layer in 0..size {
+            for row in 0..size {
+                for col in 0..size {
+                    for (d_layer, d_row, d_col) in DIRECTIONS {
+                        let line: Option<Vec<Point>> = (0..size)
+                            .map(|step| {
+                                at(
+                                    layer + step * d_layer,
+                                    row + step * d_row,
+                                    col + step * d_col,
+                                )
+                            })
+                            .collect();
+                        if let Some(line) = line.filter(|line| {
+                            line.iter()
+                                .all(|&(l, r, c)| self.cells[l][r][c] == Cell::Player(player))
+                        }) {
+                            return Some(line);
+                        }
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Game for QubicBoard {
+    type Move = Point;
+
+    fn current_player(&self) -> Player {
+        self.to_move
+    }
+
+    fn legal_moves(&self) -> Vec<Point> {
+        if self.finished {
+            return Vec::new();
+        }
+        (0..SIZE)
+            .flat_map(|layer| (0..SIZE).map(move |row| (layer, row)))
+            .flat_map(|(layer, row)| (0..SIZE).map(move |col| (layer, row, col)))
+            .filter(|&(layer, row, col)| self.cells[layer][row][col] == Cell::Empty)
+            .collect()
+    }
+
+    fn apply_move(&mut self, point: Point) -> Result<(), MoveError> {
+        let (layer, row, col) = point;
+        if self.finished {
+            return Err(MoveError::GameOver);
+        }
+        if layer >= SIZE || row >= SIZE || col >= SIZE {
+            return Err(MoveError::OutOfBounds);
+        }
+        if self.play(point) {
+            Ok(())
+        } else {
+            Err(MoveError::CellOccupied)
+        }
+    }
+
+    fn status(&self) -> Status {
+        match (self.finished, self.winner) {
+            (false, _) => Status::InProgress,
+            (true, Some(winner)) => Status::Won(winner),
+            (true, None) => Status::Drawn,
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Rule options that change how a game is decided.
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{Board, Cell, Player};
+
+/// Most marks a player may have on the board at once when marks expire.
+pub const MAX_LIVE_MARKS: usize = 3;
+
+/// The shape of the board's edges, which decides where lines may run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Topology {
+    /// Lines stop at the edges of the board.
+    #[default]
+    Flat,
+    /// Lines wrap around from one edge to the opposite one, as on a torus.
+    Torus,
+}
+
+/// The rules a game is played under.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuleSet {
+    /// Misère play: completing a line loses instead of winning.
+    pub misere: bool,
+    /// Gravity: a mark drops to the lowest empty cell of its column.
+    pub gravity: bool,
+    /// Expiring marks: placing a fourth mark removes the player's oldest,
+    /// so the board never fills up.
+    pub expiring: bool,
+    /// Whether lines wrap around the board's edges.
+    pub topology: Topology,
+}
+
+impl RuleSet {
+    /// Returns the winner of a game in which `player` completed a line.
+    pub fn line_winner(self, player: Player) -> Player {
+        if self.misere {
+            player.other()
+        } else {
+            player
+        }
+    }
+
+    /// Returns the row a mark placed at `(row, col)` ends up on, or `None`
+    /// if it cannot be placed there.
+    ///
+    /// Under gravity any cell of a column picks the column, and the mark
+    /// falls until it comes to rest on a mark or blocked cell below it.
+    pub fn landing_row<const N: usize>(
+        self,
+        board: &Board<N>,
+        row: usize,
+        col: usize,
+    ) -> Option<usize> {
+        if self.gravity {
+            (0..board.len())
+                .take_while(|&row| board[row][col] == Cell::Empty)
+                .last()
+        } else {
+            (board[row][col] == Cell::Empty).then_some(row)
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Board screenshots as PNG images.
+//!
+//! The board is drawn into an offscreen raster at a fixed size, whatever
+//! the size of the window, then encoded as PNG. Every shape is painted by
+//! how far each pixel lies from it, which gives smooth edges without a
+//! graphics library.
+
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+
+use crate::{Board, Cell, Player};
+
+/// File name suggested when saving a screenshot.
+pub const DEFAULT_FILE_NAME: &str = "tic-tac-toe.png";
+
+/// Width and height of the image in pixels.
+const IMAGE_SIZE: u32 = 480;
+/// Space between the board and the image edges.
+const MARGIN: f32 = 16.0;
+/// Space between neighbouring cells.
+const GAP: f32 = 8.0;
+/// Rounding of the cell corners.
+const CORNER_RADIUS: f32 = 6.0;
+/// Thickness of the X and O strokes.
+const STROKE: f32 = 12.0;
+/// Distance between the hatch stripes on blocked cells.
+const HATCH_INTERVAL: f32 = 8.0;
+
+const BACKGROUND: u32 = 0x2d2d2d;
+const EMPTY: u32 = 0x404040;
+const BLOCKED: u32 = 0x2a2a2a;
+const HATCH: u32 = 0x555555;
+const X_COLOR: u32 = 0xff6b6b;
+const O_COLOR: u32 = 0x4dabf7;
+
+/// Writes `board` to `path` as a PNG image.
+pub fn save(board: &Board, path: &Path) -> io::Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = png::Encoder::new(file, IMAGE_SIZE, IMAGE_SIZE);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&render(board).pixels)?;
+    writer.finish()?;
+    Ok(())
+}
+
+/// Draws `board` into a new raster.
+fn render(board: &Board) -> Canvas {
+    let mut canvas = Canvas::new(BACKGROUND);
+    let cell_size = (IMAGE_SIZE as f32 - 2.0 * MARGIN - 2.0 * GAP) / 3.0;
+    let half = cell_size / 2.0;
+
+    for (row, cells) in board.iter().enumerate() {
+        for (col, &cell) in cells.iter().enumerate() {
+            let left = MARGIN + col as f32 * (cell_size + GAP);
+            let top = MARGIN + row as f32 * (cell_size + GAP);
+            let (center_x, center_y) = (left + half, top + half);
+            let in_cell = |x: f32, y: f32| {
+                // Signed distance to the rounded square, negative inside
+                let dx = ((x - center_x).abs() - half + CORNER_RADIUS).max(0.0);
+                let dy = ((y - center_y).abs() - half + CORNER_RADIUS).max(0.0);
+                dx.hypot(dy) - CORNER_RADIUS
+            };
+
+            let background = if cell == Cell::Blocked {
+                BLOCKED
+            } else {
+                EMPTY
+            };
+            canvas.paint(background, |x, y| coverage(in_cell(x, y)));
+            match cell {
+                Cell::Empty => {}
+                Cell::Blocked => canvas.paint(HATCH, |x, y| {
+                    let stripe = (x + y).rem_euclid(HATCH_INTERVAL) - 1.0;
+                    coverage(in_cell(x, y).max(stripe.abs() - 1.0))
+                }),
+                Cell::Player(Player::X) => {
+                    let reach = cell_size * 0.28;
+                    canvas.paint(X_COLOR, |x, y| {
+                        let distance = segment_distance(
+                            (x, y),
+                            (center_x - reach, center_y - reach),
+                            (center_x + reach, center_y + reach),
+                        )
+                        .min(segment_distance(
+                            (x, y),
+                            (center_x - reach, center_y + reach),
+                            (center_x + reach, center_y - reach),
+                        ));
+                        coverage(distance - STROKE / 2.0)
+                    });
+                }
+                Cell::Player(Player::O) => {
+                    let radius = cell_size * 0.3;
+                    canvas.paint(O_COLOR, |x, y| {
+                        let distance = ((x - center_x).hypot(y - center_y) - radius).abs();
+                        coverage(distance - STROKE / 2.0)
+                    });
+                }
+            }
+        }
+    }
+    canvas
+}
+
+/// Returns how much of a pixel a shape covers, given the signed distance
+/// from the pixel center to the shape's edge.
+fn coverage(distance: f32) -> f32 {
+    (0.5 - distance).clamp(0.0, 1.0)
+}
+
+/// Returns the distance from `point` to the line segment from `start` to
+/// `end`.
+fn segment_distance(point: (f32, f32), start: (f32, f32), end: (f32, f32)) -> f32 {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let along = ((point.0 - start.0) * dx + (point.1 - start.1) * dy) / (dx * dx + dy * dy);
+    let along = along.clamp(0.0, 1.0);
+    (point.0 - start.0 - along * dx).hypot(point.1 - start.1 - along * dy)
+}
+
+/// An RGB raster of the whole image.
+struct Canvas {
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    /// Creates a raster filled with `color`.
+    fn new(color: u32) -> Self {
+        let pixel = channels(color);
+        Self {
+            pixels: pixel.repeat((IMAGE_SIZE * IMAGE_SIZE) as usize),
+        }
+    }
+
+    /// Blends `color` over every pixel by the coverage `shape` gives at the
+    /// pixel's center.
+    fn paint(&mut self, color: u32, shape: impl Fn(f32, f32) -> f32) {
+        let color = channels(color);
+        for (index, pixel) in self.pixels.chunks_exact_mut(3).enumerate() {
+            let x = (index as u32 % IMAGE_SIZE) as f32 + 0.5;
+            let y = (index as u32 / IMAGE_SIZE) as f32 + 0.5;
+            let alpha = shape(x, y);
+            if alpha <= 0.0 {
+                continue;
+            }
+            for (channel, &target) in pixel.iter_mut().zip(&color) {
+                let blended = *channel as f32 + (target as f32 - *channel as f32) * alpha;
+                *channel = blended.round() as u8;
+            }
+        }
+    }
+}
+
+/// Splits a `0xrrggbb` color into its channels.
+fn channels(color: u32) -> [u8; 3] {
+    let [_, red, green, blue] = color.to_be_bytes();
+    [red, green, blue]
+}
@@ -0,0 +1,76 @@
+//! Best-of-N match series.
+//!
+//! Two players play games until one of them has won a majority of the
+//! series, with the first move alternating from game to game. Drawn games
+//! do not count toward either side.
+
+use std::collections::HashMap;
+
+use crate::Player;
+
+/// Series lengths offered, cycled through by the toolbar button.
+pub const LENGTHS: [u32; 3] = [3, 5, 7];
+
+/// A best-of-N series in progress.
+#[derive(Clone, Debug)]
+pub struct Series {
+    /// Number of games the series is played over.
+    pub best_of: u32,
+    /// Games won by each player.
+    pub scores: HashMap<Player, u32>,
+    /// Games drawn so far.
+    pub draws: u32,
+}
+
+impl Series {
+    /// Creates a best-of-`best_of` series with no games played.
+    pub fn new(best_of: u32) -> Self {
+        Self {
+            best_of,
+            scores: HashMap::from([(Player::X, 0), (Player::O, 0)]),
+            draws: 0,
+        }
+    }
+
+    /// Returns the games won by `player`.
+    pub fn score(&self, player: Player) -> u32 {
+        self.scores.get(&player).copied().unwrap_or_default()
+    }
+
+    /// Records the result of a finished game, a draw when `winner` is
+    /// `None`.
+    pub fn record(&mut self, winner: Option<Player>) {
+        match winner {
+            Some(player) => *self.scores.entry(player).or_default() += 1,
+            None => self.draws += 1,
+        }
+    }
+
+    /// Returns the player who has won the series, once someone has.
+    pub fn champion(&self) -> Option<Player> {
+        let needed = self.best_of / 2 + 1;
+        [Player::X, Player::O]
+            .into_iter()
+            .find(|&player| self.score(player) >= needed)
+    }
+
+    /// Returns the player to move first in the next game. X opens the
+    /// series and the first move alternates after every game.
+    pub fn next_first(&self) -> Player {
+        let played = self.score(Player::X) + self.score(Player::O) + self.draws;
+        if played % 2 == 0 {
+            Player::X
+        } else {
+            Player::O
+        }
+    }
+}
+
+/// Returns the series length after `current` in the toolbar cycle, or
+/// `None` to turn series play off after the longest.
+pub fn next_length(current: Option<u32>) -> Option<u32> {
+    match current {
+        None => Some(LENGTHS[0]),
+        Some(length) => LENGTHS.iter().copied().find(|&next| next > length),
+    }
+}
@@ -0,0 +1,170 @@
+//! User preferences that persist between sessions.
+//!
+//! Unlike the window placement, these are choices the player makes
+//! explicitly, such as turning off a confirmation prompt. They are kept in
+//! a TOML file so they can also be edited by hand. Earlier versions kept
+//! them as JSON, which is read once and rewritten as TOML.
+
+use std::fs;
+use std::path::PathBuf;
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+
+use crate::ai::{Difficulty, Opponent};
+use crate::online::DEFAULT_RELAY_URL;
+
+/// Font families that are easier to read for players with dyslexia,
+/// offered first in the font picker when installed.
+pub const READABLE_FONTS: [&str; 3] = ["OpenDyslexic", "Atkinson Hyperlegible", "Lexend"];
+
+/// Game clock budgets offered in the settings panel, in minutes per
+/// player.
+pub const CLOCK_MINUTES: [u64; 3] = [1, 3, 5];
+
+/// Fischer increments offered in the settings panel, in seconds added to
+/// a player's game clock after each of their moves.
+pub const CLOCK_INCREMENT_SECS: [u64; 3] = [1, 2, 5];
+
+/// Per-move time limits offered in the settings panel, in seconds.
+pub const MOVE_LIMIT_SECS: [u64; 3] = [5, 10, 20];
+
+/// Name of the JSON settings file written by earlier versions, next to
+/// the TOML one.
+const LEGACY_FILE_NAME: &str = "settings.json";
+
+/// What happens when a player runs over the per-move time limit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum MoveTimeout {
+    /// The player loses the game.
+    #[default]
+    Forfeit,
+    /// A random legal move is played for them.
+    RandomMove,
+}
+
+/// The player's saved preferences.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Whether to ask before abandoning a game that is still in progress.
+    pub confirm_abandon: bool,
+    /// Font family used throughout the interface, or `None` for the
+    /// system default.
+    pub font: Option<String>,
+    /// How strongly the computer opponent plays.
+    pub difficulty: Difficulty,
+    /// Minutes each player has on the game clock, or `None` to play
+    /// without clocks.
+    pub clock_minutes: Option<u64>,
+    /// Seconds added to a player's game clock after each of their moves,
+    /// or `None` for no increment.
+    pub clock_increment_secs: Option<u64>,
+    /// Seconds allowed for each move in blitz play, or `None` for no
+    /// limit.
+    pub move_limit_secs: Option<u64>,
+    /// What happens when a move takes longer than the limit.
+    pub move_timeout: MoveTimeout,
+    /// WebSocket address of the relay server for online play.
+    pub relay_url: String,
+    /// Whether a coin flip decides who moves first in each new game.
+    pub random_start: bool,
+    /// Whether the relay is asked to record each online game played, so
+    /// it can be replayed from the History screen.
+    pub record_online_games: bool,
+    /// Who new sessions start against.
+    pub opponent: Opponent,
+    /// The player's name, whose profile is seated at X in new sessions,
+    /// or `None` if not given.
+    pub player_name: Option<String>,
+    /// Whether the first-run setup has been finished or skipped.
+    pub setup_done: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            confirm_abandon: true,
+            font: None,
+            difficulty: Difficulty::default(),
+            clock_minutes: None,
+            clock_increment_secs: None,
+            move_limit_secs: None,
+            move_timeout: MoveTimeout::default(),
+            relay_url: DEFAULT_RELAY_URL.to_string(),
+            random_start: false,
+            record_online_games: false,
+            opponent: Opponent::default(),
+            player_name: None,
+            setup_done: false,
+        }
+    }
+}
+
+impl Settings {
+    /// Loads the saved settings, falling back to the defaults.
+    ///
+    /// Without a TOML file, settings saved as JSON by an earlier version
+    /// are carried over and saved in the new format.
+    pub fn load() -> Self {
+        let Some(path) = settings_path() else {
+            return Self::default();
+        };
+        if let Ok(toml) = fs::read_to_string(&path) {
+            return toml::from_str(&toml).unwrap_or_default();
+        }
+        let legacy = path.with_file_name(LEGACY_FILE_NAME);
+        match fs::read_to_string(legacy).map(|json| serde_json::from_str::<Self>(&json)) {
+            Ok(Ok(settings)) => {
+                settings.save();
+                settings
+            }
+            _ => Self::default(),
+        }
+    }
+
+    /// Moves the game clock to the next budget, turning it off after the
+    /// longest.
+    pub fn cycle_clock(&mut self) {
+        self.clock_minutes = match self.clock_minutes {
+            None => Some(CLOCK_MINUTES[0]),
+            Some(minutes) => CLOCK_MINUTES.into_iter().find(|&next| next > minutes),
+        };
+    }
+
+    /// Moves the clock increment to the next length, turning it off after
+    /// the longest.
+    pub fn cycle_clock_increment(&mut self) {
+        self.clock_increment_secs = match self.clock_increment_secs {
+            None => Some(CLOCK_INCREMENT_SECS[0]),
+            Some(secs) => CLOCK_INCREMENT_SECS.into_iter().find(|&next| next > secs),
+        };
+    }
+
+    /// Moves the per-move limit to the next length, turning it off after
+    /// the longest.
+    pub fn cycle_move_limit(&mut self) {
+        self.move_limit_secs = match self.move_limit_secs {
+            None => Some(MOVE_LIMIT_SECS[0]),
+            Some(secs) => MOVE_LIMIT_SECS.into_iter().find(|&next| next > secs),
+        };
+    }
+
+    /// Writes the settings to disk, ignoring failures.
+    pub fn save(&self) {
+        let Some(path) = settings_path() else {
+            return;
+        };
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        if let Ok(toml) = toml::to_string_pretty(self) {
+            let _ = fs::write(path, toml);
+        }
+    }
+}
+
+/// Location of the settings file in the platform config directory.
+pub fn settings_path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "tic-tac-toe").map(|dirs| dirs.config_dir().join("settings.toml"))
+}
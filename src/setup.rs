@@ -0,0 +1,59 @@
+//! First-run setup: a short wizard shown on the first launch.
+//!
+//! It asks who the player usually plays against and what they are called,
+//! and writes the answers to the settings file, so new sessions start
+//! against that opponent with the player's profile seated at X instead of
+//! the defaults having to be found in the settings panel. It can be
+//! skipped, and run again from the settings panel.
+
+use tic_tac_toe::ai::Opponent;
+
+use crate::settings::Settings;
+
+/// Longest player name the wizard takes, in characters.
+pub const MAX_NAME_LEN: usize = 24;
+
+/// A page of the wizard.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Step {
+    /// Choosing the opponent new sessions start against.
+    Opponent,
+    /// Typing the player's name.
+    Name,
+}
+
+/// The wizard's answers so far.
+#[derive(Clone, Debug)]
+pub struct Setup {
+    /// The page shown.
+    pub step: Step,
+    /// The opponent chosen.
+    pub opponent: Opponent,
+    /// The name typed.
+    pub name: String,
+}
+
+impl Setup {
+    /// Starts the wizard from the saved answers, if any.
+    pub fn new(settings: &Settings) -> Self {
+        Self {
+            step: Step::Opponent,
+            opponent: settings.opponent,
+            name: settings.player_name.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Types `text` into the name, up to its longest.
+    pub fn type_name(&mut self, text: &str) {
+        let room = MAX_NAME_LEN.saturating_sub(self.name.chars().count());
+        self.name.extend(text.chars().take(room));
+    }
+
+    /// Writes the answers to `settings`, marking setup as done.
+    pub fn apply(&self, settings: &mut Settings) {
+        let name = self.name.trim();
+        settings.opponent = self.opponent;
+        settings.player_name = (!name.is_empty()).then(|| name.to_string());
+        settings.setup_done = true;
+    }
+}
@@ -0,0 +1,217 @@
+//! Share links for games.
+//!
+//! A game is packed into a few bytes and written as URL-safe base64 after
+//! [`LINK_PREFIX`], giving links like `tictactoe://game#BgAUARgC`. The
+//! first byte holds who started, who is to move, how the game ended, and
+//! whether rules other than the standard ones or a custom starting
+//! position follow. The rules take one more byte with a bit for each
+//! option, a custom position three more at two bits a cell, and then each
+//! move is one byte: its player in the high nibble and its cell, counted
+//! in reading order, in the low. Annotations are left out, to keep links
+//! short.
+
+use std::collections::BTreeMap;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+
+use crate::game_record::GameRecord;
+use crate::game_result::GameResult;
+use crate::rules::{RuleSet, Topology};
+use crate::{Board, Cell, Player};
+
+/// Text every share link starts with.
+pub const LINK_PREFIX: &str = "tictactoe://game#";
+
+const O_STARTS: u8 = 1;
+const O_TO_MOVE: u8 = 1 << 1;
+const RESULT_SHIFT: u8 = 2;
+const RESULT_MASK: u8 = 0b11 << RESULT_SHIFT;
+/// With a winner, the loser resigned; with a draw, the players agreed to
+/// it.
+const RESIGNED: u8 = 1 << 4;
+const HAS_SETUP: u8 = 1 << 5;
+const HAS_RULES: u8 = 1 << 6;
+/// The loser ran out of time.
+const TIMED_OUT: u8 = 1 << 7;
+
+const MISERE: u8 = 1;
+const GRAVITY: u8 = 1 << 1;
+const EXPIRING: u8 = 1 << 2;
+const TORUS: u8 = 1 << 3;
+
+/// Writes `record` as a share link.
+pub fn encode(record: &GameRecord) -> String {
+    let result = match record.result.map(GameResult::winner) {
+        None => 0,
+        Some(Some(Player::X)) => 1,
+        Some(Some(Player::O)) => 2,
+        Some(None) => 3,
+    };
+    let custom = record.initial_board != [[Cell::Empty; 3]; 3];
+    let mut header = result << RESULT_SHIFT;
+    match record.result {
+        Some(GameResult::Resignation { .. } | GameResult::AgreedDraw) => header |= RESIGNED,
+        Some(GameResult::Timeout { .. }) => header |= TIMED_OUT,
+        Some(GameResult::Win(_) | GameResult::Draw) | None => {}
+    }
+    if record.starting_player == Player::O {
+        header |= O_STARTS;
+    }
+    if record.current_player == Player::O {
+        header |= O_TO_MOVE;
+    }
+    if custom {
+        header |= HAS_SETUP;
+    }
+    let rules = pack_rules(record.rules);
+    if rules != 0 {
+        header |= HAS_RULES;
+    }
+
+    let mut bytes = vec![header];
+    if rules != 0 {
+        bytes.push(rules);
+    }
+    if custom {
+        bytes.extend(pack_board(&record.initial_board));
+    }
+    bytes.extend(
+        record
+            .moves
+            .iter()
+            .map(|&(row, col, player)| (player_bits(player) << 4) | (row * 3 + col) as u8),
+    );
+    format!("{LINK_PREFIX}{}", URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// Reads a share link back into a game, or `None` if `link` is not a
+/// well-formed link.
+pub fn decode(link: &str) -> Option<GameRecord> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(link.trim().strip_prefix(LINK_PREFIX)?)
+        .ok()?;
+    let (&header, mut rest) = bytes.split_first()?;
+    let player = |bit| {
+        if header & bit != 0 {
+            Player::O
+        } else {
+            Player::X
+        }
+    };
+    let winner = match (header & RESULT_MASK) >> RESULT_SHIFT {
+        0 => None,
+        1 => Some(Some(Player::X)),
+        2 => Some(Some(Player::O)),
+        _ => Some(None),
+    };
+    let result = winner.map(|winner| match winner {
+        Some(winner) if header & TIMED_OUT != 0 => GameResult::Timeout { winner },
+        Some(winner) if header & RESIGNED != 0 => GameResult::Resignation { winner },
+        Some(winner) => GameResult::Win(winner),
+        None if header & RESIGNED != 0 => GameResult::AgreedDraw,
+        None => GameResult::Draw,
+    });
+
+    let mut rules = RuleSet::default();
+    if header & HAS_RULES != 0 {
+        let (&packed, moves) = rest.split_first()?;
+        rules = unpack_rules(packed);
+        rest = moves;
+    }
+    let mut initial_board = [[Cell::Empty; 3]; 3];
+    if header & HAS_SETUP != 0 {
+        let (setup, moves) = rest.split_at_checked(3)?;
+        initial_board = unpack_board(setup);
+        rest = moves;
+    }
+    let moves = rest
+        .iter()
+        .map(|&byte| {
+            let player = match byte >> 4 {
+                0 => Player::X,
+                1 => Player::O,
+                _ => return None,
+            };
+            let index = (byte & 0xf) as usize;
+            (index < 9).then_some((index / 3, index % 3, player))
+        })
+        .collect::<Option<_>>()?;
+
+    Some(GameRecord {
+        rules,
+        initial_board,
+        starting_player: player(O_STARTS),
+        coin_seed: None,
+        moves,
+        current_player: player(O_TO_MOVE),
+        result,
+        annotations: BTreeMap::new(),
+    })
+}
+
+/// Returns the bit pattern a player is stored as.
+fn player_bits(player: Player) -> u8 {
+    match player {
+        Player::X => 0,
+        Player::O => 1,
+    }
+}
+
+/// Packs the rule options into a byte, which is 0 for the standard rules.
+fn pack_rules(rules: RuleSet) -> u8 {
+    let options = [
+        (rules.misere, MISERE),
+        (rules.gravity, GRAVITY),
+        (rules.expiring, EXPIRING),
+        (rules.topology == Topology::Torus, TORUS),
+    ];
+    options
+        .iter()
+        .filter(|(active, _)| *active)
+        .fold(0, |bits, (_, bit)| bits | bit)
+}
+
+/// Unpacks rules written by [`pack_rules`].
+fn unpack_rules(bits: u8) -> RuleSet {
+    RuleSet {
+        misere: bits & MISERE != 0,
+        gravity: bits & GRAVITY != 0,
+        expiring: bits & EXPIRING != 0,
+        topology: if bits & TORUS != 0 {
+            Topology::Torus
+        } else {
+            Topology::Flat
+        },
+    }
+}
+
+/// Packs a board at two bits a cell, in reading order.
+fn pack_board(board: &Board) -> [u8; 3] {
+    let mut bits = 0u32;
+    for (index, cell) in board.iter().flatten().enumerate() {
+        let value = match cell {
+            Cell::Empty => 0,
+            Cell::Player(player) => 1 + player_bits(*player) as u32,
+            Cell::Blocked => 3,
+        };
+        bits |= value << (index * 2);
+    }
+    let [low, middle, high, _] = bits.to_le_bytes();
+    [low, middle, high]
+}
+
+/// Unpacks a board written by [`pack_board`].
+fn unpack_board(bytes: &[u8]) -> Board {
+    let bits = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]);
+    let mut board = [[Cell::Empty; 3]; 3];
+    for index in 0..9 {
+        board[index / 3][index % 3] = match (bits >> (index * 2)) & 0b11 {
+            0 => Cell::Empty,
+            1 => Cell::Player(Player::X),
+            2 => Cell::Player(Player::O),
+            _ => Cell::Blocked,
+        };
+    }
+    board
+}
@@ -0,0 +1,94 @@
+//! Simul: one player against several computer opponents at once.
+//!
+//! As in a chess simultaneous exhibition, the player takes X on every
+//! board and moves on one board at a time. That board's computer replies
+//! at its own difficulty, and play passes to the next board still going,
+//! until every game is over.
+
+use tic_tac_toe::ai::{self, Difficulty, COMPUTER_SIDE};
+use tic_tac_toe::game::{Game, Status};
+
+use crate::{Cell, GameState, Player, Position, RuleSet};
+
+/// The computer's difficulty on each board, left to right.
+pub const OPPONENTS: [Difficulty; 2] = [Difficulty::Easy, Difficulty::Hard];
+
+/// One board of a simul and the computer playing on it.
+#[derive(Clone, Copy, Debug)]
+pub struct SimulBoard {
+    pub game: GameState,
+    pub difficulty: Difficulty,
+}
+
+/// The state of a simul.
+#[derive(Clone, Debug)]
+pub struct Simul {
+    /// The boards in play, left to right.
+    pub boards: Vec<SimulBoard>,
+    /// The board the player moves on next.
+    pub active: usize,
+}
+
+impl Simul {
+    /// Starts a game against each of [`OPPONENTS`] under `rules`, with the
+    /// player to move first on the leftmost board.
+    pub fn new(rules: RuleSet) -> Self {
+        let boards = OPPONENTS
+            .iter()
+            .map(|&difficulty| SimulBoard {
+                game: GameState::new(Player::X, [[Cell::Empty; 3]; 3], rules),
+                difficulty,
+            })
+            .collect();
+        Self { boards, active: 0 }
+    }
+
+    /// Whether every board's game is over.
+    pub fn is_finished(&self) -> bool {
+        self.boards
+            .iter()
+            .all(|board| board.game.status() != Status::InProgress)
+    }
+
+    /// Plays the player's move at `position` on the active board, then
+    /// the computer's reply, and passes play to the next board still
+    /// going. Returns whether the move was legal.
+    pub fn play(&mut self, position: Position) -> bool {
+        let board = &mut self.boards[self.active];
+        if board.game.status() != Status::InProgress || board.game.play(position).is_err() {
+            return false;
+        }
+        if board.game.status() == Status::InProgress {
+            let rules = board.game.rules();
+            let reply = ai::choose_move(board.game.board(), COMPUTER_SIDE, board.difficulty, rules);
+            if let Some(reply) = reply {
+                // The computer only chooses empty cells
+                let _ = board.game.play(reply);
+            }
+        }
+
+        let count = self.boards.len();
+        if let Some(next) = (1..=count)
+            .map(|step| (self.active + step) % count)
+            .find(|&index| self.boards[index].game.status() == Status::InProgress)
+        {
+            self.active = next;
+        }
+        true
+    }
+
+    /// Returns the player's wins, draws, and losses on the finished
+    /// boards.
+    pub fn score(&self) -> (usize, usize, usize) {
+        let mut score = (0, 0, 0);
+        for board in &self.boards {
+            match board.game.status() {
+                Status::Won(Player::X) => score.0 += 1,
+                Status::Drawn => score.1 += 1,
+                Status::Won(Player::O) => score.2 += 1,
+                Status::InProgress => {}
+            }
+        }
+        score
+    }
+}
@@ -0,0 +1,158 @@
+//! Batch self-play for evaluating the computer opponent.
+//!
+//! `tic-tac-toe simulate --games 10000 --p1 random --p2 minimax` plays the
+//! given number of computer-vs-computer games without a window, spread
+//! over every CPU core, and prints how often each side won, how many games
+//! were drawn, and how long games lasted on average. Player 1 plays X and
+//! always moves first.
+
+use std::fmt;
+use std::io;
+use std::thread;
+
+use clap::ValueEnum;
+use tic_tac_toe::ai::{self, Difficulty};
+use tic_tac_toe::engine::{Cell, GameState, Outcome, Player};
+use tic_tac_toe::rules::RuleSet;
+
+/// Games played when `--games` is left out.
+const DEFAULT_GAMES: u32 = 1000;
+
+/// How a simulated player picks its moves.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum Strategy {
+    /// Any empty cell, as the computer plays on Easy.
+    Random,
+    /// The best move half the time, as on Medium.
+    Medium,
+    /// Always the best move found by the full search, as on Hard.
+    Minimax,
+}
+
+impl Strategy {
+    fn difficulty(self) -> Difficulty {
+        match self {
+            Strategy::Random => Difficulty::Easy,
+            Strategy::Medium => Difficulty::Medium,
+            Strategy::Minimax => Difficulty::Hard,
+        }
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Strategy::Random => "random",
+            Strategy::Medium => "medium",
+            Strategy::Minimax => "minimax",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Options for a simulation, read from the command line.
+#[derive(Clone, Copy, Debug, clap::Args)]
+pub struct Options {
+    /// Number of games to play.
+    #[arg(long, default_value_t = DEFAULT_GAMES, value_parser = clap::value_parser!(u32).range(1..))]
+    games: u32,
+    /// How player 1, playing X, picks moves.
+    #[arg(long, value_enum, default_value_t = Strategy::Minimax)]
+    p1: Strategy,
+    /// How player 2, playing O, picks moves.
+    #[arg(long, value_enum, default_value_t = Strategy::Minimax)]
+    p2: Strategy,
+}
+
+/// Results tallied over many games.
+#[derive(Clone, Copy, Debug, Default)]
+struct Tally {
+    /// Games won by each side, indexed by [`Player::index`].
+    wins: [u32; 2],
+    draws: u32,
+    /// Moves played over all games.
+    moves: u64,
+}
+
+impl Tally {
+    fn add(mut self, other: Tally) -> Tally {
+        self.wins[0] += other.wins[0];
+        self.wins[1] += other.wins[1];
+        self.draws += other.draws;
+        self.moves += other.moves;
+        self
+    }
+
+    fn games(&self) -> u32 {
+        self.wins[0] + self.wins[1] + self.draws
+    }
+}
+
+/// Runs a simulation with the given options and prints its statistics.
+pub fn run(options: Options) -> io::Result<()> {
+    let players = [options.p1, options.p2];
+    let workers = thread::available_parallelism().map_or(1, |count| count.get() as u32);
+    let tally = thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|worker| {
+                // Spread the games as evenly as the count allows
+                let games = options.games / workers + u32::from(worker < options.games % workers);
+                scope.spawn(move || {
+                    (0..games).fold(Tally::default(), |tally, _| tally.add(play(players)))
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("simulation thread panicked"))
+            .fold(Tally::default(), Tally::add)
+    });
+
+    let games = tally.games();
+    let percent = |count: u32| f64::from(count) * 100.0 / f64::from(games);
+    let [p1, p2] = players;
+    println!("{games} games, {p1} (X) vs {p2} (O)");
+    println!(
+        "X wins: {} ({:.1}%)",
+        tally.wins[Player::X.index()],
+        percent(tally.wins[Player::X.index()])
+    );
+    println!(
+        "O wins: {} ({:.1}%)",
+        tally.wins[Player::O.index()],
+        percent(tally.wins[Player::O.index()])
+    );
+    println!("Draws:  {} ({:.1}%)", tally.draws, percent(tally.draws));
+    println!(
+        "Average length: {:.2} moves",
+        tally.moves as f64 / f64::from(games)
+    );
+    Ok(())
+}
+
+/// Plays one game between the two strategies, X first, and returns its
+/// result as a tally of one.
+fn play(players: [Strategy; 2]) -> Tally {
+    let mut game = GameState::new(Player::X, [[Cell::Empty; 3]; 3], RuleSet::default());
+    let mut tally = Tally::default();
+    loop {
+        match game.outcome() {
+            Outcome::InProgress => {}
+            Outcome::Won(winner, _) => tally.wins[winner.index()] += 1,
+            Outcome::Draw => tally.draws += 1,
+        }
+        if game.outcome().is_over() {
+            tally.moves = game.ply() as u64;
+            return tally;
+        }
+        let player = game.to_move();
+        let position = ai::choose_move(
+            game.board(),
+            player,
+            players[player.index()].difficulty(),
+            game.rules(),
+        )
+        .expect("an unfinished game has a free cell");
+        game.play(position).expect("the computer plays legal moves");
+    }
+}
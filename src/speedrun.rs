@@ -0,0 +1,158 @@
+//! Speedrun: beating each computer difficulty in turn against the clock.
+//!
+//! A run plays the computer on Easy, then Medium, then Hard, and each
+//! difficulty is a split. A split is cleared by a game the player does not
+//! lose, since Hard plays perfectly and a draw is the best anyone can do
+//! against it. A lost game is replayed at the same difficulty with the
+//! clock still running. The best time for each split and for the whole run
+//! are kept between sessions, and the split in progress shows how far it is
+//! ahead of or behind its best.
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use tic_tac_toe::ai::{Difficulty, COMPUTER_SIDE};
+
+use crate::Player;
+
+/// The difficulties of a run, in the order they are played.
+pub const SPLITS: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+
+/// The best times, persisted between sessions.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PersonalBests {
+    /// Fastest clear of each split, in [`SPLITS`] order.
+    splits: [Option<Duration>; 3],
+    /// Fastest whole run.
+    run: Option<Duration>,
+}
+
+impl PersonalBests {
+    /// Loads the saved times, falling back to none.
+    pub fn load() -> Self {
+        bests_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Returns the fastest clear of the split at `index`.
+    pub fn split(&self, index: usize) -> Option<Duration> {
+        self.splits.get(index).copied().flatten()
+    }
+
+    /// Returns the fastest whole run.
+    pub fn run(&self) -> Option<Duration> {
+        self.run
+    }
+
+    /// Writes the times to disk, ignoring failures.
+    fn save(&self) {
+        let Some(path) = bests_path() else {
+            return;
+        };
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        if let Ok(json) = serde_json::to_string_pretty(self) {
+            let _ = fs::write(path, json);
+        }
+    }
+}
+
+/// A run in progress or just finished.
+#[derive(Clone, Debug)]
+pub struct Speedrun {
+    /// The best times from before this run.
+    pub bests: PersonalBests,
+    /// How long each split cleared so far took.
+    pub times: Vec<Duration>,
+    /// When the run started.
+    started: Instant,
+    /// When the split in progress started.
+    split_started: Instant,
+}
+
+impl Speedrun {
+    /// Starts a run on its first split.
+    pub fn start() -> Self {
+        let now = Instant::now();
+        Self {
+            bests: PersonalBests::load(),
+            times: Vec::new(),
+            started: now,
+            split_started: now,
+        }
+    }
+
+    /// Whether every split has been cleared.
+    pub fn is_finished(&self) -> bool {
+        self.times.len() == SPLITS.len()
+    }
+
+    /// Returns the index of the split being played, or of the last one
+    /// once the run is over.
+    pub fn split(&self) -> usize {
+        self.times.len().min(SPLITS.len() - 1)
+    }
+
+    /// Returns the computer's difficulty for the split being played.
+    pub fn difficulty(&self) -> Difficulty {
+        SPLITS[self.split()]
+    }
+
+    /// Returns the time the run has taken, stopping once it is over.
+    pub fn elapsed(&self) -> Duration {
+        if self.is_finished() {
+            self.times.iter().sum()
+        } else {
+            self.started.elapsed()
+        }
+    }
+
+    /// Returns the time spent on the split in progress.
+    pub fn split_elapsed(&self) -> Duration {
+        self.split_started.elapsed()
+    }
+
+    /// Returns how many seconds the split in progress is behind its best,
+    /// negative when it is ahead, or `None` with no best to compare to.
+    pub fn delta(&self) -> Option<f64> {
+        let best = self.bests.split(self.split())?;
+        Some(self.split_elapsed().as_secs_f64() - best.as_secs_f64())
+    }
+
+    /// Records a finished game of the split in progress, won by `winner`
+    /// or drawn when `None`. Unless the computer won, the split is cleared
+    /// and the next one starts; clearing the last one finishes the run and
+    /// saves any new best times.
+    pub fn record(&mut self, winner: Option<Player>) {
+        if self.is_finished() || winner == Some(COMPUTER_SIDE) {
+            return;
+        }
+        let index = self.times.len();
+        let time = self.split_started.elapsed();
+        self.times.push(time);
+        self.split_started = Instant::now();
+
+        let mut bests = PersonalBests::load();
+        if bests.split(index).map_or(true, |best| time < best) {
+            bests.splits[index] = Some(time);
+        }
+        if self.is_finished() {
+            let total = self.elapsed();
+            if bests.run.map_or(true, |best| total < best) {
+                bests.run = Some(total);
+            }
+        }
+        bests.save();
+    }
+}
+
+/// Location of the best-times file in the platform data directory.
+pub fn bests_path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "tic-tac-toe").map(|dirs| dirs.data_dir().join("speedrun.json"))
+}
@@ -0,0 +1,85 @@
+//! 2v2 team play.
+//!
+//! Each side is a team of two seats that take turns placing that side's
+//! marks, so partners alternate moves. Results are tallied per team across
+//! the session.
+
+use crate::Player;
+
+/// A team of two players sharing one side.
+#[derive(Debug)]
+pub struct Team {
+    pub name: String,
+    /// Seat names, in the order they take turns.
+    pub seats: [String; 2],
+    /// Index of the seat that places the team's next mark.
+    next_seat: usize,
+    pub wins: u32,
+    pub draws: u32,
+}
+
+impl Team {
+    fn new(side: Player) -> Self {
+        Self {
+            name: format!("Team {side:?}"),
+            seats: ["Seat 1".to_string(), "Seat 2".to_string()],
+            next_seat: 0,
+            wins: 0,
+            draws: 0,
+        }
+    }
+
+    /// Returns the name of the seat whose turn it is for this team.
+    pub fn seat_to_move(&self) -> &str {
+        &self.seats[self.next_seat]
+    }
+}
+
+/// The two teams in a 2v2 session.
+#[derive(Debug)]
+pub struct TeamMatch {
+    x: Team,
+    o: Team,
+}
+
+impl TeamMatch {
+    /// Creates a session with both teams at their first seat and no results.
+    pub fn new() -> Self {
+        Self {
+            x: Team::new(Player::X),
+            o: Team::new(Player::O),
+        }
+    }
+
+    /// Returns the team playing `side`.
+    pub fn team(&self, side: Player) -> &Team {
+        match side {
+            Player::X => &self.x,
+            Player::O => &self.o,
+        }
+    }
+
+    fn team_mut(&mut self, side: Player) -> &mut Team {
+        match side {
+            Player::X => &mut self.x,
+            Player::O => &mut self.o,
+        }
+    }
+
+    /// Passes `side`'s turn to the team's other seat after it moves.
+    pub fn advance_seat(&mut self, side: Player) {
+        let team = self.team_mut(side);
+        team.next_seat = 1 - team.next_seat;
+    }
+
+    /// Tallies a finished game, where `None` is a draw.
+    pub fn record(&mut self, winner: Option<Player>) {
+        match winner {
+            Some(side) => self.team_mut(side).wins += 1,
+            None => {
+                self.x.draws += 1;
+                self.o.draws += 1;
+            }
+        }
+    }
+}
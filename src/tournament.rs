@@ -0,0 +1,307 @@
+//! Local tournaments for hot-seat groups.
+//!
+//! Players are entered by name and play either a round robin, where every
+//! pair meets once and results are tallied into a standings table, or a
+//! single-elimination bracket, where winners advance until one champion
+//! is left.
+
+use crate::Player;
+
+/// Fewest players a tournament can have.
+pub const MIN_PLAYERS: usize = 3;
+
+/// Most players a tournament can have.
+pub const MAX_PLAYERS: usize = 8;
+
+/// Player counts a single-elimination bracket can be played with.
+pub const BRACKET_SIZES: [usize; 2] = [4, 8];
+
+/// How a tournament is played.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Format {
+    /// Every player meets every other player once.
+    #[default]
+    RoundRobin,
+    /// Players are knocked out until one is left.
+    Bracket,
+}
+
+/// The name-entry screen shown before a tournament starts.
+#[derive(Debug, Default)]
+pub struct TournamentSetup {
+    /// Names entered so far, in entry order.
+    pub names: Vec<String>,
+    /// The name currently being typed.
+    pub input: String,
+    /// The format the tournament will be played in.
+    pub format: Format,
+}
+
+impl TournamentSetup {
+    /// Adds the typed name to the list, ignoring blanks and duplicates.
+    pub fn add_name(&mut self) {
+        let name = self.input.trim().to_string();
+        self.input.clear();
+        if name.is_empty() || self.names.len() >= MAX_PLAYERS || self.names.contains(&name) {
+            return;
+        }
+        self.names.push(name);
+    }
+
+    /// Removes the name at `index`.
+    pub fn remove_name(&mut self, index: usize) {
+        if index < self.names.len() {
+            self.names.remove(index);
+        }
+    }
+
+    /// Whether the right number of players has been entered to start: at
+    /// least [`MIN_PLAYERS`] for a round robin, or exactly one of the
+    /// [`BRACKET_SIZES`] for a bracket.
+    pub fn can_start(&self) -> bool {
+        match self.format {
+            Format::RoundRobin => self.names.len() >= MIN_PLAYERS,
+            Format::Bracket => BRACKET_SIZES.contains(&self.names.len()),
+        }
+    }
+}
+
+/// A player's record in the tournament.
+#[derive(Clone, Debug)]
+pub struct Standing {
+    pub name: String,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl Standing {
+    /// Points earned: three for a win and one for a draw.
+    pub fn points(&self) -> u32 {
+        self.wins * 3 + self.draws
+    }
+}
+
+/// A round-robin tournament in progress.
+#[derive(Debug)]
+pub struct Tournament {
+    /// One entry per player, in entry order.
+    standings: Vec<Standing>,
+    /// Every game to play as (X, O) player indices, in order.
+    schedule: Vec<(usize, usize)>,
+    /// Index of the next game in the schedule.
+    next_game: usize,
+}
+
+impl Tournament {
+    /// Creates a round-robin schedule where every player meets every other
+    /// player once.
+    ///
+    /// Rounds are generated with the circle method so nobody plays twice in
+    /// a row more than necessary, and sides alternate between rounds.
+    pub fn round_robin(names: Vec<String>) -> Self {
+        let standings: Vec<_> = names
+            .into_iter()
+            .map(|name| Standing {
+                name,
+                wins: 0,
+                draws: 0,
+                losses: 0,
+            })
+            .collect();
+
+        // Pad to an even count with a bye, represented by `None`.
+        let mut seats: Vec<Option<usize>> = (0..standings.len()).map(Some).collect();
+        if seats.len() % 2 == 1 {
+            seats.push(None);
+        }
+
+        let mut schedule = Vec::new();
+        let seat_count = seats.len();
+        for round in 0..seat_count - 1 {
+            for i in 0..seat_count / 2 {
+                if let (Some(a), Some(b)) = (seats[i], seats[seat_count - 1 - i]) {
+                    schedule.push(if round % 2 == 0 { (a, b) } else { (b, a) });
+                }
+            }
+            // Keep the first seat fixed and rotate the rest.
+            seats[1..].rotate_right(1);
+        }
+
+        Self {
+            standings,
+            schedule,
+            next_game: 0,
+        }
+    }
+
+    /// Returns the names of the X and O players for the next game.
+    pub fn current_pairing(&self) -> Option<(&str, &str)> {
+        let &(x, o) = self.schedule.get(self.next_game)?;
+        Some((&self.standings[x].name, &self.standings[o].name))
+    }
+
+    /// Returns the number of the next game (1-based) and the total.
+    pub fn progress(&self) -> (usize, usize) {
+        (self.next_game + 1, self.schedule.len())
+    }
+
+    /// Records the result of the current game and moves to the next one.
+    pub fn record(&mut self, winner: Option<Player>) {
+        let Some(&(x, o)) = self.schedule.get(self.next_game) else {
+            return;
+        };
+        match winner {
+            Some(Player::X) => {
+                self.standings[x].wins += 1;
+                self.standings[o].losses += 1;
+            }
+            Some(Player::O) => {
+                self.standings[o].wins += 1;
+                self.standings[x].losses += 1;
+            }
+            None => {
+                self.standings[x].draws += 1;
+                self.standings[o].draws += 1;
+            }
+        }
+        self.next_game += 1;
+    }
+
+    /// Whether every scheduled game has been played.
+    pub fn is_complete(&self) -> bool {
+        self.next_game >= self.schedule.len()
+    }
+
+    /// Returns the standings ordered by points, then wins.
+    pub fn table(&self) -> Vec<&Standing> {
+        let mut table: Vec<_> = self.standings.iter().collect();
+        table.sort_by(|a, b| b.points().cmp(&a.points()).then(b.wins.cmp(&a.wins)));
+        table
+    }
+
+    /// Returns the winner of a completed tournament.
+    pub fn champion(&self) -> Option<&str> {
+        if !self.is_complete() {
+            return None;
+        }
+        self.table().first().map(|standing| standing.name.as_str())
+    }
+}
+
+/// A game in a bracket, between two players given by their entry index.
+#[derive(Clone, Copy, Debug)]
+pub struct BracketMatch {
+    /// The player with X.
+    pub x: usize,
+    /// The player with O.
+    pub o: usize,
+    /// The player who went through, once the match is decided.
+    pub winner: Option<usize>,
+}
+
+/// A single-elimination bracket in progress.
+#[derive(Debug)]
+pub struct Bracket {
+    /// Player names in entry order.
+    names: Vec<String>,
+    /// The matches of each round played so far. Adjacent matches feed the
+    /// same match of the next round.
+    rounds: Vec<Vec<BracketMatch>>,
+}
+
+impl Bracket {
+    /// Creates a bracket pairing the players in entry order.
+    pub fn new(names: Vec<String>) -> Self {
+        let first_round = (0..names.len() / 2)
+            .map(|index| BracketMatch {
+                x: index * 2,
+                o: index * 2 + 1,
+                winner: None,
+            })
+            .collect();
+        Self {
+            names,
+            rounds: vec![first_round],
+        }
+    }
+
+    /// Returns a player's name.
+    pub fn name(&self, player: usize) -> &str {
+        &self.names[player]
+    }
+
+    /// Returns the rounds played so far, earliest first.
+    pub fn rounds(&self) -> &[Vec<BracketMatch>] {
+        &self.rounds
+    }
+
+    /// Returns the number of rounds the whole bracket takes.
+    pub fn round_count(&self) -> usize {
+        self.names.len().trailing_zeros() as usize
+    }
+
+    /// Returns the name of `round` counted from the first, such as
+    /// "Semifinals".
+    pub fn round_name(&self, round: usize) -> &'static str {
+        match self.round_count() - round {
+            1 => "Final",
+            2 => "Semifinals",
+            _ => "Quarterfinals",
+        }
+    }
+
+    /// Returns the names of the X and O players for the next match.
+    pub fn current_pairing(&self) -> Option<(&str, &str)> {
+        let game = self.current_match()?;
+        Some((self.name(game.x), self.name(game.o)))
+    }
+
+    /// Returns the first undecided match of the latest round.
+    fn current_match(&self) -> Option<&BracketMatch> {
+        self.rounds
+            .last()?
+            .iter()
+            .find(|game| game.winner.is_none())
+    }
+
+    /// Records the result of the current match.
+    ///
+    /// A drawn match is replayed with the sides swapped. Once every match
+    /// of a round is decided, the winners are paired for the next round.
+    pub fn record(&mut self, winner: Option<Player>) {
+        let Some(round) = self.rounds.last_mut() else {
+            return;
+        };
+        let Some(game) = round.iter_mut().find(|game| game.winner.is_none()) else {
+            return;
+        };
+        match winner {
+            Some(Player::X) => game.winner = Some(game.x),
+            Some(Player::O) => game.winner = Some(game.o),
+            None => std::mem::swap(&mut game.x, &mut game.o),
+        }
+
+        let winners: Option<Vec<usize>> = round.iter().map(|game| game.winner).collect();
+        if let Some(winners) = winners.filter(|winners| winners.len() > 1) {
+            let next_round = winners
+                .chunks(2)
+                .map(|pair| BracketMatch {
+                    x: pair[0],
+                    o: pair[1],
+                    winner: None,
+                })
+                .collect();
+            self.rounds.push(next_round);
+        }
+    }
+
+    /// Returns the winner of the final, once it has been played.
+    pub fn champion(&self) -> Option<&str> {
+        let last = self.rounds.last()?;
+        if self.rounds.len() < self.round_count() {
+            return None;
+        }
+        last.first()?.winner.map(|winner| self.name(winner))
+    }
+}
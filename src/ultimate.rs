@@ -0,0 +1,230 @@
+//! Ultimate Tic Tac Toe.
+//!
+//! The board is a 3x3 grid of ordinary boards. The cell a player picks
+//! within a small board sends their opponent to the matching small board
+//! for the next move. Winning a small board claims that square of the
+//! large board, and three claimed squares in a row win the game.
+
+use tic_tac_toe::engine::MoveError;
+use tic_tac_toe::game::{Game, Status};
+
+use crate::{has_line, Board, Cell, Player};
+
+/// How many moves ahead the computer looks before scoring a position as
+/// undecided.
+pub const SEARCH_DEPTH: u32 = 4;
+
+/// How a small board was decided.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SubResult {
+    /// A player completed a line on it.
+    Won(Player),
+    /// It filled up without a line.
+    Drawn,
+}
+
+/// The state of an Ultimate Tic Tac Toe game.
+#[derive(Clone, Debug)]
+pub struct UltimateBoard {
+    /// The nine small boards, in reading order.
+    pub boards: [Board; 9],
+    /// How each small board was decided, if it has been.
+    pub results: [Option<SubResult>; 9],
+    /// The player whose turn it is.
+    pub to_move: Player,
+    /// The small board the next move must be played on, or `None` when
+    /// any undecided board may be chosen.
+    pub forced: Option<usize>,
+    /// Whether the game has ended.
+    pub finished: bool,
+    /// The winner of the game, if any.
+    pub winner: Option<Player>,
+}
+
+impl UltimateBoard {
+    /// Creates an empty game with `first` to move on any board.
+    pub fn new(first: Player) -> Self {
+        Self {
+            boards: [[[Cell::Empty; 3]; 3]; 9],
+            results: [None; 9],
+            to_move: first,
+            forced: None,
+            finished: false,
+            winner: None,
+        }
+    }
+
+    /// Whether the next move may be played on small board `board`.
+    pub fn is_board_open(&self, board: usize) -> bool {
+        !self.finished && self.results[board].is_none() && self.forced.is_none_or(|b| b == board)
+    }
+
+    /// Plays the current player's mark, returning whether the move was
+    /// legal. A cell off the boards is refused.
+    pub fn play(&mut self, board: usize, row: usize, col: usize) -> bool {
+        if board >= 9 || row >= 3 || col >= 3 {
+            return false;
+        }
+        if !self.is_board_open(board) || self.boards[board][row][col] != Cell::Empty {
+            return false;
+        }
+
+        let player = self.to_move;
+        let small = &mut self.boards[board];
+        small[row][col] = Cell::Player(player);
+        if has_line(small, player) {
+            self.results[board] = Some(SubResult::Won(player));
+        } else if small.iter().flatten().all(|&cell| cell != Cell::Empty) {
+            self.results[board] = Some(SubResult::Drawn);
+        }
+
+        if has_line(&self.claimed(), player) {
+            self.finished = true;
+            self.winner = Some(player);
+        } else if self.results.iter().all(Option::is_some) {
+            self.finished = true;
+        }
+
+        // The opponent is sent to the board matching the cell just played,
+        // unless that board is already decided
+        let next = row * 3 + col;
+        self.forced = self.results[next].is_none().then_some(next);
+        self.to_move = player.other();
+        true
+    }
+
+    /// Returns the large board, where each square holds the player who
+    /// won that small board.
+    fn claimed(&self) -> Board {
+        let mut claimed = [[Cell::Empty; 3]; 3];
+        for (index, result) in self.results.iter().enumerate() {
+            if let Some(SubResult::Won(player)) = result {
+                claimed[index / 3][index % 3] = Cell::Player(*player);
+            }
+        }
+        claimed
+    }
+}
+
+impl Game for UltimateBoard {
+    /// A cell as (small board, row, column).
+    type Move = (usize, usize, usize);
+
+    fn current_player(&self) -> Player {
+        self.to_move
+    }
+
+    fn legal_moves(&self) -> Vec<(usize, usize, usize)> {
+        (0..9)
+            .filter(|&board| self.is_board_open(board))
+            .flat_map(|board| (0..9).map(move |cell| (board, cell / 3, cell % 3)))
+            .filter(|&(board, row, col)| self.boards[board][row][col] == Cell::Empty)
+            .collect()
+    }
+
+    fn apply_move(&mut self, (board, row, col): (usize, usize, usize)) -> Result<(), MoveError> {
+        if self.finished {
+            return Err(MoveError::GameOver);
+        }
+        if board >= 9 || row >= 3 || col >= 3 {
+            return Err(MoveError::OutOfBounds);
+        }
+        if !self.is_board_open(board) {
+            return Err(MoveError::BoardClosed);
+        }
+        if self.play(board, row, col) {
+            Ok(())
+        } else {
+            Err(MoveError::CellOccupied)
+        }
+    }
+
+    fn status(&self) -> Status {
+        match (self.finished, self.winner) {
+            (false, _) => Status::InProgress,
+            (true, Some(winner)) => Status::Won(winner),
+            (true, None) => Status::Drawn,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const X: Cell = Cell::Player(Player::X);
+
+    /// Returns the small boards the next move may be played on.
+    fn open(game: &UltimateBoard) -> Vec<usize> {
+        (0..9).filter(|&board| game.is_board_open(board)).collect()
+    }
+
+    #[test]
+    fn the_first_move_may_be_on_any_board() {
+        let game = UltimateBoard::new(Player::X);
+        assert_eq!(game.forced, None);
+        assert_eq!(open(&game), (0..9).collect::<Vec<_>>());
+        assert_eq!(game.legal_moves().len(), 81);
+    }
+
+    #[test]
+    fn the_cell_played_picks_the_next_board() {
+        let mut game = UltimateBoard::new(Player::X);
+        assert!(game.play(4, 0, 2));
+        assert_eq!(game.forced, Some(2));
+        assert_eq!(open(&game), vec![2]);
+        assert!(game.legal_moves().iter().all(|&(board, _, _)| board == 2));
+        assert!(!game.play(4, 1, 1));
+        assert!(game.play(2, 2, 1));
+        assert_eq!(game.forced, Some(7));
+        assert_eq!(game.to_move, Player::X);
+    }
+
+    #[test]
+    fn being_sent_to_a_decided_board_frees_the_choice() {
+        let mut game = UltimateBoard::new(Player::X);
+        game.results[0] = Some(SubResult::Drawn);
+        assert!(game.play(4, 0, 0));
+        assert_eq!(game.forced, None);
+        assert_eq!(open(&game), (1..9).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn winning_a_board_closes_it() {
+        let mut game = UltimateBoard::new(Player::X);
+        game.boards[0][0][1] = X;
+        game.boards[0][0][2] = X;
+        game.forced = Some(0);
+        assert!(game.play(0, 0, 0));
+        assert_eq!(game.results[0], Some(SubResult::Won(Player::X)));
+        // The winning cell points back at the board just won
+        assert_eq!(game.forced, None);
+        assert!(!game.is_board_open(0));
+    }
+
+    #[test]
+    fn moves_off_the_forced_board_are_refused() {
+        let mut game = UltimateBoard::new(Player::X);
+        game.apply_move((4, 0, 2)).unwrap();
+        assert_eq!(game.apply_move((9, 0, 0)), Err(MoveError::OutOfBounds));
+        assert_eq!(game.apply_move((2, 3, 0)), Err(MoveError::OutOfBounds));
+        assert_eq!(game.apply_move((4, 1, 1)), Err(MoveError::BoardClosed));
+        assert!(!game.play(2, 0, 3));
+        game.apply_move((2, 0, 2)).unwrap();
+        assert_eq!(game.apply_move((2, 0, 2)), Err(MoveError::CellOccupied));
+    }
+
+    #[test]
+    fn three_boards_in_a_row_win() {
+        let mut game = UltimateBoard::new(Player::X);
+        game.results[0] = Some(SubResult::Won(Player::X));
+        game.results[1] = Some(SubResult::Won(Player::X));
+        game.boards[2][0][1] = X;
+        game.boards[2][0][2] = X;
+        game.forced = Some(2);
+        assert!(game.play(2, 0, 0));
+        assert!(game.finished);
+        assert_eq!(game.status(), Status::Won(Player::X));
+        assert_eq!(game.apply_move((3, 0, 0)), Err(MoveError::GameOver));
+    }
+}
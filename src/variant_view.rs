@@ -0,0 +1,78 @@
+//! Views of the variants played on boards of their own.
+//!
+//! Each variant's screen is drawn by its own submodule, out of the cells
+//! and boards built here, so the variants look alike: marks in the
+//! players' colours on dark cells, which light up under the pointer when
+//! they can be played.
+
+mod connect_k;
+mod notakto;
+mod pentago;
+mod qubic;
+mod simul;
+mod ultimate;
+
+use std::ops::Range;
+
+use gpui::{div, prelude::*, rgb, Div, ElementId, Pixels, Stateful};
+use tic_tac_toe::game::{Game, Status};
+
+use crate::{Cell, Player};
+
+/// Returns the column a variant's status, boards, and buttons are laid
+/// out in.
+fn screen() -> Div {
+    div()
+        .flex()
+        .flex_col()
+        .items_center()
+        .gap_2()
+        .text_color(rgb(0xffffff))
+}
+
+/// Returns the status line of a finished Ultimate or Qubic game, or
+/// `None` while it is still going.
+fn finished_status<G: Game>(game: &G) -> Option<String> {
+    match game.status() {
+        Status::InProgress => None,
+        Status::Won(player) => Some(format!("Player {player:?} Wins!")),
+        Status::Drawn => Some("It's a Draw!".to_string()),
+    }
+}
+
+/// Returns a cell `size` across showing `cell`, with the pointer and hover
+/// highlight of a cell that can be played when `playable`.
+fn cell(id: impl Into<ElementId>, size: Pixels, cell: Cell, playable: bool) -> Stateful<Div> {
+    let (label, color) = match cell {
+        Cell::Empty | Cell::Blocked => ("", rgb(0x404040)),
+        Cell::Player(Player::X) => ("X", rgb(0xff6b6b)),
+        Cell::Player(Player::O) => ("O", rgb(0x4dabf7)),
+    };
+    div()
+        .id(id)
+        .size(size)
+        .bg(color)
+        .flex()
+        .justify_center()
+        .items_center()
+        .text_size(size * 0.6)
+        .when(playable, |el| {
+            el.cursor_pointer().hover(|el| el.bg(rgb(0x505050)))
+        })
+        .child(label)
+}
+
+/// Returns the board holding the cells `cell` makes for each of `rows`
+/// and `cols`.
+fn board(
+    rows: Range<usize>,
+    cols: Range<usize>,
+    mut cell: impl FnMut(usize, usize) -> Stateful<Div>,
+) -> Div {
+    div().flex().flex_col().gap_0p5().children(rows.map(|row| {
+        div()
+            .flex()
+            .gap_0p5()
+            .children(cols.clone().map(|col| cell(row, col)))
+    }))
+}
@@ -0,0 +1,87 @@
+//! The Connect-K screen.
+
+use gpui::{div, prelude::*, rgb, Context, ElementId, MouseButton, Pixels};
+
+use super::{board, cell, screen};
+use crate::connect_k::{self, ConnectK};
+use crate::{action_button, small_button, Cell, Outcome, Player, TicTacToe};
+
+impl TicTacToe {
+    /// Renders the Connect-K game: a button for each board offered, the
+    /// status, and the board, with the winning line outlined.
+    pub fn render_connect_k(&self, cell_size: Pixels, cx: &mut Context<Self>) -> impl IntoElement {
+        let screen = screen();
+        let Some(game) = &self.connect_k else {
+            return screen;
+        };
+        let size = game.size();
+        let small_cell = cell_size * 3.0 / size as f32;
+        let outcome = game.outcome();
+
+        let status = match outcome {
+            Outcome::Won(winner, _) => format!("Player {winner:?} Wins!"),
+            Outcome::Draw => "It's a Draw!".to_string(),
+            Outcome::InProgress => format!(
+                "{:?} to Move · {} in a Row Wins",
+                game.to_move(),
+                game.win_length()
+            ),
+        };
+
+        let mut size_buttons: Vec<_> = Vec::new();
+        for option in connect_k::SIZES {
+            size_buttons.push(
+                small_button(
+                    ElementId::Name(format!("connect-k-size-{}", option.cells).into()),
+                    option.name,
+                    option.cells == size,
+                )
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event, _window, _cx| {
+                        this.connect_k = ConnectK::new(option.cells, Player::X);
+                    }),
+                ),
+            );
+        }
+
+        let cells = board(0..size, 0..size, |row, col| {
+            let in_line = outcome.line().is_some_and(|line| {
+                line.cells()
+                    .iter()
+                    .any(|position| position.row() == row && position.col() == col)
+            });
+            let mark = game.cell(row, col);
+            cell(
+                ElementId::Name(format!("connect-k-{row}-{col}").into()),
+                small_cell,
+                mark,
+                !outcome.is_over() && mark == Cell::Empty,
+            )
+            .when(in_line, |el| el.border_2().border_color(rgb(0xffd43b)))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, _event, _window, _cx| {
+                    if let Some(game) = this.connect_k.as_mut() {
+                        game.play(row, col, this.opponent);
+                    }
+                }),
+            )
+        });
+
+        screen
+            .child(div().flex().gap_2().children(size_buttons))
+            .child(div().text_xl().child(status))
+            .child(cells)
+            .when(outcome.is_over(), |el| {
+                el.child(
+                    action_button("connect-k-new-game-button", "Play Again").on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event, _window, _cx| {
+                            this.connect_k = ConnectK::new(size, Player::X);
+                        }),
+                    ),
+                )
+            })
+    }
+}
@@ -0,0 +1,107 @@
+//! The Notakto screen.
+
+use gpui::{div, prelude::*, Context, ElementId, MouseButton, Pixels};
+use tic_tac_toe::game::{Game, Status};
+
+use super::{board, cell, screen};
+use crate::notakto::{self, Notakto};
+use crate::{action_button, play_variant, small_button, Player, TicTacToe};
+
+impl TicTacToe {
+    /// Renders the Notakto boards side by side, with killed boards crossed
+    /// out, and buttons choosing how many boards to play on.
+    pub fn render_notakto(&self, cell_size: Pixels, cx: &mut Context<Self>) -> impl IntoElement {
+        let screen = screen();
+        let Some(game) = &self.notakto else {
+            return screen;
+        };
+        let count = game.boards.len();
+        let small_cell = cell_size / (count as f32 + 0.5);
+
+        // Both players place X, so they go by their turn order instead
+        let name = |player: Player| match player {
+            Player::X => "Player 1",
+            Player::O => "Player 2",
+        };
+        let status = match game.status() {
+            Status::Won(winner) => format!(
+                "{} Killed the Last Board - {} Wins!",
+                name(winner.other()),
+                name(winner)
+            ),
+            Status::Drawn | Status::InProgress => format!("{} to Move", name(game.to_move)),
+        };
+        let legal = game.legal_moves();
+
+        let mut count_buttons: Vec<_> = Vec::new();
+        for boards in 1..=notakto::MAX_BOARDS {
+            count_buttons.push(
+                small_button(
+                    ElementId::Name(format!("notakto-count-{boards}").into()),
+                    ["1 Board", "2 Boards", "3 Boards"][boards - 1],
+                    boards == count,
+                )
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event, _window, _cx| {
+                        this.notakto = Some(Notakto::new(boards, Player::X));
+                    }),
+                ),
+            );
+        }
+
+        let mut boards: Vec<_> = Vec::new();
+        for index in 0..count {
+            let cells = board(0..3, 0..3, |row, col| {
+                cell(
+                    ElementId::Name(format!("notakto-{index}-{row}-{col}").into()),
+                    small_cell,
+                    game.boards[index][row][col],
+                    legal.contains(&(index, row, col)),
+                )
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event, _window, _cx| {
+                        if let Some(game) = this.notakto.as_mut() {
+                            play_variant(
+                                game,
+                                (index, row, col),
+                                this.opponent,
+                                notakto::SEARCH_DEPTH,
+                            );
+                        }
+                    }),
+                )
+            });
+            boards.push(div().relative().child(cells).when(game.dead[index], |el| {
+                el.opacity(0.4).child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .size_full()
+                        .flex()
+                        .justify_center()
+                        .items_center()
+                        .text_size(small_cell * 2.0)
+                        .child("✕"),
+                )
+            }));
+        }
+
+        screen
+            .child(div().flex().gap_2().children(count_buttons))
+            .child(div().text_xl().child(status))
+            .child(div().flex().gap_4().children(boards))
+            .when(game.status() != Status::InProgress, |el| {
+                el.child(
+                    action_button("notakto-new-game-button", "Play Again").on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event, _window, _cx| {
+                            this.notakto = Some(Notakto::new(count, Player::X));
+                        }),
+                    ),
+                )
+            })
+    }
+}
@@ -0,0 +1,110 @@
+//! The Pentago screen.
+
+use gpui::{div, prelude::*, rgb, Context, ElementId, MouseButton, Pixels};
+
+use super::{board, cell, screen};
+use crate::pentago::{Pentago, Phase};
+use crate::{action_button, small_button, Cell, Player, TicTacToe};
+
+impl TicTacToe {
+    /// Renders the Pentago board as four quadrants, each with rotation
+    /// arrows that appear once the mark for the turn has been placed.
+    pub fn render_pentago(&self, cell_size: Pixels, cx: &mut Context<Self>) -> impl IntoElement {
+        let screen = screen();
+        let Some(game) = &self.pentago else {
+            return screen;
+        };
+        let small_cell = cell_size / 2.5;
+        let rotating = game.phase == Phase::Rotate && !game.finished;
+        let placing = game.phase == Phase::Place && !game.finished;
+
+        let status = match (game.finished, game.winner) {
+            (true, Some(player)) => format!("Player {player:?} Wins!"),
+            (true, None) => "It's a Draw!".to_string(),
+            (false, _) if rotating => format!("{:?}: Rotate a Quadrant", game.to_move),
+            (false, _) => format!("{:?}: Place a Mark", game.to_move),
+        };
+
+        let mut quadrant_rows: Vec<_> = Vec::new();
+        for quadrant_row in 0..2 {
+            let mut quadrants: Vec<_> = Vec::new();
+            for quadrant_col in 0..2 {
+                let quadrant = quadrant_row * 2 + quadrant_col;
+                let rows = quadrant_row * 3..quadrant_row * 3 + 3;
+                let cols = quadrant_col * 3..quadrant_col * 3 + 3;
+                let cells = board(rows, cols, |row, col| {
+                    cell(
+                        ElementId::Name(format!("pentago-{row}-{col}").into()),
+                        small_cell,
+                        game.cells[row][col],
+                        placing && game.cells[row][col] == Cell::Empty,
+                    )
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event, _window, _cx| {
+                            if let Some(game) = this.pentago.as_mut() {
+                                game.place(row, col);
+                            }
+                        }),
+                    )
+                });
+
+                let mut arrows: Vec<_> = Vec::new();
+                for (clockwise, label) in [(false, "↺"), (true, "↻")] {
+                    arrows.push(
+                        small_button(
+                            ElementId::Name(
+                                format!("pentago-rotate-{quadrant}-{clockwise}").into(),
+                            ),
+                            label,
+                            false,
+                        )
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(move |this, _event, _window, _cx| {
+                                if let Some(game) = this.pentago.as_mut() {
+                                    game.rotate(quadrant, clockwise);
+                                }
+                            }),
+                        ),
+                    );
+                }
+
+                quadrants.push(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .items_center()
+                        .gap_1()
+                        .p_1()
+                        .rounded_md()
+                        .border_2()
+                        .border_color(if rotating {
+                            rgb(0xffd43b)
+                        } else {
+                            rgb(0x2d2d2d)
+                        })
+                        .child(cells)
+                        .when(rotating, |el| {
+                            el.child(div().flex().gap_2().children(arrows))
+                        }),
+                );
+            }
+            quadrant_rows.push(div().flex().gap_2().children(quadrants));
+        }
+
+        screen
+            .child(div().text_xl().child(status))
+            .child(div().flex().flex_col().gap_2().children(quadrant_rows))
+            .when(game.finished, |el| {
+                el.child(
+                    action_button("pentago-new-game-button", "Play Again").on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, _cx| {
+                            this.pentago = Some(Pentago::new(Player::X));
+                        }),
+                    ),
+                )
+            })
+    }
+}
@@ -0,0 +1,79 @@
+//! The Qubic screen.
+
+use gpui::{div, prelude::*, rgb, Context, ElementId, MouseButton, Pixels};
+use tic_tac_toe::game::{Game, Status};
+
+use super::{board, cell, finished_status, screen};
+use crate::qubic::{self, QubicBoard};
+use crate::{action_button, play_variant, Player, TicTacToe};
+
+impl TicTacToe {
+    /// Renders the Qubic cube as its four layers side by side, top layer
+    /// first, with the winning line highlighted once there is one.
+    pub fn render_qubic(&self, cell_size: Pixels, cx: &mut Context<Self>) -> impl IntoElement {
+        let screen = screen();
+        let Some(game) = &self.qubic else {
+            return screen;
+        };
+        let small_cell = cell_size / 6.0;
+
+        let status =
+            finished_status(game).unwrap_or_else(|| format!("Current Player: {:?}", game.to_move));
+        let legal = game.legal_moves();
+
+        let mut layers: Vec<_> = Vec::new();
+        for layer in 0..qubic::SIZE {
+            let cells = board(0..qubic::SIZE, 0..qubic::SIZE, |row, col| {
+                let winning = game.winning_line.contains(&(layer, row, col));
+                cell(
+                    ElementId::Name(format!("qubic-{layer}-{row}-{col}").into()),
+                    small_cell,
+                    game.cells[layer][row][col],
+                    legal.contains(&(layer, row, col)),
+                )
+                .when(winning, |el| el.border_2().border_color(rgb(0xffd43b)))
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event, _window, _cx| {
+                        if let Some(game) = this.qubic.as_mut() {
+                            play_variant(
+                                game,
+                                (layer, row, col),
+                                this.opponent,
+                                qubic::SEARCH_DEPTH,
+                            );
+                        }
+                    }),
+                )
+            });
+            layers.push(
+                div()
+                    .flex()
+                    .flex_col()
+                    .items_center()
+                    .gap_1()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(0xcccccc))
+                            .child(format!("Layer {}", layer + 1)),
+                    )
+                    .child(cells),
+            );
+        }
+
+        screen
+            .child(div().text_xl().child(status))
+            .child(div().flex().gap_3().children(layers))
+            .when(game.status() != Status::InProgress, |el| {
+                el.child(
+                    action_button("qubic-new-game-button", "Play Again").on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, _cx| {
+                            this.qubic = Some(QubicBoard::new(Player::X));
+                        }),
+                    ),
+                )
+            })
+    }
+}
@@ -0,0 +1,99 @@
+//! The simul screen.
+
+use gpui::{div, prelude::*, rgb, Context, ElementId, MouseButton, Pixels};
+use tic_tac_toe::ai::Difficulty;
+use tic_tac_toe::game::{Game, Status};
+
+use super::{board, cell, screen};
+use crate::simul::Simul;
+use crate::{action_button, Cell, Player, Position, TicTacToe};
+
+impl TicTacToe {
+    /// Renders the simul's boards side by side, each headed by its
+    /// computer's difficulty and how its game stands. Only the board the
+    /// player moves on next is outlined and playable.
+    pub fn render_simul(&self, cell_size: Pixels, cx: &mut Context<Self>) -> impl IntoElement {
+        let screen = screen();
+        let Some(simul) = &self.simul else {
+            return screen;
+        };
+        let small_cell = cell_size / (simul.boards.len() as f32 + 0.5);
+
+        let status = if simul.is_finished() {
+            let (wins, draws, losses) = simul.score();
+            format!("Simul Over - {wins} Won, {draws} Drawn, {losses} Lost")
+        } else {
+            "Your Move".to_string()
+        };
+
+        let mut boards: Vec<_> = Vec::new();
+        for (index, simul_board) in simul.boards.iter().enumerate() {
+            let active = index == simul.active && !simul.is_finished();
+            let opponent = match simul_board.difficulty {
+                Difficulty::Easy => "Easy",
+                Difficulty::Medium => "Medium",
+                Difficulty::Hard => "Hard",
+            };
+            let standing = match simul_board.game.status() {
+                Status::Won(Player::X) => "You Won",
+                Status::Won(Player::O) => "You Lost",
+                Status::Drawn => "Draw",
+                Status::InProgress if active => "Your Move",
+                Status::InProgress => "Waiting",
+            };
+
+            let cells = board(0..3, 0..3, |row, col| {
+                let mark = simul_board.game.board()[row][col];
+                cell(
+                    ElementId::Name(format!("simul-{index}-{row}-{col}").into()),
+                    small_cell,
+                    mark,
+                    active && mark == Cell::Empty,
+                )
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(move |this, _event, _window, _cx| {
+                        let Some(simul) = this.simul.as_mut() else {
+                            return;
+                        };
+                        if simul.active == index {
+                            if let Some(position) = Position::new(row, col) {
+                                simul.play(position);
+                            }
+                        }
+                    }),
+                )
+            });
+            boards.push(
+                div()
+                    .flex()
+                    .flex_col()
+                    .items_center()
+                    .gap_1()
+                    .child(format!("vs {opponent} - {standing}"))
+                    .child(
+                        div()
+                            .p_1()
+                            .border_2()
+                            .border_color(if active { rgb(0xffd43b) } else { rgb(0x303030) })
+                            .child(cells),
+                    ),
+            );
+        }
+
+        let rules = self.rules;
+        screen
+            .child(div().text_xl().child(status))
+            .child(div().flex().gap_4().children(boards))
+            .when(simul.is_finished(), |el| {
+                el.child(
+                    action_button("simul-new-game-button", "Play Again").on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event, _window, _cx| {
+                            this.simul = Some(Simul::new(rules));
+                        }),
+                    ),
+                )
+            })
+    }
+}
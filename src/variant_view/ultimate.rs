@@ -0,0 +1,106 @@
+//! The Ultimate Tic Tac Toe screen.
+
+use gpui::{div, prelude::*, rgb, rgba, Context, ElementId, MouseButton, Pixels};
+use tic_tac_toe::game::{Game, Status};
+
+use super::{board, cell, finished_status, screen};
+use crate::ultimate::{self, SubResult, UltimateBoard};
+use crate::{action_button, play_variant, Player, TicTacToe};
+
+impl TicTacToe {
+    /// Renders the Ultimate Tic Tac Toe board.
+    ///
+    /// Small boards open for the next move are outlined, and decided ones
+    /// are covered by their result.
+    pub fn render_ultimate(&self, cell_size: Pixels, cx: &mut Context<Self>) -> impl IntoElement {
+        let screen = screen();
+        let Some(game) = &self.ultimate else {
+            return screen;
+        };
+        let small_cell = cell_size / 3.5;
+
+        let status = finished_status(game).unwrap_or_else(|| match game.forced {
+            Some(_) => format!("{:?} to Move in the Outlined Board", game.to_move),
+            None => format!("{:?} to Move on Any Open Board", game.to_move),
+        });
+        let legal = game.legal_moves();
+
+        let mut meta_rows: Vec<_> = Vec::new();
+        for meta_row in 0..3 {
+            let mut boards: Vec<_> = Vec::new();
+            for meta_col in 0..3 {
+                let index = meta_row * 3 + meta_col;
+                let open = game.is_board_open(index);
+                let small_board = board(0..3, 0..3, |row, col| {
+                    cell(
+                        ElementId::Name(format!("ultimate-{index}-{row}-{col}").into()),
+                        small_cell,
+                        game.boards[index][row][col],
+                        legal.contains(&(index, row, col)),
+                    )
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event, _window, _cx| {
+                            if let Some(game) = this.ultimate.as_mut() {
+                                play_variant(
+                                    game,
+                                    (index, row, col),
+                                    this.opponent,
+                                    ultimate::SEARCH_DEPTH,
+                                );
+                            }
+                        }),
+                    )
+                });
+
+                // A decided board is covered by its winner's mark, or a dash
+                // for a draw
+                let cover = game.results[index].map(|result| {
+                    let (label, color) = match result {
+                        SubResult::Won(Player::X) => ("X", rgb(0xff6b6b)),
+                        SubResult::Won(Player::O) => ("O", rgb(0x4dabf7)),
+                        SubResult::Drawn => ("-", rgb(0xcccccc)),
+                    };
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .size_full()
+                        .flex()
+                        .justify_center()
+                        .items_center()
+                        .bg(rgba(0x2d2d2dcc))
+                        .text_size(small_cell * 2.0)
+                        .text_color(color)
+                        .child(label)
+                });
+
+                boards.push(
+                    div()
+                        .relative()
+                        .p_1()
+                        .rounded_md()
+                        .border_2()
+                        .border_color(if open { rgb(0xffd43b) } else { rgb(0x2d2d2d) })
+                        .child(small_board)
+                        .children(cover),
+                );
+            }
+            meta_rows.push(div().flex().gap_1().children(boards));
+        }
+
+        screen
+            .child(div().text_xl().child(status))
+            .child(div().flex().flex_col().gap_1().children(meta_rows))
+            .when(game.status() != Status::InProgress, |el| {
+                el.child(
+                    action_button("ultimate-new-game-button", "Play Again").on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, _cx| {
+                            this.ultimate = Some(UltimateBoard::new(Player::X));
+                        }),
+                    ),
+                )
+            })
+    }
+}
@@ -0,0 +1,98 @@
+//! The variants offered on the menu.
+//!
+//! Each variant registers its name, how to set up a game of it, and which
+//! board the window draws for it in a [`VariantRegistry`]. The menu lists
+//! whatever is registered, so a new variant is added by registering it
+//! here rather than by adding a button to the menu.
+
+use crate::connect_k::{self, ConnectK};
+use crate::notakto::Notakto;
+use crate::pentago::Pentago;
+use crate::qubic::QubicBoard;
+use crate::simul::Simul;
+use crate::ultimate::UltimateBoard;
+use crate::{Player, TicTacToe};
+
+/// Which board the window draws.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BoardLayout {
+    /// The 3x3 grid of ordinary play, under any rule options.
+    Grid,
+    /// Nine small boards making up a large one.
+    Ultimate,
+    /// The four layers of a 4x4x4 cube.
+    Qubic,
+    /// One or more boards that both players mark with X.
+    Notakto,
+    /// Four rotating quadrants.
+    Pentago,
+    /// A larger grid needing a longer line to win.
+    ConnectK,
+    /// Side-by-side boards against computers of different strengths.
+    Simul,
+}
+
+/// A variant listed on the menu.
+#[derive(Clone, Copy, Debug)]
+pub struct Variant {
+    /// Name on the variant's menu button.
+    pub name: &'static str,
+    /// Sets up the window to play the variant, once any other variant has
+    /// been left and before the new game starts.
+    pub setup: fn(&mut TicTacToe),
+    /// The board drawn while the variant is played.
+    pub layout: BoardLayout,
+}
+
+/// The variants on the menu, in the order they are listed.
+#[derive(Clone, Debug, Default)]
+pub struct VariantRegistry {
+    variants: Vec<Variant>,
+}
+
+impl VariantRegistry {
+    /// Returns a registry of the variants that come with the game.
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        registry.register(Variant {
+            name: "Ultimate",
+            setup: |game| game.ultimate = Some(UltimateBoard::new(Player::X)),
+            layout: BoardLayout::Ultimate,
+        });
+        registry.register(Variant {
+            name: "Qubic",
+            setup: |game| game.qubic = Some(QubicBoard::new(Player::X)),
+            layout: BoardLayout::Qubic,
+        });
+        registry.register(Variant {
+            name: "Notakto",
+            setup: |game| game.notakto = Some(Notakto::new(1, Player::X)),
+            layout: BoardLayout::Notakto,
+        });
+        registry.register(Variant {
+            name: "Pentago",
+            setup: |game| game.pentago = Some(Pentago::new(Player::X)),
+            layout: BoardLayout::Pentago,
+        });
+        registry.register(Variant {
+            name: "Connect-K",
+            setup: |game| game.connect_k = ConnectK::new(connect_k::SIZES[0].cells, Player::X),
+            layout: BoardLayout::ConnectK,
+        });
+        registry.register(Variant {
+            name: "Simul",
+            setup: |game| game.simul = Some(Simul::new(game.rules)),
+            layout: BoardLayout::Simul,
+        });
+        registry
+    }
+
+    /// Adds `variant` to the end of the menu.
+    pub fn register(&mut self, variant: Variant) {
+        self.variants.push(variant);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Variant> {
+        self.variants.iter()
+    }
+}
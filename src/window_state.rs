@@ -0,0 +1,128 @@
+//! Per-display window placement.
+//!
+//! Remembers the window bounds last used on each display so the game
+//! reopens where it was left, even when the displays have different
+//! sizes or scale factors.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use directories::ProjectDirs;
+use gpui::{point, px, size, App, Bounds, DisplayId, Pixels, Window};
+use serde::{Deserialize, Serialize};
+
+/// Share of the window height given to the event log panel by default.
+const DEFAULT_SPLIT_RATIO: f32 = 0.25;
+
+/// Smallest and largest share of the window the event log panel may take.
+const SPLIT_RATIO_RANGE: (f32, f32) = (0.1, 0.6);
+
+/// Window bounds stored in logical pixels.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct StoredBounds {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+}
+
+impl From<Bounds<Pixels>> for StoredBounds {
+    fn from(bounds: Bounds<Pixels>) -> Self {
+        Self {
+            x: f32::from(bounds.origin.x),
+            y: f32::from(bounds.origin.y),
+            width: f32::from(bounds.size.width),
+            height: f32::from(bounds.size.height),
+        }
+    }
+}
+
+impl From<StoredBounds> for Bounds<Pixels> {
+    fn from(stored: StoredBounds) -> Self {
+        Bounds::new(
+            point(px(stored.x), px(stored.y)),
+            size(px(stored.width), px(stored.height)),
+        )
+    }
+}
+
+/// The remembered window placement across all displays.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct WindowState {
+    /// Last window bounds keyed by display UUID.
+    displays: HashMap<String, StoredBounds>,
+    /// UUID of the display the window was last on.
+    last_display: Option<String>,
+    /// Share of the window height given to the event log panel.
+    split_ratio: Option<f32>,
+}
+
+impl WindowState {
+    /// Loads the saved placement, falling back to an empty state.
+    pub fn load() -> Self {
+        state_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Writes the placement to disk, ignoring failures.
+    pub fn save(&self) {
+        let Some(path) = state_path() else {
+            return;
+        };
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        if let Ok(json) = serde_json::to_string_pretty(self) {
+            let _ = fs::write(path, json);
+        }
+    }
+
+    /// Returns the display and bounds to reopen the window with, if the
+    /// display it was last on is still connected.
+    pub fn restore(&self, cx: &App) -> Option<(DisplayId, Bounds<Pixels>)> {
+        let uuid = self.last_display.as_ref()?;
+        let bounds = self.displays.get(uuid)?;
+        let display = cx
+            .displays()
+            .into_iter()
+            .find(|display| display.uuid().is_ok_and(|id| id.to_string() == *uuid))?;
+        Some((display.id(), (*bounds).into()))
+    }
+
+    /// Returns the share of the window height given to the event log panel.
+    pub fn split_ratio(&self) -> f32 {
+        self.split_ratio.unwrap_or(DEFAULT_SPLIT_RATIO)
+    }
+
+    /// Sets the event log panel's share of the window height, clamped to a
+    /// usable range.
+    pub fn set_split_ratio(&mut self, ratio: f32) {
+        let (min, max) = SPLIT_RATIO_RANGE;
+        self.split_ratio = Some(ratio.clamp(min, max));
+    }
+
+    /// Records the window's current bounds against the display it is on.
+    ///
+    /// Returns true when the window has moved to a different display.
+    pub fn record(&mut self, window: &Window, cx: &App) -> bool {
+        let Some(uuid) = window
+            .display(cx)
+            .and_then(|display| display.uuid().ok())
+            .map(|id| id.to_string())
+        else {
+            return false;
+        };
+        self.displays.insert(uuid.clone(), window.bounds().into());
+        let moved = self.last_display.as_ref() != Some(&uuid);
+        self.last_display = Some(uuid);
+        moved
+    }
+}
+
+/// Location of the window state file in the platform config directory.
+fn state_path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "tic-tac-toe").map(|dirs| dirs.config_dir().join("window.json"))
+}